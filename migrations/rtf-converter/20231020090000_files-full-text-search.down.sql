@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS files_tenant_id_idx;
+DROP INDEX IF EXISTS files_search_vector_idx;
+ALTER TABLE files DROP COLUMN IF EXISTS search_vector;
+ALTER TABLE files DROP COLUMN IF EXISTS extracted_text;
@@ -0,0 +1,11 @@
+-- Description: Plain text extracted from each file, indexed for full-text search
+ALTER TABLE files ADD COLUMN extracted_text TEXT NULL;            -- Plain text of the file, as produced by the converter
+
+-- 'simple' configuration: templates are written in many languages, so words are not stemmed
+ALTER TABLE files ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS (
+    setweight(to_tsvector('simple', coalesce(file_name, '')), 'A') ||
+    setweight(to_tsvector('simple', coalesce(extracted_text, '')), 'B')
+) STORED;
+
+CREATE INDEX files_search_vector_idx ON files USING GIN (search_vector);
+CREATE INDEX files_tenant_id_idx ON files (tenant_id);
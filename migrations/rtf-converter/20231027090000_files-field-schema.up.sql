@@ -0,0 +1,2 @@
+-- Description: JSON Schema of the data each template is rendered with
+ALTER TABLE files ADD COLUMN field_schema JSONB NULL;             -- Generated from the fields of the template at upload
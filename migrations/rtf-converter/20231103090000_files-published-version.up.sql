@@ -0,0 +1,4 @@
+-- Description: Version of each template served when none is requested
+ALTER TABLE files ADD COLUMN published BOOLEAN NOT NULL DEFAULT FALSE;   -- Set on at most one version of a file
+
+CREATE UNIQUE INDEX files_published_idx ON files (id) WHERE published;
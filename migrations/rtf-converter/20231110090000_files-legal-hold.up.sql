@@ -0,0 +1,4 @@
+-- Description: Retention of files past their max_age
+ALTER TABLE files ADD COLUMN legal_hold BOOLEAN NOT NULL DEFAULT FALSE;  -- Keeps the file, expired or not, until lifted
+
+CREATE INDEX files_expiry_idx ON files (max_age) WHERE NOT legal_hold;
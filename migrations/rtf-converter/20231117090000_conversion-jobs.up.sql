@@ -0,0 +1,23 @@
+-- Description: Conversions run asynchronously by the workers, with their input and result
+CREATE TABLE conversion_jobs (
+                       id UUID DEFAULT uuid_generate_v4 (),             -- UUID identifier of the job
+                       status VARCHAR(16) NOT NULL DEFAULT 'queued',    -- queued, running, succeeded, failed or dead
+                       progress REAL NOT NULL DEFAULT 0,                -- Share of the conversion done, from 0 to 1
+                       format VARCHAR(16) NOT NULL,                     -- Output format
+                       charset VARCHAR(64) NULL,                        -- Encoding overriding the document codepage
+                       timeout_ms BIGINT NOT NULL,                      -- Time budget of an attempt
+                       input BYTEA NULL,                                -- Document to convert, cleared once the job is over
+                       result BYTEA NULL,                               -- Converted document
+                       content_type VARCHAR(255) NULL,                  -- Content type of the result
+                       error TEXT NULL,                                 -- Error of the last failed attempt
+                       attempts INTEGER NOT NULL DEFAULT 0,             -- Attempts started
+                       max_attempts INTEGER NOT NULL,                   -- Attempts before the job is dead-lettered
+                       created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),   -- Timestamp when the job was queued
+                       started_at TIMESTAMPTZ NULL,                     -- Timestamp when the first attempt started
+                       attempted_at TIMESTAMPTZ NULL,                   -- Timestamp when the last attempt started
+                       finished_at TIMESTAMPTZ NULL,                    -- Timestamp when the job was over
+                       PRIMARY KEY (id)
+);
+
+CREATE INDEX conversion_jobs_status_idx ON conversion_jobs (status);
+CREATE INDEX conversion_jobs_running_idx ON conversion_jobs (attempted_at) WHERE status = 'running';
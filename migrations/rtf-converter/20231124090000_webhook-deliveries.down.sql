@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS webhook_deliveries CASCADE;
+ALTER TABLE conversion_jobs DROP COLUMN IF EXISTS callback_include_output;
+ALTER TABLE conversion_jobs DROP COLUMN IF EXISTS callback_secret;
+ALTER TABLE conversion_jobs DROP COLUMN IF EXISTS callback_url;
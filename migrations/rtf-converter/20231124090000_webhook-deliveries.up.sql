@@ -0,0 +1,18 @@
+-- Description: Callbacks of the conversion jobs, and their deliveries
+ALTER TABLE conversion_jobs ADD COLUMN callback_url TEXT NULL;                                  -- URL posted when the job is over
+ALTER TABLE conversion_jobs ADD COLUMN callback_secret TEXT NULL;                               -- Key of the HMAC-SHA256 signature of the callbacks
+ALTER TABLE conversion_jobs ADD COLUMN callback_include_output BOOLEAN NOT NULL DEFAULT FALSE;  -- Whether the callbacks embed the result
+
+CREATE TABLE webhook_deliveries (
+                       id UUID DEFAULT uuid_generate_v4 (),             -- UUID identifier of the delivery
+                       job_id UUID NOT NULL REFERENCES conversion_jobs (id) ON DELETE CASCADE,
+                       url TEXT NOT NULL,                               -- URL posted
+                       status VARCHAR(16) NOT NULL DEFAULT 'pending',   -- pending, delivered or failed
+                       response_status INTEGER NULL,                    -- HTTP status of the last attempt
+                       attempts JSONB NOT NULL DEFAULT '[]',            -- HTTP attempts, with their outcome
+                       created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),   -- Timestamp when the delivery was queued
+                       finished_at TIMESTAMPTZ NULL,                    -- Timestamp of the last attempt
+                       PRIMARY KEY (id)
+);
+
+CREATE INDEX webhook_deliveries_job_idx ON webhook_deliveries (job_id, created_at);
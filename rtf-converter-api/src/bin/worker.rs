@@ -0,0 +1,109 @@
+//! rtf-converter-worker: runs the conversion jobs queued by the API servers
+//! and delivers their callbacks, and purges expired files when retention is
+//! enabled.
+
+use anyhow::Result;
+use axum::{routing::get, Router, Server};
+use std::{
+    future::ready,
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
+use tokio::signal::{
+    self,
+    unix::{signal, SignalKind},
+};
+use tracing::info;
+use tracing_subscriber::{prelude::*, EnvFilter};
+use rtf_converter::{
+    infra::db::postgres,
+    jobs::{convert, retention, webhook::WebhookClient},
+    metrics::{process, prom::setup_metrics_recorder},
+    repositories::{file::FileRepoImpl, job::JobRepoImpl},
+    routes::fallback::notfound_404,
+    settings::Settings,
+    tracing_layers::format_layer::LogFmtLayer,
+};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let (stdout_writer, _stdout_guard) = tracing_appender::non_blocking(io::stdout());
+    tracing_subscriber::Registry::default()
+        .with(LogFmtLayer::new(stdout_writer).with_target(true).with_filter(
+            EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| EnvFilter::new("rtf_converter=info,sqlxmq=warn")),
+        ))
+        .init();
+
+    let settings = Settings::load()?;
+    info!(
+        subject = "worker_settings",
+        category = "init",
+        "starting with settings: {:?}",
+        settings,
+    );
+    let recorder_handle = setup_metrics_recorder()?;
+
+    let mq_pool = postgres::mq_db_connect().await;
+    let rtf_pool = Arc::new(postgres::rtf_db_connect().await);
+    let mut conversions = convert::start(
+        &mq_pool,
+        JobRepoImpl::new(rtf_pool.clone()),
+        settings.limits().clone(),
+        WebhookClient::new(settings.webhooks())?,
+        settings.jobs(),
+    )
+    .await?;
+    let purge = if settings.retention().enabled {
+        let repo = FileRepoImpl::new(rtf_pool);
+        Some(retention::start(&mq_pool, repo, settings.retention().clone()).await?)
+    } else {
+        None
+    };
+
+    tokio::task::spawn(process::collect_metrics(
+        settings.monitoring().process_collector_interval,
+    ));
+    let metrics_router = Router::new()
+        .route("/metrics", get(move || ready(recorder_handle.render())))
+        .fallback(notfound_404);
+    let bind_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), settings.server().metrics_port);
+    info!(
+        subject = "worker_start",
+        category = "init",
+        "worker running {} conversions at most, metrics on {}",
+        settings.jobs().concurrency,
+        bind_addr
+    );
+    Server::bind(&bind_addr)
+        .serve(metrics_router.into_make_service())
+        .with_graceful_shutdown(shutdown())
+        .await?;
+
+    // Running jobs finish, or are retried by another worker.
+    conversions.stop().await;
+    if let Some(mut purge) = purge {
+        purge.stop().await;
+    }
+    Ok(())
+}
+
+/// Captures and waits for system signals.
+async fn shutdown() {
+    #[cfg(unix)]
+    let term = async {
+        signal(SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM")
+            .recv()
+            .await
+    };
+
+    #[cfg(not(unix))]
+    let term = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = signal::ctrl_c() => {}
+        _ = term => {}
+    }
+}
@@ -2,17 +2,23 @@
 
 use crate::{
     error::AppError,
-    routes::template::{self, TemplateUploadRequest, TemplateUploadResponse, ReqUuid, ReqHumanDuration},
+    routes::template::{self, TemplateRenderRequest, TemplateUpdateRequest, TemplateUploadRequest, TemplateVersionRequest, TemplateUploadResponse, ReqUuid, ReqHumanDuration},
     routes::health,
-    routes::convert::{self, RequestData}
+    routes::convert::{self, RequestData},
+    routes::analyze::{self, AnalyzeRequest},
+    routes::diff::{self, DiffRequest},
+    routes::jobs::{self, ConvertJobRequest},
+    routes::generate,
+    routes::normalize::{self, NormalizeRequest},
+    routes::sanitize::{self, SanitizeRequest},
 };
 use utoipa::OpenApi;
 
 /// API documentation generator.
 #[derive(OpenApi)]
 #[openapi(
-        paths(health::healthcheck, convert::convert, template::upload),
-        components(schemas(AppError), schemas(TemplateUploadRequest), schemas(TemplateUploadResponse), schemas(RequestData), schemas(ReqUuid), schemas(ReqHumanDuration)),
+        paths(health::healthcheck, convert::convert, analyze::analyze, diff::diff, jobs::create, jobs::status, jobs::result, jobs::deliveries, jobs::replay, generate::text, generate::markdown, generate::html, normalize::normalize, sanitize::sanitize, template::upload, template::list, template::metadata, template::content, template::update, template::delete, template::create_version, template::versions, template::version_metadata, template::version_content, template::render_version, template::publish, template::rollback, template::render, template::fields),
+        components(schemas(AppError), schemas(TemplateUploadRequest), schemas(TemplateUploadResponse), schemas(TemplateRenderRequest), schemas(TemplateUpdateRequest), schemas(TemplateVersionRequest), schemas(RequestData), schemas(DiffRequest), schemas(ConvertJobRequest), schemas(AnalyzeRequest), schemas(NormalizeRequest), schemas(SanitizeRequest), schemas(ReqUuid), schemas(ReqHumanDuration)),
         tags(
             (name = "", description = "rtf-converter service/middleware")
         )
@@ -6,6 +6,7 @@ use axum::{
     Json,
 };
 
+use crate::rtf::error::{Limit, LimitExceeded, TemplateError};
 use serde::{Deserialize, Serialize};
 use tracing::warn;
 use ulid::Ulid;
@@ -89,6 +90,27 @@ impl IntoResponse for AppError {
     }
 }
 
+impl From<LimitExceeded> for AppError {
+    fn from(err: LimitExceeded) -> Self {
+        let status = match err.limit {
+            Limit::InputBytes => StatusCode::PAYLOAD_TOO_LARGE,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, Some(err))
+    }
+}
+
+impl From<TemplateError> for AppError {
+    fn from(err: TemplateError) -> Self {
+        let status = match err {
+            TemplateError::UnknownEngine { .. } => StatusCode::BAD_REQUEST,
+            TemplateError::Syntax(_) | TemplateError::InvalidValue(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            TemplateError::Limit(limit) => return limit.into(),
+        };
+        Self::new(status, Some(err))
+    }
+}
+
 impl From<anyhow::Error> for AppError {
     fn from(err: anyhow::Error) -> Self {
         warn!(
@@ -173,6 +195,19 @@ mod tests {
         assert_eq!(err.status, StatusCode::INTERNAL_SERVER_ERROR);
     }
 
+    #[test]
+    fn test_from_limit_exceeded() {
+        let err: AppError = LimitExceeded::new(Limit::InputBytes, 10).into();
+        assert_eq!(err.status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(
+            err.detail.unwrap(),
+            "document exceeds the max_input_bytes limit of 10".to_string()
+        );
+
+        let err: AppError = LimitExceeded::new(Limit::GroupDepth, 256).into();
+        assert_eq!(err.status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
     #[test]
     fn test_not_found() {
         let id = Ulid::new();
@@ -0,0 +1,88 @@
+//! `If-Match` Extractor for optimistic concurrency on versioned resources.
+
+use async_trait::async_trait;
+use axum::extract::FromRequestParts;
+use http::{header, request::Parts, StatusCode};
+
+use crate::error::AppError;
+
+/// ETag of a resource at `version`: the version number, quoted.
+pub fn etag(version: i32) -> String {
+    format!("\"{version}\"")
+}
+
+/// Version expected by the `If-Match` header of a request.
+///
+/// Holds `None` when the header is absent or `*`, which any existing version
+/// matches. The request is rejected with a `400 Bad Request` when the header
+/// is not `*` or a single strong ETag from [etag].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IfMatch(pub Option<i32>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for IfMatch
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(header::IF_MATCH) else {
+            return Ok(IfMatch(None));
+        };
+        let version = match value.to_str().map(str::trim) {
+            Ok("*") => None,
+            Ok(tag) => Some(
+                tag.strip_prefix('"')
+                    .and_then(|tag| tag.strip_suffix('"'))
+                    .and_then(|tag| tag.parse::<i32>().ok())
+                    .ok_or_else(invalid)?,
+            ),
+            Err(_) => return Err(invalid()),
+        };
+        Ok(IfMatch(version))
+    }
+}
+
+fn invalid() -> AppError {
+    AppError::new(
+        StatusCode::BAD_REQUEST,
+        Some("If-Match must be `*` or the ETag of a version, such as `\"3\"`"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::{get, Router};
+    use http::Request;
+    use hyper::Body;
+    use tower::ServiceExt;
+
+    async fn expected(if_match: Option<&str>) -> (StatusCode, String) {
+        let app = Router::new().route(
+            "/",
+            get(|IfMatch(version): IfMatch| async move { format!("{version:?}") }),
+        );
+        let mut request = Request::builder().uri("/");
+        if let Some(if_match) = if_match {
+            request = request.header("If-Match", if_match);
+        }
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn parse_if_match() {
+        assert_eq!(expected(None).await, (StatusCode::OK, "None".to_string()));
+        assert_eq!(expected(Some("*")).await, (StatusCode::OK, "None".to_string()));
+        assert_eq!(expected(Some(&etag(3))).await, (StatusCode::OK, "Some(3)".to_string()));
+        assert_eq!(expected(Some("W/\"3\"")).await.0, StatusCode::BAD_REQUEST);
+        assert_eq!(expected(Some("3")).await.0, StatusCode::BAD_REQUEST);
+    }
+}
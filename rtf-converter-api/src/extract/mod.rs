@@ -1,3 +1,4 @@
 //! Custom [axum::extract] Extractors.
 
+pub mod if_match;
 pub mod json;
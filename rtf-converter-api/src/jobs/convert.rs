@@ -0,0 +1,377 @@
+//! Asynchronous conversions.
+//!
+//! `POST /jobs/convert` stores the document in a [conversion
+//! job](crate::models::job) and queues its identifier; a worker converts it
+//! and stores the result. A document which does not convert fails the job at
+//! once. Attempts timing out, or interrupted, are retried with an
+//! exponential backoff, and the job is dead-lettered after its last attempt.
+//! The conversion stops at the deadline of its attempt; a sweep dead-letters
+//! the jobs whose worker stopped during their last attempt, which the queue
+//! does not retry. Jobs over post their [callback](super::webhook), when they
+//! have one.
+
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use metrics::{describe_counter, describe_histogram, Unit};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use sqlxmq::{job, CurrentJob, JobRegistry, JobRunnerHandle};
+use tokio::task::JoinHandle;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::models::job::{ConversionJobInput, JobStatus, NewConversionJob};
+use crate::jobs::webhook::{self, WebhookClient};
+use crate::repositories::job::{JobRepo, JobRepoImpl};
+use crate::routes::convert::{convert_tokens, parse_charset, parse_format, Converted};
+use crate::rtf::{error::{Limit, LimitExceeded}, options::ConvertOptions, to_text};
+use crate::settings::{Jobs, Limits};
+
+/// Error of the jobs whose worker stopped during their last attempt.
+const STALLED_ERROR: &str = "the worker stopped during the last attempt";
+
+/// Describe counters and histograms of conversion jobs.
+pub(crate) fn describe() {
+    describe_counter!(
+        "conversion_jobs_total",
+        Unit::Count,
+        "Conversion job attempts, by outcome."
+    );
+    describe_histogram!(
+        "conversion_job_duration_seconds",
+        Unit::Seconds,
+        "Duration of the conversion job attempts."
+    );
+}
+
+/// Payload of a queued conversion.
+#[derive(Debug, Serialize, Deserialize)]
+struct Payload {
+    id: Uuid,
+}
+
+/// Stores `job` and queues its conversion in `queue`, returning its
+/// identifier.
+pub async fn enqueue<R: JobRepo + Sync>(
+    repo: &R,
+    queue: &Pool<Postgres>,
+    job: &NewConversionJob,
+    settings: &Jobs,
+) -> anyhow::Result<Uuid> {
+    let id = repo.add(job).await?;
+    let queued = convert
+        .builder()
+        .set_json(&Payload { id })
+        .map_err(anyhow::Error::from)?
+        .set_retries(job.max_attempts.max(1) as u32 - 1)
+        .set_retry_backoff(settings.retry_backoff())
+        .spawn(queue)
+        .await;
+    if let Err(err) = queued {
+        repo.fail(id, JobStatus::Dead, "the job could not be queued").await?;
+        return Err(err.into());
+    }
+    Ok(id)
+}
+
+/// Job converting a stored document.
+#[job(channel_name = "conversions")]
+pub async fn convert(
+    mut current_job: CurrentJob,
+    repo: JobRepoImpl,
+    limits: Limits,
+) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+    let Payload { id } = current_job
+        .json()?
+        .ok_or("conversion job without payload")?;
+    // Jobs over, or lost, have nothing left to convert.
+    let Some(job) = repo.start(id).await? else {
+        current_job.complete().await?;
+        return Ok(());
+    };
+
+    let started = Instant::now();
+    let status = attempt(&repo, job, limits).await?;
+    record(status, started);
+    if status == JobStatus::Queued {
+        // Retried by the queue after its backoff.
+        return Err(format!("conversion job {id} timed out").into());
+    }
+    // The conversion is over, whether its callback is queued or not.
+    notify(current_job.pool(), &repo, id).await;
+    current_job.complete().await?;
+    Ok(())
+}
+
+/// Runs an attempt at `job` and records its outcome in `repo`, returning the
+/// status of the job after it: queued again when it timed out before its
+/// last attempt.
+async fn attempt<R: JobRepo + Sync>(repo: &R, job: ConversionJobInput, limits: Limits) -> anyhow::Result<JobStatus> {
+    let id = job.id;
+    let deadline = Instant::now() + Duration::from_millis(job.timeout_ms.max(0) as u64);
+    let outcome = run(repo, id, job.input, &job.format, job.charset.as_deref(), limits, deadline).await;
+    let status = match outcome {
+        Ok(converted) => {
+            repo.succeed(id, converted.content_type, &converted.output).await?;
+            JobStatus::Succeeded
+        }
+        Err(err) if !timed_out(&err, deadline) => {
+            repo.fail(id, JobStatus::Failed, &format!("{err:#}")).await?;
+            JobStatus::Failed
+        }
+        Err(_) if job.attempts < job.max_attempts => {
+            let error = format!(
+                "attempt {} timed out after {} ms",
+                job.attempts, job.timeout_ms
+            );
+            repo.fail(id, JobStatus::Queued, &error).await?;
+            JobStatus::Queued
+        }
+        Err(_) => {
+            let error = format!(
+                "all {} attempts timed out after {} ms",
+                job.attempts, job.timeout_ms
+            );
+            warn!(
+                subject = "conversion_job",
+                category = "jobs",
+                job_id = %id,
+                "dead-lettered conversion job: {}",
+                error
+            );
+            repo.fail(id, JobStatus::Dead, &error).await?;
+            JobStatus::Dead
+        }
+    };
+    Ok(status)
+}
+
+/// Whether a conversion failed with `err` because it reached its `deadline`,
+/// rather than a shorter duration limit of the server.
+fn timed_out(err: &anyhow::Error, deadline: Instant) -> bool {
+    err.downcast_ref::<LimitExceeded>()
+        .is_some_and(|exceeded| exceeded.limit == Limit::Duration && Instant::now() >= deadline)
+}
+
+/// Queues the callback of the job `id`, which is over, logging failures.
+async fn notify<R: JobRepo + Sync>(queue: &Pool<Postgres>, repo: &R, id: Uuid) {
+    if let Err(err) = webhook::notify(queue, repo, id).await {
+        warn!(
+            subject = "webhook",
+            category = "jobs",
+            job_id = %id,
+            "failure to queue the callback {:#}",
+            err
+        );
+    }
+}
+
+/// Dead-letters the jobs still running `grace` past the time budget of their
+/// last attempt, and queues their callbacks, returning how many there were.
+pub async fn sweep<R: JobRepo + Sync>(queue: &Pool<Postgres>, repo: &R, grace: Duration) -> anyhow::Result<usize> {
+    let stalled = repo.sweep_stalled(grace, STALLED_ERROR).await?;
+    for &id in &stalled {
+        warn!(
+            subject = "conversion_job",
+            category = "jobs",
+            job_id = %id,
+            "dead-lettered conversion job: {}",
+            STALLED_ERROR
+        );
+        metrics::increment_counter!("conversion_jobs_total", &[("status", JobStatus::Dead.as_str())]);
+        notify(queue, repo, id).await;
+    }
+    Ok(stalled.len())
+}
+
+/// Records the outcome and duration of an attempt.
+fn record(status: JobStatus, started: Instant) {
+    let labels = [("status", status.as_str())];
+    metrics::increment_counter!("conversion_jobs_total", &labels);
+    metrics::histogram!(
+        "conversion_job_duration_seconds",
+        started.elapsed().as_secs_f64(),
+        &labels
+    );
+}
+
+/// Converts `input` by `deadline`, reporting progress once it is parsed.
+async fn run<R: JobRepo + Sync>(
+    repo: &R,
+    id: Uuid,
+    input: Vec<u8>,
+    format: &str,
+    charset: Option<&str>,
+    mut limits: Limits,
+    deadline: Instant,
+) -> anyhow::Result<Converted> {
+    let format = parse_format(Some(format))?;
+    let charset = parse_charset(charset)?;
+    let tokens = tokio::task::spawn_blocking({
+        let limits = limits.clone();
+        move || to_text::tokenize(input.as_slice(), &limits)
+    })
+    .await
+    .map_err(|err| anyhow!("conversion panicked: {err}"))??;
+    repo.set_progress(id, 0.5).await?;
+    // The conversion checks its duration itself: a blocking task cannot be
+    // cancelled from outside, and would hold its worker past the deadline.
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    limits.max_duration_ms = limits.max_duration_ms.min(remaining.as_millis() as u64);
+    let options = ConvertOptions {
+        charset,
+        ..ConvertOptions::new(limits)
+    };
+    tokio::task::spawn_blocking(move || convert_tokens(&tokens, format, &options))
+        .await
+        .map_err(|err| anyhow!("conversion panicked: {err}"))?
+}
+
+/// Runner of the conversion jobs and their callbacks, with the task sweeping
+/// the stalled jobs.
+///
+/// Both stop when the worker is dropped.
+pub struct ConversionWorker {
+    runner: JobRunnerHandle,
+    sweeper: JoinHandle<()>,
+}
+
+impl ConversionWorker {
+    /// Stops sweeping, and waits for the running jobs.
+    pub async fn stop(&mut self) {
+        self.sweeper.abort();
+        self.runner.stop().await;
+    }
+}
+
+impl std::fmt::Debug for ConversionWorker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConversionWorker").finish_non_exhaustive()
+    }
+}
+
+impl Drop for ConversionWorker {
+    fn drop(&mut self) {
+        self.sweeper.abort();
+    }
+}
+
+/// Runs conversion jobs, and the delivery of their callbacks, from the queue
+/// in `pool`, sweeping the stalled jobs every `settings.sweep_interval_ms`,
+/// until the returned worker is dropped.
+///
+/// A stalled job is swept once, by whichever worker gets to it first.
+pub async fn start(
+    pool: &Pool<Postgres>,
+    repo: JobRepoImpl,
+    limits: Limits,
+    webhooks: WebhookClient,
+    settings: &Jobs,
+) -> anyhow::Result<ConversionWorker> {
+    let mut registry = JobRegistry::new(&[convert, webhook::deliver]);
+    registry.set_context(repo.clone());
+    registry.set_context(limits);
+    registry.set_context(webhooks);
+    let concurrency = settings.concurrency.max(1);
+    let runner = registry
+        .runner(pool)
+        .set_channel_names(&["conversions", "webhooks"])
+        .set_concurrency((concurrency / 2).max(1), concurrency)
+        .run()
+        .await?;
+
+    let pool = pool.clone();
+    let interval = settings.sweep_interval();
+    let sweeper = tokio::task::spawn(async move {
+        let mut ticks = tokio::time::interval(interval);
+        loop {
+            ticks.tick().await;
+            if let Err(err) = sweep(&pool, &repo, interval).await {
+                warn!(
+                    subject = "conversion_job",
+                    category = "jobs",
+                    "failure to sweep the stalled conversion jobs {:#}",
+                    err
+                );
+            }
+        }
+    });
+    Ok(ConversionWorker { runner, sweeper })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::job::MockJobRepo;
+    use sqlx::postgres::PgPoolOptions;
+
+    fn job(format: &str, timeout_ms: i64, attempts: i32, input: String) -> ConversionJobInput {
+        ConversionJobInput {
+            id: Uuid::new_v4(),
+            format: format.to_string(),
+            charset: None,
+            timeout_ms,
+            input: input.into_bytes(),
+            attempts,
+            max_attempts: 3,
+        }
+    }
+
+    #[tokio::test]
+    async fn attempt_succeeds() {
+        let mut repo = MockJobRepo::new();
+        repo.expect_set_progress().withf(|_, progress| *progress == 0.5).times(1).returning(|_, _| Ok(()));
+        repo.expect_succeed()
+            .withf(|_, content_type, output| content_type.starts_with("text/plain") && output.starts_with(b"Hello"))
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        repo.expect_fail().never();
+        let status = attempt(&repo, job("text", 10_000, 1, r"{\rtf1 Hello}".to_string()), Limits::default()).await;
+        assert_eq!(status.unwrap(), JobStatus::Succeeded);
+    }
+
+    #[tokio::test]
+    async fn attempt_fails() {
+        let mut repo = MockJobRepo::new();
+        repo.expect_fail()
+            .withf(|_, status, error| *status == JobStatus::Failed && error.contains("unknown format"))
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        repo.expect_succeed().never();
+        let status = attempt(&repo, job("docx", 10_000, 1, r"{\rtf1 Hello}".to_string()), Limits::default()).await;
+        assert_eq!(status.unwrap(), JobStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn attempt_times_out() {
+        // Past its deadline at once, the conversion stops at its first duration check.
+        let input = format!(r"{{\rtf1 {}}}", r"\b x\b0 ".repeat(1_000));
+        for (attempts, expected) in [(1, JobStatus::Queued), (3, JobStatus::Dead)] {
+            let mut repo = MockJobRepo::new();
+            repo.expect_set_progress().returning(|_, _| Ok(()));
+            repo.expect_fail()
+                .withf(move |_, status, error| *status == expected && error.contains("timed out after 0 ms"))
+                .times(1)
+                .returning(|_, _, _| Ok(()));
+            repo.expect_succeed().never();
+            let status = attempt(&repo, job("html", 0, attempts, input.clone()), Limits::default()).await;
+            assert_eq!(status.unwrap(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn sweep_stalled_jobs() {
+        let queue = PgPoolOptions::new().connect_lazy("postgres://localhost/unused").unwrap();
+        let stalled = vec![Uuid::new_v4(), Uuid::new_v4()];
+        let mut repo = MockJobRepo::new();
+        repo.expect_sweep_stalled()
+            .withf(|grace, error| *grace == Duration::from_secs(60) && error == STALLED_ERROR)
+            .times(1)
+            .returning(move |_, _| Ok(stalled.clone()));
+        // Jobs without callback queue no delivery.
+        repo.expect_add_delivery().times(2).returning(|_| Ok(None));
+        assert_eq!(sweep(&queue, &repo, Duration::from_secs(60)).await.unwrap(), 2);
+    }
+}
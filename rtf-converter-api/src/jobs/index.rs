@@ -0,0 +1,92 @@
+//! Indexing of the files stored without extracted text.
+//!
+//! Text is extracted for search when a file is uploaded; files stored before
+//! the `extracted_text` column was added have none, and are indexed in
+//! batches when the server starts. Files which do not convert are indexed
+//! with no text, so that they are not extracted again. Servers starting
+//! together may extract the same files, which only writes the same text twice.
+
+use axum::body::Bytes;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::repositories::file::{FileRepo, FileRepoImpl};
+use crate::routes::template::extract_text;
+use crate::settings::Limits;
+
+/// Files indexed per batch.
+const BATCH_SIZE: i64 = 100;
+
+/// Extracts the text of the files stored without, in batches of
+/// `batch_size`, returning how many were indexed.
+pub async fn backfill<R: FileRepo + Sync>(repo: &R, limits: &Limits, batch_size: i64) -> anyhow::Result<usize> {
+    let mut indexed = 0;
+    loop {
+        let batch = repo.unindexed(batch_size).await?;
+        for file in &batch {
+            let contents = Bytes::from(file.file_binary_content.clone().unwrap_or_default());
+            let text = extract_text(contents, limits.clone()).await.unwrap_or_default();
+            repo.set_extracted_text(file.id, file.version, &text).await?;
+        }
+        indexed += batch.len();
+        if (batch.len() as i64) < batch_size {
+            return Ok(indexed);
+        }
+    }
+}
+
+/// Spawns the indexing of the files stored without extracted text.
+pub fn start(repo: FileRepoImpl, limits: Limits) -> JoinHandle<()> {
+    tokio::task::spawn(async move {
+        match backfill(&repo, &limits, BATCH_SIZE).await {
+            Ok(files) => info!(subject = "index", category = "jobs", files, "indexed files without text"),
+            Err(err) => warn!(subject = "index", category = "jobs", "failure to index files without text {:#}", err),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::file::FileContent;
+    use crate::repositories::file::MockFileRepo;
+    use uuid::Uuid;
+
+    fn file(version: i32, content: &[u8]) -> FileContent {
+        FileContent {
+            id: Uuid::nil(),
+            version,
+            file_name: None,
+            content_type: None,
+            file_binary_content: Some(content.to_vec()),
+            templating_engine: None,
+            templating_engine_version: None,
+            field_schema: None,
+            max_age: None,
+            legal_hold: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn backfill_batches() {
+        let mut batches = vec![
+            vec![file(1, br"{\rtf1 Hello}"), file(2, b"")],
+            vec![file(3, br"{\rtf1 World}")],
+        ]
+        .into_iter();
+        let mut repo = MockFileRepo::new();
+        repo.expect_unindexed()
+            .times(2)
+            .returning(move |_| Ok(batches.next().unwrap()));
+        repo.expect_set_extracted_text()
+            .withf(|_, version, text| match version {
+                1 => text.starts_with("Hello"),
+                2 => text.is_empty(),
+                _ => text.starts_with("World"),
+            })
+            .times(3)
+            .returning(|_, _, _| Ok(()));
+
+        assert_eq!(backfill(&repo, &Limits::default(), 2).await.unwrap(), 3);
+    }
+}
@@ -0,0 +1,6 @@
+//! Background jobs, run on the [sqlxmq] queue.
+
+pub mod convert;
+pub mod index;
+pub mod retention;
+pub mod webhook;
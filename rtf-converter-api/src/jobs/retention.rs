@@ -0,0 +1,158 @@
+//! Purge of the files past their `max_age`.
+//!
+//! Expired files answer `410 Gone` for a grace period, then a purge job,
+//! spawned on an interval, deletes them in batches. Files under legal hold
+//! are kept.
+
+use std::error::Error;
+
+use metrics::{describe_counter, Unit};
+use sqlx::{Pool, Postgres};
+use sqlxmq::{job, CurrentJob, JobRegistry, JobRunnerHandle};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::models::file::PurgedFiles;
+use crate::repositories::file::{FileRepo, FileRepoImpl};
+use crate::settings::Retention;
+
+/// Describe counters of purged files.
+pub(crate) fn describe() {
+    describe_counter!(
+        "retention_purged_files_total",
+        Unit::Count,
+        "Expired file versions deleted."
+    );
+    describe_counter!(
+        "retention_purged_bytes_total",
+        Unit::Bytes,
+        "Content bytes of the expired file versions deleted."
+    );
+}
+
+/// Deletes up to `max_batches` batches of expired files, stopping at the
+/// first batch which is not full.
+pub async fn purge<R: FileRepo + Sync>(repo: &R, retention: &Retention) -> anyhow::Result<PurgedFiles> {
+    let mut total = PurgedFiles::default();
+    for _ in 0..retention.max_batches {
+        let batch = repo
+            .purge_expired(retention.grace_period(), retention.batch_size)
+            .await?;
+        metrics::counter!("retention_purged_files_total", batch.files as u64);
+        metrics::counter!("retention_purged_bytes_total", batch.bytes as u64);
+        total.files += batch.files;
+        total.bytes += batch.bytes;
+        if batch.files < retention.batch_size {
+            break;
+        }
+    }
+    Ok(total)
+}
+
+/// Job purging the expired files.
+#[job(channel_name = "retention")]
+pub async fn purge_expired(
+    mut current_job: CurrentJob,
+    repo: FileRepoImpl,
+    retention: Retention,
+) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+    let purged = purge(&repo, &retention).await?;
+    info!(
+        subject = "retention",
+        category = "jobs",
+        files = purged.files,
+        bytes = purged.bytes,
+        "purged expired files"
+    );
+    current_job.complete().await?;
+    Ok(())
+}
+
+/// Runner of the purge jobs, with the task spawning them.
+///
+/// Both stop when the worker is dropped.
+pub struct RetentionWorker {
+    runner: JobRunnerHandle,
+    spawner: JoinHandle<()>,
+}
+
+impl RetentionWorker {
+    /// Stops spawning purges, and waits for the running one.
+    pub async fn stop(&mut self) {
+        self.spawner.abort();
+        self.runner.stop().await;
+    }
+}
+
+impl std::fmt::Debug for RetentionWorker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetentionWorker").finish_non_exhaustive()
+    }
+}
+
+impl Drop for RetentionWorker {
+    fn drop(&mut self) {
+        self.spawner.abort();
+    }
+}
+
+/// Runs purge jobs from the queue in `pool`, spawning one every
+/// `retention.interval_secs`.
+///
+/// Jobs are ordered, so that purges spawned by several servers run one at a
+/// time.
+pub async fn start(pool: &Pool<Postgres>, repo: FileRepoImpl, retention: Retention) -> anyhow::Result<RetentionWorker> {
+    let mut registry = JobRegistry::new(&[purge_expired]);
+    registry.set_context(repo);
+    registry.set_context(retention.clone());
+    let runner = registry
+        .runner(pool)
+        .set_channel_names(&["retention"])
+        .set_concurrency(1, 1)
+        .run()
+        .await?;
+
+    let pool = pool.clone();
+    let spawner = tokio::task::spawn(async move {
+        let mut interval = tokio::time::interval(retention.interval());
+        loop {
+            interval.tick().await;
+            if let Err(err) = purge_expired.builder().set_ordered(true).spawn(&pool).await {
+                warn!(
+                    subject = "retention",
+                    category = "jobs",
+                    "failure to spawn the purge of expired files {:#}",
+                    err
+                );
+            }
+        }
+    });
+    Ok(RetentionWorker {
+        runner,
+        spawner,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::file::MockFileRepo;
+
+    #[tokio::test]
+    async fn purge_batches() {
+        let retention = Retention {
+            batch_size: 2,
+            ..Retention::default()
+        };
+        let mut batches = [(2, 10), (2, 20), (1, 5)]
+            .map(|(files, bytes)| PurgedFiles { files, bytes })
+            .into_iter();
+        let mut repo = MockFileRepo::new();
+        repo.expect_purge_expired()
+            .times(3)
+            .returning(move |_, _| Ok(batches.next().unwrap()));
+
+        let purged = purge(&repo, &retention).await.unwrap();
+        assert_eq!(purged, PurgedFiles { files: 5, bytes: 35 });
+    }
+}
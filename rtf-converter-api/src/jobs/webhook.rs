@@ -0,0 +1,533 @@
+//! Callbacks of the conversion jobs.
+//!
+//! When a job with a callback URL is over, a delivery is queued which POSTs
+//! the job status, and its result when asked, to the URL. The body is signed
+//! with HMAC-SHA256 over `{timestamp}.{body}`, keyed by the job secret.
+//!
+//! Callbacks only reach public addresses, checked when the job is created
+//! and again on every delivery, which connects to the addresses checked
+//! whatever the host resolves to by then; redirects are not followed. Transient
+//! failures are retried by the client, and every HTTP attempt is recorded on
+//! the delivery, with the kind of its error only. Deliveries can be replayed.
+
+use std::error::Error;
+use std::net::{IpAddr, SocketAddr};
+
+use anyhow::{anyhow, bail, Context};
+use base64::Engine;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use metrics::{describe_counter, Unit};
+use reqwest::{header, redirect, Client};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::policies::ExponentialBackoff;
+use reqwest_tracing::TracingMiddleware;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::{Pool, Postgres};
+use sqlxmq::{job, CurrentJob};
+use std::time::Duration;
+use task_local_extensions::Extensions;
+use tracing::{info, warn};
+use url::{Host, Url};
+use uuid::Uuid;
+
+use crate::middleware::{
+    client::{
+        attempts::{error_kind, Attempt, Attempts, RecordAttempts},
+        metrics::Metrics,
+    },
+    reqwest_retry::RetryTransientMiddleware,
+    reqwest_tracing::ExtendedTrace,
+};
+use crate::models::job::{ConversionJob, JobStatus};
+use crate::repositories::job::{JobRepo, JobRepoImpl};
+use crate::settings::Webhooks;
+
+/// Header of the delivery identifier, the same across retries.
+pub const DELIVERY_HEADER: &str = "x-webhook-delivery";
+/// Header of the Unix time of the signature.
+pub const TIMESTAMP_HEADER: &str = "x-webhook-timestamp";
+/// Header of the signature, as `sha256=<hex>`.
+pub const SIGNATURE_HEADER: &str = "x-webhook-signature";
+
+/// Client name for metrics and retries.
+const CLIENT_NAME: &str = "webhook";
+
+/// Describe counters of callback deliveries.
+pub(crate) fn describe() {
+    describe_counter!(
+        "webhook_deliveries_total",
+        Unit::Count,
+        "Callback deliveries, by result."
+    );
+}
+
+/// Signature of a callback `body` sent at `timestamp`, with the `secret` of
+/// its job.
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Whether callbacks may reach `ip`: not a loopback, private, link-local,
+/// shared (carrier-grade NAT), benchmarking, "this network", multicast or
+/// broadcast address, nor an IPv6 address translating to IPv4 (NAT64, 6to4,
+/// IPv4-compatible) other than IPv4-mapped public ones.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            let this_network = a == 0;
+            let shared = a == 100 && b & 0xc0 == 64;
+            let benchmarking = a == 198 && b & 0xfe == 18;
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_multicast()
+                || ip.is_broadcast()
+                || this_network
+                || shared
+                || benchmarking)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(ip));
+            }
+            let segments = ip.segments();
+            // Unspecified and loopback addresses included.
+            let ipv4_compatible = segments[..6] == [0; 6];
+            let nat64 = segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0];
+            let six_to_four = segments[0] == 0x2002;
+            !(ipv4_compatible
+                || nat64
+                || six_to_four
+                || ip.is_unique_local()
+                || ip.is_unicast_link_local()
+                || ip.is_multicast())
+        }
+    }
+}
+
+/// Addresses of the callback host `host`, failing unless they are all public
+/// or `allow_private`.
+async fn resolve(host: &str, port: u16, allow_private: bool) -> anyhow::Result<Vec<SocketAddr>> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|_| anyhow!("unknown callback host"))?
+        .collect();
+    if addrs.is_empty() {
+        bail!("unknown callback host");
+    }
+    if !allow_private && !addrs.iter().all(|addr| is_public(addr.ip())) {
+        bail!("callback host not allowed");
+    }
+    Ok(addrs)
+}
+
+/// Checks that the callback `url` only reaches public addresses, unless
+/// `allow_private`, returning the addresses its host resolves to, if it is
+/// not an address itself.
+pub async fn check_callback(url: &Url, allow_private: bool) -> anyhow::Result<Vec<SocketAddr>> {
+    let ip = match url.host() {
+        None => bail!("callback URL without host"),
+        Some(Host::Domain(domain)) => {
+            let port = url.port_or_known_default().unwrap_or(80);
+            return resolve(domain, port, allow_private).await;
+        }
+        Some(Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(Host::Ipv6(ip)) => IpAddr::V6(ip),
+    };
+    if !allow_private && !is_public(ip) {
+        bail!("callback host not allowed");
+    }
+    Ok(Vec::new())
+}
+
+/// HTTP client of the callbacks.
+#[derive(Clone, Debug)]
+pub struct WebhookClient {
+    settings: Webhooks,
+}
+
+impl WebhookClient {
+    /// Checks the webhooks settings by building a client.
+    pub fn new(settings: &Webhooks) -> anyhow::Result<Self> {
+        let client = Self {
+            settings: settings.clone(),
+        };
+        client.pinned(None, &[])?;
+        Ok(client)
+    }
+
+    /// Client connecting to `addrs` for the host `domain`, rather than to
+    /// the addresses it resolves to.
+    fn pinned(&self, domain: Option<&str>, addrs: &[SocketAddr]) -> anyhow::Result<ClientWithMiddleware> {
+        let http_client = &self.settings.http_client;
+        let retry_policy = ExponentialBackoff::builder()
+            .retry_bounds(
+                Duration::from_millis(http_client.retry_options.bounds_low_ms),
+                Duration::from_millis(http_client.retry_options.bounds_high_ms),
+            )
+            .build_with_max_retries(http_client.retry_options.count.into());
+
+        let mut reqwest_client = Client::builder()
+            .pool_idle_timeout(http_client.pool_idle_timeout())
+            .timeout(Duration::from_millis(http_client.timeout_ms))
+            // A redirect could lead anywhere, private addresses included.
+            .redirect(redirect::Policy::none());
+        if let (Some(domain), false) = (domain, addrs.is_empty()) {
+            reqwest_client = reqwest_client.resolve_to_addrs(domain, addrs);
+        }
+        let reqwest_client = reqwest_client.build()?;
+
+        Ok(ClientBuilder::new(reqwest_client)
+                .with(TracingMiddleware::<ExtendedTrace>::new())
+                .with(RetryTransientMiddleware::new_with_policy(
+                    retry_policy,
+                    CLIENT_NAME.to_string(),
+                ))
+                .with(Metrics {
+                    name: CLIENT_NAME.to_string(),
+                })
+            .with(RecordAttempts)
+            .build())
+    }
+}
+
+/// Body of a callback.
+#[derive(Debug, Serialize)]
+struct Callback<'a> {
+    /// `conversion_job.` followed by the status of the job.
+    event: String,
+    delivery_id: Uuid,
+    job: &'a ConversionJob,
+    /// Result, base64-encoded, when asked and within `max_output_bytes`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<String>,
+}
+
+/// Payload of a queued delivery.
+#[derive(Debug, Serialize, Deserialize)]
+struct Payload {
+    id: Uuid,
+}
+
+/// Queues a delivery of the callback of the job `job_id`, returning its
+/// identifier, or `None` when the job has no callback.
+pub async fn notify<R: JobRepo + Sync>(
+    queue: &Pool<Postgres>,
+    repo: &R,
+    job_id: Uuid,
+) -> anyhow::Result<Option<Uuid>> {
+    let Some(id) = repo.add_delivery(job_id).await? else {
+        return Ok(None);
+    };
+    let queued = deliver
+        .builder()
+        .set_json(&Payload { id })
+        .map_err(anyhow::Error::from)?
+        .spawn(queue)
+        .await;
+    if let Err(err) = queued {
+        let attempt = Attempt {
+            attempted_at: Utc::now(),
+            duration_ms: 0,
+            status: None,
+            error: Some("the delivery could not be queued".to_string()),
+        };
+        repo.record_delivery(id, false, &[attempt]).await?;
+        return Err(err.into());
+    }
+    Ok(Some(id))
+}
+
+/// Posts the callback of the delivery `delivery_id` and records its
+/// attempts, returning whether it was delivered.
+pub async fn post_callback<R: JobRepo + Sync>(
+    repo: &R,
+    client: &WebhookClient,
+    delivery_id: Uuid,
+) -> anyhow::Result<bool> {
+    let target = repo
+        .find_webhook(delivery_id)
+        .await?
+        .ok_or_else(|| anyhow!("webhook delivery {delivery_id} not found"))?;
+    let job = repo
+        .find(target.job_id)
+        .await?
+        .ok_or_else(|| anyhow!("conversion job {} not found", target.job_id))?;
+
+    let output = if target.include_output && job.status == JobStatus::Succeeded.as_str() {
+        repo.find_result(job.id)
+            .await?
+            .and_then(|result| result.result)
+            .filter(|result| result.len() <= client.settings.max_output_bytes)
+            .map(|result| base64::engine::general_purpose::STANDARD.encode(result))
+    } else {
+        None
+    };
+    let body = serde_json::to_vec(&Callback {
+        event: format!("conversion_job.{}", job.status),
+        delivery_id,
+        job: &job,
+        output,
+    })?;
+
+    // The host may resolve elsewhere since the job was created: the addresses
+    // checked now are the ones connected to.
+    let url = Url::parse(&target.url).context("invalid callback URL")?;
+    let addrs = match check_callback(&url, client.settings.allow_private_hosts).await {
+        Ok(addrs) => addrs,
+        Err(err) => {
+            let attempt = Attempt {
+                attempted_at: Utc::now(),
+                duration_ms: 0,
+                status: None,
+                error: Some(err.to_string()),
+            };
+            repo.record_delivery(delivery_id, false, &[attempt]).await?;
+            metrics::increment_counter!("webhook_deliveries_total", &[("result", "failed")]);
+            return Ok(false);
+        }
+    };
+    let http = client.pinned(url.domain(), &addrs)?;
+
+    let timestamp = Utc::now().timestamp();
+    let mut request = http
+        .post(url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(DELIVERY_HEADER, delivery_id.to_string())
+        .header(TIMESTAMP_HEADER, timestamp.to_string());
+    if let Some(secret) = &target.secret {
+        request = request.header(SIGNATURE_HEADER, sign(secret, timestamp, &body));
+    }
+    let request = request.body(body).build().context("invalid callback request")?;
+
+    let mut extensions = Extensions::new();
+    extensions.insert(Attempts::default());
+    let started_at = Utc::now();
+    let result = http
+        .execute_with_extensions(request, &mut extensions)
+        .await;
+    let Attempts(mut attempts) = extensions.remove::<Attempts>().unwrap_or_default();
+    // Failures before any attempt, such as a request which cannot be retried.
+    if let (true, Err(err)) = (attempts.is_empty(), &result) {
+        attempts.push(Attempt {
+            attempted_at: started_at,
+            duration_ms: 0,
+            status: None,
+            error: Some(error_kind(err).to_string()),
+        });
+    }
+
+    let delivered = matches!(&result, Ok(response) if response.status().is_success());
+    repo.record_delivery(delivery_id, delivered, &attempts).await?;
+    let labels = [("result", if delivered { "delivered" } else { "failed" })];
+    metrics::increment_counter!("webhook_deliveries_total", &labels);
+    Ok(delivered)
+}
+
+/// Job delivering a callback.
+#[job(channel_name = "webhooks")]
+pub async fn deliver(
+    mut current_job: CurrentJob,
+    repo: JobRepoImpl,
+    client: WebhookClient,
+) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+    let Payload { id } = current_job
+        .json()?
+        .ok_or("webhook delivery without payload")?;
+    if post_callback(&repo, &client, id).await? {
+        info!(
+            subject = "webhook",
+            category = "jobs",
+            delivery_id = %id,
+            "delivered callback"
+        );
+    } else {
+        warn!(
+            subject = "webhook",
+            category = "jobs",
+            delivery_id = %id,
+            "failure to deliver callback"
+        );
+    }
+    current_job.complete().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::job::MockJobRepo;
+    use crate::settings::{HttpClient, HttpClientRetryOptions};
+    use crate::models::job::WebhookTarget;
+    use std::sync::{Arc, Mutex};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// Delivered flag and attempts of the recorded deliveries.
+    type Recorded = Arc<Mutex<Vec<(bool, Vec<Attempt>)>>>;
+
+    fn client(allow_private_hosts: bool) -> WebhookClient {
+        WebhookClient::new(&Webhooks {
+            http_client: HttpClient {
+                pool_idle_timeout_ms: None,
+                retry_options: HttpClientRetryOptions {
+                    count: 2,
+                    bounds_low_ms: 1,
+                    bounds_high_ms: 10,
+                },
+                timeout_ms: 2_000,
+            },
+            allow_private_hosts,
+            ..Webhooks::default()
+        })
+        .unwrap()
+    }
+
+    fn repo(url: String, recorded: &Recorded) -> MockJobRepo {
+        let mut repo = MockJobRepo::new();
+        repo.expect_find_webhook().returning(move |id| {
+            Ok(Some(WebhookTarget {
+                id,
+                job_id: Uuid::nil(),
+                url: url.clone(),
+                secret: Some("secret".to_string()),
+                include_output: false,
+            }))
+        });
+        repo.expect_find().returning(|id| {
+            Ok(Some(ConversionJob {
+                id,
+                status: JobStatus::Succeeded.as_str().to_string(),
+                progress: 1.0,
+                format: "text".to_string(),
+                content_type: Some("text/plain; charset=utf-8".to_string()),
+                error: None,
+                attempts: 1,
+                max_attempts: 3,
+                callback_url: None,
+                created_at: Utc::now(),
+                started_at: None,
+                finished_at: None,
+            }))
+        });
+        let recorded = recorded.clone();
+        repo.expect_record_delivery().times(1).returning(move |_, delivered, attempts| {
+            recorded.lock().unwrap().push((delivered, attempts.to_vec()));
+            Ok(())
+        });
+        repo
+    }
+
+    /// Delivers a callback to `url`, returning whether it was delivered and
+    /// the status, or error, of its attempts.
+    async fn deliver_to(url: String, client: &WebhookClient) -> (bool, Vec<Result<u16, String>>) {
+        let recorded = Recorded::default();
+        let delivered = post_callback(&repo(url, &recorded), client, Uuid::new_v4()).await.unwrap();
+        let (recorded_delivered, attempts) = recorded.lock().unwrap().pop().unwrap();
+        assert_eq!(delivered, recorded_delivered);
+        let outcomes = attempts
+            .into_iter()
+            .map(|attempt| attempt.status.ok_or_else(|| attempt.error.unwrap_or_default()))
+            .collect();
+        (delivered, outcomes)
+    }
+
+    #[tokio::test]
+    async fn deliver_signed_callback() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/hook")).respond_with(ResponseTemplate::new(204)).mount(&server).await;
+
+        let (delivered, attempts) = deliver_to(format!("{}/hook", server.uri()), &client(true)).await;
+        assert!(delivered);
+        assert_eq!(attempts, [Ok(204)]);
+
+        let requests = server.received_requests().await.unwrap();
+        let header = |name: &str| {
+            let name: wiremock::http::HeaderName = name.parse().unwrap();
+            requests[0].headers.get(&name).unwrap().last().as_str().to_string()
+        };
+        let timestamp: i64 = header(TIMESTAMP_HEADER).parse().unwrap();
+        assert_eq!(header(SIGNATURE_HEADER), sign("secret", timestamp, &requests[0].body));
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["event"], "conversion_job.succeeded");
+        assert_eq!(body["delivery_id"].as_str(), Some(header(DELIVERY_HEADER).as_str()));
+    }
+
+    #[tokio::test]
+    async fn retry_transient_failures() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).respond_with(ResponseTemplate::new(503)).up_to_n_times(1).mount(&server).await;
+        Mock::given(method("POST")).respond_with(ResponseTemplate::new(200)).mount(&server).await;
+        let (delivered, attempts) = deliver_to(server.uri(), &client(true)).await;
+        assert!(delivered);
+        assert_eq!(attempts, [Ok(503), Ok(200)]);
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).respond_with(ResponseTemplate::new(500)).mount(&server).await;
+        let (delivered, attempts) = deliver_to(server.uri(), &client(true)).await;
+        assert!(!delivered);
+        assert_eq!(attempts, [Ok(500), Ok(500), Ok(500)]);
+    }
+
+    #[tokio::test]
+    async fn record_attempts_without_details() {
+        // Redirects are not followed.
+        let server = MockServer::start().await;
+        let elsewhere = format!("{}/elsewhere", server.uri());
+        Mock::given(path("/hook"))
+            .respond_with(ResponseTemplate::new(302).insert_header("location", elsewhere.as_str()))
+            .mount(&server)
+            .await;
+        let (delivered, attempts) = deliver_to(format!("{}/hook", server.uri()), &client(true)).await;
+        assert!(!delivered);
+        assert_eq!(attempts, [Ok(302)]);
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+
+        // Connection errors are recorded by kind, without the address.
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let (delivered, attempts) = deliver_to(format!("http://{closed}/"), &client(true)).await;
+        assert!(!delivered);
+        assert_eq!(attempts, vec![Err("connection failed".to_string()); 3]);
+
+        // Private hosts are not even attempted, whether given by name or address.
+        let port = server.address().port();
+        for url in [format!("{}/hook", server.uri()), format!("http://localhost:{port}/hook")] {
+            let (delivered, attempts) = deliver_to(url, &client(false)).await;
+            assert!(!delivered);
+            assert_eq!(attempts, [Err("callback host not allowed".to_string())]);
+        }
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn public_addresses() {
+        for ip in ["93.184.216.34", "100.128.0.1", "198.20.0.1", "2606:2800:220:1:248:1893:25c8:1946", "::ffff:93.184.216.34"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in ["127.0.0.1", "10.0.0.1", "172.16.0.1", "192.168.1.1", "169.254.169.254", "0.0.0.0", "0.1.2.3",
+                   "100.64.0.1", "100.127.255.254", "198.18.0.1", "198.19.255.254", "224.0.0.1", "239.255.255.250",
+                   "255.255.255.255", "::1", "::", "fd00::1", "fe80::1", "ff02::1", "::ffff:10.0.0.1",
+                   "64:ff9b::a00:1", "64:ff9b::5db8:d822", "2002:a00:1::", "2002:5db8:d822::1", "::10.0.0.1",
+                   "::93.184.216.34"] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[test]
+    fn sign_timestamped_body() {
+        // HMAC-SHA256 of "1700000000.{}" keyed by "secret".
+        let expected = "sha256=b8569b78799ff9e3cbff0fc2d63a33a2b57f3282abd07c37ae5e8e7d79a5f163";
+
+        assert_eq!(sign("secret", 1_700_000_000, b"{}"), expected);
+        assert_ne!(sign("other", 1_700_000_000, b"{}"), expected);
+        assert_ne!(sign("secret", 1_700_000_001, b"{}"), expected);
+    }
+}
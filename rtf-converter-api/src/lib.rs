@@ -19,6 +19,7 @@ pub mod rtf;
 pub mod repositories;
 pub mod models;
 pub mod infra;
+pub mod jobs;
 
 
 /// Test utilities.
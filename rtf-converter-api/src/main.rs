@@ -32,6 +32,7 @@ use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 use rtf_converter::{
     docs::ApiDoc,
+    jobs::{convert, index, retention, webhook::WebhookClient},
     repositories::create_repositories,
     metrics::{process, prom::setup_metrics_recorder},
     middleware::{self, request_ulid::MakeRequestUlid, runtime},
@@ -66,6 +67,23 @@ async fn main() -> Result<()> {
     let env = settings.environment();
     let recorder_handle = setup_metrics_recorder()?;
 
+    // Shared by the routes and the workers.
+    let repos = Arc::new(create_repositories().await);
+
+    // Kept until shutdown: dropping the handles stops the workers.
+    let mut _retention_worker = None;
+    let mut _conversion_worker = None;
+    index::start(repos.file.clone(), settings.limits().clone());
+    if settings.retention().enabled {
+        let repo = repos.file.clone();
+        _retention_worker = Some(retention::start(&repos.queue, repo, settings.retention().clone()).await?);
+    }
+    if settings.jobs().run_in_server {
+        let repo = repos.job.clone();
+        let webhooks = WebhookClient::new(settings.webhooks())?;
+        _conversion_worker = Some(convert::start(&repos.queue, repo, settings.limits().clone(), webhooks, settings.jobs()).await?);
+    }
+
     let app_metrics = async {
         let metrics_router = Router::new()
             .route("/metrics", get(move || ready(recorder_handle.render())))
@@ -83,9 +101,12 @@ async fn main() -> Result<()> {
 
     let app = async {
         let req_id = HeaderName::from_static(REQUEST_ID);
-        let router = router::setup_app_router()
+        let router = router::setup_app_router(repos.clone())
             .await.route_layer(axum::middleware::from_fn(middleware::metrics::track))
             .layer(Extension(env))
+            .layer(Extension(settings.limits().clone()))
+            .layer(Extension(settings.jobs().clone()))
+            .layer(Extension(settings.webhooks().clone()))
             // Include trace context as header into the response.
             .layer(OtelInResponseLayer::default())
             // Opentelemetry tracing middleware.
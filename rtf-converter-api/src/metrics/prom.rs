@@ -1,6 +1,9 @@
 //! Metrics Prometheus recorder.
 
-use crate::metrics::process;
+use crate::{
+    jobs::{convert, retention, webhook},
+    metrics::process,
+};
 
 use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
 
@@ -18,6 +21,9 @@ pub fn setup_metrics_recorder() -> anyhow::Result<PrometheusHandle> {
         .install_recorder()?;
 
     process::describe();
+    convert::describe();
+    retention::describe();
+    webhook::describe();
 
     Ok(builder)
 }
@@ -0,0 +1,76 @@
+//! Middleware recording each attempt of a client [reqwest::Request].
+//!
+//! Placed after [RetryTransientMiddleware], it sees every retry. Attempts are
+//! only recorded for requests sent with an [Attempts] extension.
+//!
+//! [RetryTransientMiddleware]: crate::middleware::reqwest_retry::RetryTransientMiddleware
+
+use chrono::{DateTime, Utc};
+use reqwest_middleware::Middleware as ReqwestMiddleware;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use task_local_extensions::Extensions;
+
+/// Outcome of one attempt of a request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attempt {
+    /// Start of the attempt.
+    pub attempted_at: DateTime<Utc>,
+    /// Duration of the attempt in milliseconds.
+    pub duration_ms: u64,
+    /// Status of the response, when one was received.
+    pub status: Option<u16>,
+    /// Kind of error of the attempt, when no response was received, without
+    /// the details of the upstream host.
+    pub error: Option<String>,
+}
+
+/// Kind of a request error, as recorded: its message would tell about the
+/// network of the upstream host, such as which ports are closed.
+pub fn error_kind(err: &reqwest_middleware::Error) -> &'static str {
+    match err {
+        reqwest_middleware::Error::Reqwest(err) if err.is_timeout() => "timed out",
+        reqwest_middleware::Error::Reqwest(err) if err.is_connect() => "connection failed",
+        reqwest_middleware::Error::Reqwest(err) if err.is_redirect() => "redirected",
+        reqwest_middleware::Error::Reqwest(err) if err.is_builder() => "invalid request",
+        _ => "request failed",
+    }
+}
+
+/// Attempts of a request, in order.
+#[derive(Clone, Debug, Default)]
+pub struct Attempts(pub Vec<Attempt>);
+
+/// Middleware recording attempts into the [Attempts] extension.
+#[derive(Debug)]
+pub struct RecordAttempts;
+
+#[async_trait::async_trait]
+impl ReqwestMiddleware for RecordAttempts {
+    async fn handle(
+        &self,
+        request: reqwest::Request,
+        extensions: &mut Extensions,
+        next: reqwest_middleware::Next<'_>,
+    ) -> Result<reqwest::Response, reqwest_middleware::Error> {
+        let attempted_at = Utc::now();
+        let now = Instant::now();
+
+        let result = next.run(request, extensions).await;
+
+        if let Some(Attempts(attempts)) = extensions.get_mut::<Attempts>() {
+            let (status, error) = match &result {
+                Ok(response) => (Some(response.status().as_u16()), None),
+                Err(err) => (None, Some(error_kind(err).to_string())),
+            };
+            attempts.push(Attempt {
+                attempted_at,
+                duration_ms: now.elapsed().as_millis() as u64,
+                status,
+                error,
+            });
+        }
+
+        result
+    }
+}
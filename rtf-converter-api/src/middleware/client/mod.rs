@@ -1,3 +1,4 @@
 //! Middleware for calls to outside client APIs.
 
+pub mod attempts;
 pub mod metrics;
@@ -33,12 +33,16 @@ mod test {
     use tower::{ServiceBuilder, ServiceExt};
     use tower_http::catch_panic::CatchPanicLayer;
 
+    async fn panics() -> &'static str {
+        panic!("hi")
+    }
+
     #[tokio::test]
     async fn catch_panic_error() {
         let middleware = ServiceBuilder::new().layer(CatchPanicLayer::custom(catch_panic));
 
         let app = Router::new()
-            .route("/", get(|| async { panic!("hi") }))
+            .route("/", get(panics))
             .layer(middleware);
 
         let res = app
@@ -22,11 +22,97 @@ pub struct NewFile {
 	pub max_age: Option<chrono::DateTime<chrono::Utc>>,
 	pub templating_engine: Option<String>,
 	pub templating_engine_version: Option<i32>,
+	pub extracted_text: Option<String>,
+	pub field_schema: Option<serde_json::Value>,
 }
 
-#[derive(FromRow, Type, Serialize, Deserialize, Debug)]
+/// Filters and page of a file listing.
+#[derive(Serialize, Deserialize, Debug, Validate)]
 pub struct FileConditions {
+	/// Part of the file name, matched case-insensitively.
+	#[validate(length(min = 1, max = 255))]
+	pub file_name: Option<String>,
+	/// Tenant owning the files; files without a tenant when `None`.
+	pub tenant_id: Option<Uuid>,
+	pub owner_id: Option<Uuid>,
+	pub templating_engine: Option<String>,
+	#[validate(range(min = 1, max = 100))]
+	pub limit: i64,
+	#[validate(range(min = 0))]
+	pub offset: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct FileSearch {
+	#[validate(length(min = 1, max = 1000))]
+	pub query: String,
+	/// Filters and page of the matching files.
+	#[validate]
+	pub conditions: FileConditions,
+}
+
+/// Metadata changes of a file, applied to all its versions; `None` keeps the
+/// current value.
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct FileUpdate {
+	#[validate(length(min = 1, max = 255))]
+	pub file_name: Option<String>,
+	pub owner_id: Option<Uuid>,
+	pub max_age: Option<chrono::DateTime<chrono::Utc>>,
+	pub legal_hold: Option<bool>,
+}
+
+#[derive(FromRow, Serialize, Deserialize, Debug)]
+pub struct FileSearchHit {
+	pub id: Uuid,
+	pub version: i32,
+	pub file_name: Option<String>,
+	pub rank: f32,
+	/// Fragments of the text, escaped as HTML, with the matches in `<mark>` tags.
+	pub snippet: String,
+}
+
+/// Version of a stored file, with its content.
+#[derive(FromRow, Serialize, Deserialize, Debug)]
+pub struct FileContent {
+	pub id: Uuid,
+	pub version: i32,
 	pub file_name: Option<String>,
+	pub content_type: Option<String>,
+	pub file_binary_content: Option<Vec<u8>>,
+	pub templating_engine: Option<String>,
+	pub templating_engine_version: Option<String>,
+	pub field_schema: Option<serde_json::Value>,
+	pub max_age: Option<chrono::DateTime<chrono::Utc>>,
+	pub legal_hold: bool,
+}
+
+/// Version of a file to read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionSelector {
+	/// The latest version.
+	Latest,
+	/// The published version, or the latest when none is published.
+	Published,
+	/// The given version.
+	Number(i32),
+}
+
+/// Outcome of a write guarded by the expected latest version of a file.
+#[derive(Debug, PartialEq, Eq)]
+pub enum VersionedWrite<T> {
+	/// The write was made.
+	Done(T),
+	/// The file, or the version written from, does not exist.
+	NotFound,
+	/// The latest version of the file is not the expected one.
+	VersionMismatch {
+		latest: i32,
+	},
+	/// A concurrent write of the file got there first.
+	Conflict,
+	/// The file is under legal hold.
+	Held,
 }
 
 #[derive(FromRow, Type, Serialize, Deserialize, Debug, PartialEq, Eq, Validate)]
@@ -36,21 +122,40 @@ pub struct FileIdentifier {
 	pub version: i32,
 }
 
+/// Metadata of a version of a stored file, without its content.
 #[serde_as]
 #[derive(FromRow, Serialize, Deserialize, Validate, Debug)]
 pub struct File {
+	#[sqlx(flatten)]
+	#[serde(flatten)]
 	pub id:  FileIdentifier,
-	pub tenant_id: Uuid,
+	pub tenant_id: Option<Uuid>,
 	pub owner_id: Option<Uuid>,
-	pub file_binary_content: Vec<u8>,
 	#[validate(length(min = 1, max = 255))]
-	pub content_type: String,
+	pub content_type: Option<String>,
 	#[validate(length(min = 1, max = 255))]
-	pub file_name: String,
+	pub file_name: Option<String>,
 	#[validate(range(min = 0))]
-	pub file_size: i64,
+	pub file_size: Option<i64>,
+	#[sqlx(rename = "created_at")]
 	pub insertion_date: Option<chrono::DateTime<chrono::Utc>>,
 	pub max_age: Option<chrono::DateTime<chrono::Utc>>,
 	pub templating_engine: Option<String>,
 	pub templating_engine_version: Option<String>,
+	/// Whether this version is served when none is requested.
+	pub published: bool,
+	/// Whether the file is kept past its `max_age`.
+	pub legal_hold: bool,
+}
+
+/// Files and bytes deleted by a purge of expired files.
+#[derive(FromRow, Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PurgedFiles {
+	pub files: i64,
+	pub bytes: i64,
+}
+
+/// Whether a file kept until `max_age` has expired.
+pub fn is_expired(max_age: Option<chrono::DateTime<chrono::Utc>>, legal_hold: bool) -> bool {
+	!legal_hold && max_age.is_some_and(|max_age| max_age <= chrono::Utc::now())
 }
\ No newline at end of file
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, FromRow};
+use uuid::Uuid;
+
+use crate::middleware::client::attempts::Attempt;
+
+/// State of a conversion job.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+	/// Waiting for a worker, first or again after a failed attempt.
+	Queued,
+	/// Converted by a worker.
+	Running,
+	/// Converted; the result can be fetched.
+	Succeeded,
+	/// The document does not convert.
+	Failed,
+	/// Every attempt failed or timed out.
+	Dead,
+}
+
+impl JobStatus {
+	/// Name of the status, as stored.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			JobStatus::Queued => "queued",
+			JobStatus::Running => "running",
+			JobStatus::Succeeded => "succeeded",
+			JobStatus::Failed => "failed",
+			JobStatus::Dead => "dead",
+		}
+	}
+
+	/// Whether the job is over.
+	pub fn is_final(&self) -> bool {
+		matches!(self, JobStatus::Succeeded | JobStatus::Failed | JobStatus::Dead)
+	}
+}
+
+/// Conversion job to queue.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NewConversionJob {
+	/// Output format, as accepted by the convert route.
+	pub format: String,
+	/// Encoding label overriding the document codepage.
+	pub charset: Option<String>,
+	/// Time budget of each attempt.
+	pub timeout_ms: i64,
+	/// Attempts before the job is dead-lettered.
+	pub max_attempts: i32,
+	/// Document to convert.
+	pub input: Vec<u8>,
+	/// URL posted when the job is over.
+	pub callback_url: Option<String>,
+	/// Secret signing the callbacks.
+	pub callback_secret: Option<String>,
+	/// Whether the callbacks embed the result.
+	pub callback_include_output: bool,
+}
+
+/// Status of a conversion job, without its input and result.
+#[derive(FromRow, Serialize, Deserialize, Debug)]
+pub struct ConversionJob {
+	/// Job identifier.
+	pub id: Uuid,
+	/// [JobStatus], as stored.
+	pub status: String,
+	/// Share of the conversion done, from 0 to 1.
+	pub progress: f32,
+	/// Output format.
+	pub format: String,
+	/// Content type of the result, once converted.
+	pub content_type: Option<String>,
+	/// Error of the last failed attempt.
+	pub error: Option<String>,
+	/// Attempts started.
+	pub attempts: i32,
+	/// Attempts before the job is dead-lettered.
+	pub max_attempts: i32,
+	/// URL posted when the job is over.
+	pub callback_url: Option<String>,
+	/// When the job was queued.
+	pub created_at: chrono::DateTime<chrono::Utc>,
+	/// Start of the first attempt.
+	pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+	/// When the job was over.
+	pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Conversion job started by a worker.
+#[derive(FromRow, Debug)]
+pub struct ConversionJobInput {
+	/// Job identifier.
+	pub id: Uuid,
+	/// Output format.
+	pub format: String,
+	/// Encoding label overriding the document codepage.
+	pub charset: Option<String>,
+	/// Time budget of the attempt.
+	pub timeout_ms: i64,
+	/// Document to convert.
+	pub input: Vec<u8>,
+	/// Attempts started, this one included.
+	pub attempts: i32,
+	/// Attempts before the job is dead-lettered.
+	pub max_attempts: i32,
+}
+
+/// Result of a conversion job.
+#[derive(FromRow, Debug)]
+pub struct ConversionJobResult {
+	/// [JobStatus], as stored.
+	pub status: String,
+	/// Content type of the result.
+	pub content_type: Option<String>,
+	/// Converted document, once the job succeeded.
+	pub result: Option<Vec<u8>>,
+}
+
+/// Callback of a delivery, with the signing secret of its job.
+#[derive(FromRow, Debug)]
+pub struct WebhookTarget {
+	/// Delivery identifier.
+	pub id: Uuid,
+	/// Job whose callback is delivered.
+	pub job_id: Uuid,
+	/// URL posted.
+	pub url: String,
+	/// Secret signing the callback.
+	pub secret: Option<String>,
+	/// Whether the callback embeds the result.
+	pub include_output: bool,
+}
+
+/// Delivery of a job callback, with its HTTP attempts.
+#[derive(FromRow, Serialize, Debug)]
+pub struct WebhookDelivery {
+	/// Delivery identifier.
+	pub id: Uuid,
+	/// Job whose callback is delivered.
+	pub job_id: Uuid,
+	/// URL posted.
+	pub url: String,
+	/// `pending`, `delivered` or `failed`.
+	pub status: String,
+	/// HTTP status of the last attempt.
+	pub response_status: Option<i32>,
+	/// HTTP attempts, with their outcome.
+	pub attempts: Json<Vec<Attempt>>,
+	/// When the delivery was queued.
+	pub created_at: chrono::DateTime<chrono::Utc>,
+	/// When the last attempt was over.
+	pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+}
@@ -1,7 +1,8 @@
 use crate::infra::db::postgres::RtfDb;
-use crate::models::file::{NewFile, File, FileConditions, FileIdentifier, FileList};
+use crate::models::file::{NewFile, File, FileConditions, FileContent, FileIdentifier, FileList, FileSearch, FileSearchHit, FileUpdate, PurgedFiles, VersionSelector, VersionedWrite};
 use anyhow::{Result, Context};
 use async_trait::async_trait;
+use sqlx::PgConnection;
 use uuid::Uuid;
 use validator::Validate;
 
@@ -13,9 +14,65 @@ use opentelemetry::trace::FutureExt;
 #[async_trait]
 pub trait FileRepo {
 	async fn find_all(&self, conditions: &FileConditions) -> Result<FileList>;
+	async fn count(&self, conditions: &FileConditions) -> Result<i64>;
+	async fn search(&self, search: &FileSearch) -> Result<Vec<FileSearchHit>>;
+	async fn count_search(&self, search: &FileSearch) -> Result<i64>;
 	async fn add(&self, file_data: &NewFile) -> Result<FileIdentifier>;
-	async fn find_by_id(&self, file_id: Uuid) -> Result<File>;
-	async fn delete(&self, file_id: Uuid) -> Result<()>;
+	async fn add_version(&self, file_id: Uuid, expected: Option<i32>, file_data: &NewFile) -> Result<VersionedWrite<FileIdentifier>>;
+	async fn rollback(&self, file_id: Uuid, expected: Option<i32>, version: i32) -> Result<VersionedWrite<FileIdentifier>>;
+	async fn find_by_id(&self, file_id: Uuid) -> Result<Option<File>>;
+	async fn find_version(&self, file_id: Uuid, version: i32) -> Result<Option<File>>;
+	async fn find_versions(&self, file_id: Uuid) -> Result<FileList>;
+	async fn find_content(&self, file_id: Uuid, version: VersionSelector) -> Result<Option<FileContent>>;
+	async fn publish(&self, file_id: Uuid, expected: Option<i32>, version: i32) -> Result<VersionedWrite<File>>;
+	async fn update(&self, file_id: Uuid, expected: Option<i32>, changes: &FileUpdate) -> Result<VersionedWrite<File>>;
+	async fn delete(&self, file_id: Uuid, expected: Option<i32>) -> Result<VersionedWrite<()>>;
+	async fn purge_expired(&self, grace_period: std::time::Duration, batch_size: i64) -> Result<PurgedFiles>;
+	async fn unindexed(&self, batch_size: i64) -> Result<Vec<FileContent>>;
+	async fn set_extracted_text(&self, file_id: Uuid, version: i32, text: &str) -> Result<()>;
+}
+
+/// Metadata columns of [File].
+const FILE_COLUMNS: &str = "id, version, tenant_id, owner_id, content_type, file_name, file_size, created_at, max_age, templating_engine, templating_engine_version, published, legal_hold";
+
+/// Filters of [FileConditions], binding $1 to $4; expired files are left out.
+const FILE_CONDITIONS: &str = r#"
+	(legal_hold OR max_age > NOW())
+	AND tenant_id IS NOT DISTINCT FROM $1
+	AND ($2::uuid IS NULL OR owner_id = $2)
+	AND ($3::text IS NULL OR file_name ILIKE '%' || $3 || '%')
+	AND ($4::text IS NULL OR lower(templating_engine) = lower($4))
+"#;
+
+/// Extracted text escaped as HTML, so that only the `<mark>` tags of search
+/// snippets are markup.
+const ESCAPED_TEXT: &str =
+	"replace(replace(replace(coalesce(extracted_text, ''), '&', '&amp;'), '<', '&lt;'), '>', '&gt;')";
+
+/// Locks the latest version of `file_id` until the end of the transaction,
+/// returning its number when it is `expected`, or when nothing is expected.
+async fn lock_latest<T>(conn: &mut PgConnection, file_id: Uuid, expected: Option<i32>) -> Result<Result<i32, VersionedWrite<T>>> {
+	let latest = sqlx::query_scalar::<_, i32>("SELECT version FROM files WHERE id = $1 ORDER BY version DESC LIMIT 1 FOR UPDATE")
+		.bind(file_id)
+		.fetch_optional(conn)
+		.await
+		.context("DB ERROR (lock latest file version)")?;
+	Ok(match latest {
+		None => Err(VersionedWrite::NotFound),
+		Some(latest) if expected.is_some() && expected != Some(latest) => Err(VersionedWrite::VersionMismatch { latest }),
+		Some(latest) => Ok(latest),
+	})
+}
+
+/// Outcome of inserting the version after `latest`: another insert of the
+/// same version got there first when it already exists.
+fn inserted(row: sqlx::Result<Option<FileIdentifier>>, latest: i32) -> Result<VersionedWrite<FileIdentifier>> {
+	match row {
+		Ok(Some(row)) => Ok(VersionedWrite::Done(row)),
+		Ok(None) => Ok(VersionedWrite::NotFound),
+		Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Ok(VersionedWrite::VersionMismatch { latest: latest + 1 }),
+		Err(e) => Err(anyhow::Error::from(e).context("DB ERROR (insert file version)")),
+	}
 }
 
 #[derive(Clone)]
@@ -33,18 +90,103 @@ impl FileRepoImpl {
 impl FileRepo for FileRepoImpl {
 
 	async fn find_all(&self, conditions: &FileConditions) -> Result<FileList> {
-		let mut query = sqlx::query_as::<_, File>("select * from files");
-		if let Some(name) = &conditions.file_name {
-			query = sqlx::query_as::<_, File>("select * from files where file_name LIKE $1")
-				.bind(format!("%{}%", name))
-		}
-		let result = query
+		let query = format!(
+			r#"
+			SELECT * FROM (
+				SELECT DISTINCT ON (id) {FILE_COLUMNS}
+				FROM files
+				WHERE {FILE_CONDITIONS}
+				ORDER BY id, version DESC
+			) AS latest
+			ORDER BY created_at DESC, id
+			LIMIT $5 OFFSET $6
+			"#
+		);
+		let result = sqlx::query_as::<_, File>(&query)
+			.bind(conditions.tenant_id)
+			.bind(conditions.owner_id)
+			.bind(&conditions.file_name)
+			.bind(&conditions.templating_engine)
+			.bind(conditions.limit)
+			.bind(conditions.offset)
 			.fetch_all(&*self.pool)
 			.await
 			.context("DB ERROR (find all files)")?;
 		Ok(result)
 	}
 
+	async fn count(&self, conditions: &FileConditions) -> Result<i64> {
+		let query = format!("SELECT COUNT(DISTINCT id) FROM files WHERE {FILE_CONDITIONS}");
+		let count = sqlx::query_scalar::<_, i64>(&query)
+			.bind(conditions.tenant_id)
+			.bind(conditions.owner_id)
+			.bind(&conditions.file_name)
+			.bind(&conditions.templating_engine)
+			.fetch_one(&*self.pool)
+			.await
+			.context("DB ERROR (count files)")?;
+		Ok(count)
+	}
+
+	async fn search(&self, search: &FileSearch) -> Result<Vec<FileSearchHit>> {
+		// Only the latest version of each file is searched, as it is listed.
+		let query = format!(
+			r#"
+			SELECT id, version, file_name,
+			       ts_rank(search_vector, query) AS rank,
+			       ts_headline('simple', {ESCAPED_TEXT}, query,
+			                   'StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MinWords=5, MaxWords=20') AS snippet
+			FROM (
+				SELECT DISTINCT ON (id) id, version, file_name, extracted_text, search_vector, created_at
+				FROM files
+				WHERE {FILE_CONDITIONS}
+				ORDER BY id, version DESC
+			) AS latest, websearch_to_tsquery('simple', $5) AS query
+			WHERE search_vector @@ query
+			ORDER BY rank DESC, created_at DESC, id
+			LIMIT $6 OFFSET $7
+			"#
+		);
+		let conditions = &search.conditions;
+		let hits = sqlx::query_as::<_, FileSearchHit>(&query)
+			.bind(conditions.tenant_id)
+			.bind(conditions.owner_id)
+			.bind(&conditions.file_name)
+			.bind(&conditions.templating_engine)
+			.bind(&search.query)
+			.bind(conditions.limit)
+			.bind(conditions.offset)
+			.fetch_all(&*self.pool)
+			.await
+			.context("DB ERROR (search files)")?;
+		Ok(hits)
+	}
+
+	async fn count_search(&self, search: &FileSearch) -> Result<i64> {
+		let query = format!(
+			r#"
+			SELECT COUNT(*) FROM (
+				SELECT DISTINCT ON (id) search_vector
+				FROM files
+				WHERE {FILE_CONDITIONS}
+				ORDER BY id, version DESC
+			) AS latest
+			WHERE search_vector @@ websearch_to_tsquery('simple', $5)
+			"#
+		);
+		let conditions = &search.conditions;
+		let count = sqlx::query_scalar::<_, i64>(&query)
+			.bind(conditions.tenant_id)
+			.bind(conditions.owner_id)
+			.bind(&conditions.file_name)
+			.bind(&conditions.templating_engine)
+			.bind(&search.query)
+			.fetch_one(&*self.pool)
+			.await
+			.context("DB ERROR (count matching files)")?;
+		Ok(count)
+	}
+
 	async fn add(&self, file_data: &NewFile) -> Result<FileIdentifier> {
 		if let Err(errors) = file_data.validate() {
 			let err_messages: Vec<String> = errors
@@ -56,8 +198,8 @@ impl FileRepo for FileRepoImpl {
 
 		let row = sqlx::query_as::<_, FileIdentifier>(
 			r#"
-			INSERT INTO files (tenant_id, owner_id, file_binary_content, content_type, file_name, file_size, created_at, max_age, templating_engine, templating_engine_version, version)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+			INSERT INTO files (tenant_id, owner_id, file_binary_content, content_type, file_name, file_size, created_at, max_age, templating_engine, templating_engine_version, version, extracted_text, field_schema)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, COALESCE($8, NOW() + interval '1 month'), $9, $10, $11, $12, $13)
             RETURNING id, version
             "#,
 		)
@@ -72,6 +214,8 @@ impl FileRepo for FileRepoImpl {
 			.bind(&file_data.templating_engine)
 			.bind(&file_data.templating_engine_version)
 			.bind(1)
+			.bind(&file_data.extracted_text)
+			.bind(&file_data.field_schema)
 			.fetch_one(&*self.pool)
 			.await
 			.map_err(|e| {
@@ -82,20 +226,262 @@ impl FileRepo for FileRepoImpl {
 		Ok(row)
 	}
 
-	async fn find_by_id(&self, file_id: Uuid) -> Result<File> {
-		let row = sqlx::query_as::<_, File>("select * from users where id = $1")
+	async fn add_version(&self, file_id: Uuid, expected: Option<i32>, file_data: &NewFile) -> Result<VersionedWrite<FileIdentifier>> {
+		let mut tx = self.pool.begin().await.context("DB ERROR (begin transaction)")?;
+		let latest = match lock_latest(&mut tx, file_id, expected).await? {
+			Ok(latest) => latest,
+			Err(outcome) => return Ok(outcome),
+		};
+		// Tenant, owner and retention are those of the file, not of a version.
+		let row = sqlx::query_as::<_, FileIdentifier>(
+			r#"
+			INSERT INTO files (id, tenant_id, owner_id, file_binary_content, content_type, file_name, file_size, created_at, max_age, templating_engine, templating_engine_version, version, extracted_text, field_schema)
+			SELECT id, tenant_id, owner_id, $3, $4, COALESCE(NULLIF($5, ''), file_name), $6, COALESCE($7, NOW()), max_age, $8, $9, $10, $11, $12
+			FROM files
+			WHERE id = $1 AND version = $2
+			RETURNING id, version
+			"#,
+		)
 			.bind(file_id)
-			.fetch_one(&*self.pool)
+			.bind(latest)
+			.bind(&file_data.file_binary_content)
+			.bind(&file_data.content_type)
+			.bind(&file_data.file_name)
+			.bind(file_data.file_size)
+			.bind(file_data.insertion_date)
+			.bind(&file_data.templating_engine)
+			.bind(file_data.templating_engine_version)
+			.bind(latest + 1)
+			.bind(&file_data.extracted_text)
+			.bind(&file_data.field_schema)
+			.fetch_optional(&mut *tx)
+			.await;
+		let outcome = inserted(row, latest)?;
+		if let VersionedWrite::Done(_) = outcome {
+			tx.commit().await.context("DB ERROR (commit file version)")?;
+		}
+		Ok(outcome)
+	}
+
+	async fn rollback(&self, file_id: Uuid, expected: Option<i32>, version: i32) -> Result<VersionedWrite<FileIdentifier>> {
+		let mut tx = self.pool.begin().await.context("DB ERROR (begin transaction)")?;
+		let latest = match lock_latest(&mut tx, file_id, expected).await? {
+			Ok(latest) => latest,
+			Err(outcome) => return Ok(outcome),
+		};
+		let row = sqlx::query_as::<_, FileIdentifier>(
+			r#"
+			INSERT INTO files (id, tenant_id, owner_id, file_binary_content, content_type, file_name, file_size, created_at, max_age, templating_engine, templating_engine_version, version, extracted_text, field_schema)
+			SELECT source.id, latest.tenant_id, latest.owner_id, source.file_binary_content, source.content_type, latest.file_name, source.file_size, NOW(), latest.max_age,
+			       source.templating_engine, source.templating_engine_version, $4, source.extracted_text, source.field_schema
+			FROM files AS source
+			JOIN files AS latest ON latest.id = source.id AND latest.version = $3
+			WHERE source.id = $1 AND source.version = $2
+			RETURNING id, version
+			"#,
+		)
+			.bind(file_id)
+			.bind(version)
+			.bind(latest)
+			.bind(latest + 1)
+			.fetch_optional(&mut *tx)
+			.await;
+		let outcome = inserted(row, latest)?;
+		if let VersionedWrite::Done(_) = outcome {
+			tx.commit().await.context("DB ERROR (commit file rollback)")?;
+		}
+		Ok(outcome)
+	}
+
+	async fn find_by_id(&self, file_id: Uuid) -> Result<Option<File>> {
+		let query = format!("SELECT {FILE_COLUMNS} FROM files WHERE id = $1 ORDER BY version DESC LIMIT 1");
+		let row = sqlx::query_as::<_, File>(&query)
+			.bind(file_id)
+			.fetch_optional(&*self.pool)
 			.await
 			.context("DB ERROR (find file by id)")?;
 		Ok(row)
 	}
 
-	async fn delete(&self, file_id: Uuid) -> Result<()> {
-		sqlx::query("DELETE FROM files WHERE id = $1")
-			.execute(&*self.pool)
-			.await?;
+	async fn find_version(&self, file_id: Uuid, version: i32) -> Result<Option<File>> {
+		let query = format!("SELECT {FILE_COLUMNS} FROM files WHERE id = $1 AND version = $2");
+		let row = sqlx::query_as::<_, File>(&query)
+			.bind(file_id)
+			.bind(version)
+			.fetch_optional(&*self.pool)
+			.await
+			.context("DB ERROR (find file version)")?;
+		Ok(row)
+	}
 
+	async fn find_versions(&self, file_id: Uuid) -> Result<FileList> {
+		let query = format!("SELECT {FILE_COLUMNS} FROM files WHERE id = $1 ORDER BY version DESC");
+		let rows = sqlx::query_as::<_, File>(&query)
+			.bind(file_id)
+			.fetch_all(&*self.pool)
+			.await
+			.context("DB ERROR (find file versions)")?;
+		Ok(rows)
+	}
+
+	async fn find_content(&self, file_id: Uuid, version: VersionSelector) -> Result<Option<FileContent>> {
+		let (number, published) = match version {
+			VersionSelector::Latest => (None, false),
+			VersionSelector::Published => (None, true),
+			VersionSelector::Number(number) => (Some(number), false),
+		};
+		let row = sqlx::query_as::<_, FileContent>(
+			r#"
+			SELECT id, version, file_name, content_type, file_binary_content, templating_engine, templating_engine_version, field_schema, max_age, legal_hold
+			FROM files
+			WHERE id = $1 AND ($2::int IS NULL OR version = $2)
+			ORDER BY ($3 AND published) DESC, version DESC
+			LIMIT 1
+			"#,
+		)
+			.bind(file_id)
+			.bind(number)
+			.bind(published)
+			.fetch_optional(&*self.pool)
+			.await
+			.context("DB ERROR (find file content)")?;
+		Ok(row)
+	}
+
+	async fn publish(&self, file_id: Uuid, expected: Option<i32>, version: i32) -> Result<VersionedWrite<File>> {
+		let mut tx = self.pool.begin().await.context("DB ERROR (begin transaction)")?;
+		if let Err(outcome) = lock_latest(&mut tx, file_id, expected).await? {
+			return Ok(outcome);
+		}
+		// Unpublished first: at most one version is published at any time.
+		sqlx::query("UPDATE files SET published = FALSE WHERE id = $1 AND published AND version <> $2")
+			.bind(file_id)
+			.bind(version)
+			.execute(&mut *tx)
+			.await
+			.context("DB ERROR (unpublish file versions)")?;
+		let query = format!("UPDATE files SET published = TRUE WHERE id = $1 AND version = $2 RETURNING {FILE_COLUMNS}");
+		let row = sqlx::query_as::<_, File>(&query)
+			.bind(file_id)
+			.bind(version)
+			.fetch_optional(&mut *tx)
+			.await;
+		let row = match row {
+			Ok(Some(row)) => row,
+			Ok(None) => return Ok(VersionedWrite::NotFound),
+			// Another version was published in the meantime.
+			Err(sqlx::Error::Database(e)) if e.is_unique_violation() => return Ok(VersionedWrite::Conflict),
+			Err(e) => return Err(anyhow::Error::from(e).context("DB ERROR (publish file version)")),
+		};
+		tx.commit().await.context("DB ERROR (commit file publication)")?;
+		Ok(VersionedWrite::Done(row))
+	}
+
+	async fn update(&self, file_id: Uuid, expected: Option<i32>, changes: &FileUpdate) -> Result<VersionedWrite<File>> {
+		let mut tx = self.pool.begin().await.context("DB ERROR (begin transaction)")?;
+		let latest = match lock_latest(&mut tx, file_id, expected).await? {
+			Ok(latest) => latest,
+			Err(outcome) => return Ok(outcome),
+		};
+		sqlx::query(
+			r#"
+			UPDATE files
+			SET file_name = COALESCE($2, file_name),
+			    owner_id = COALESCE($3, owner_id),
+			    max_age = COALESCE($4, max_age),
+			    legal_hold = COALESCE($5, legal_hold)
+			WHERE id = $1
+			"#,
+		)
+			.bind(file_id)
+			.bind(&changes.file_name)
+			.bind(changes.owner_id)
+			.bind(changes.max_age)
+			.bind(changes.legal_hold)
+			.execute(&mut *tx)
+			.await
+			.context("DB ERROR (update file)")?;
+		let query = format!("SELECT {FILE_COLUMNS} FROM files WHERE id = $1 AND version = $2");
+		let row = sqlx::query_as::<_, File>(&query)
+			.bind(file_id)
+			.bind(latest)
+			.fetch_one(&mut *tx)
+			.await
+			.context("DB ERROR (find updated file)")?;
+		tx.commit().await.context("DB ERROR (commit file update)")?;
+		Ok(VersionedWrite::Done(row))
+	}
+
+	async fn delete(&self, file_id: Uuid, expected: Option<i32>) -> Result<VersionedWrite<()>> {
+		let mut tx = self.pool.begin().await.context("DB ERROR (begin transaction)")?;
+		if let Err(outcome) = lock_latest(&mut tx, file_id, expected).await? {
+			return Ok(outcome);
+		}
+		// Held files are kept, as by the retention purge.
+		let deleted = sqlx::query("DELETE FROM files WHERE id = $1 AND NOT legal_hold")
+			.bind(file_id)
+			.execute(&mut *tx)
+			.await
+			.context("DB ERROR (delete file)")?;
+		if deleted.rows_affected() == 0 {
+			return Ok(VersionedWrite::Held);
+		}
+		tx.commit().await.context("DB ERROR (commit file deletion)")?;
+
+		Ok(VersionedWrite::Done(()))
+	}
+
+	async fn purge_expired(&self, grace_period: std::time::Duration, batch_size: i64) -> Result<PurgedFiles> {
+		// Skips the rows locked by writers: they are purged by a later batch.
+		let purged = sqlx::query_as::<_, PurgedFiles>(
+			r#"
+			WITH expired AS (
+				SELECT id, version FROM files
+				WHERE NOT legal_hold AND max_age <= NOW() - make_interval(secs => $1)
+				ORDER BY max_age
+				LIMIT $2
+				FOR UPDATE SKIP LOCKED
+			), purged AS (
+				DELETE FROM files USING expired
+				WHERE files.id = expired.id AND files.version = expired.version
+				RETURNING octet_length(files.file_binary_content) AS bytes
+			)
+			SELECT COUNT(*) AS files, COALESCE(SUM(bytes), 0)::BIGINT AS bytes FROM purged
+			"#,
+		)
+			.bind(grace_period.as_secs_f64())
+			.bind(batch_size)
+			.fetch_one(&*self.pool)
+			.await
+			.context("DB ERROR (purge expired files)")?;
+		Ok(purged)
+	}
+
+	async fn unindexed(&self, batch_size: i64) -> Result<Vec<FileContent>> {
+		let files = sqlx::query_as::<_, FileContent>(
+			r#"
+			SELECT id, version, file_name, content_type, file_binary_content, templating_engine, templating_engine_version, field_schema, max_age, legal_hold
+			FROM files
+			WHERE extracted_text IS NULL
+			ORDER BY id, version
+			LIMIT $1
+			"#,
+		)
+			.bind(batch_size)
+			.fetch_all(&*self.pool)
+			.await
+			.context("DB ERROR (find unindexed files)")?;
+		Ok(files)
+	}
+
+	async fn set_extracted_text(&self, file_id: Uuid, version: i32, text: &str) -> Result<()> {
+		sqlx::query("UPDATE files SET extracted_text = $3 WHERE id = $1 AND version = $2")
+			.bind(file_id)
+			.bind(version)
+			.bind(text)
+			.execute(&*self.pool)
+			.await
+			.context("DB ERROR (set extracted text)")?;
 		Ok(())
 	}
 }
@@ -0,0 +1,261 @@
+use crate::infra::db::postgres::RtfDb;
+use crate::middleware::client::attempts::Attempt;
+use crate::models::job::{ConversionJob, ConversionJobInput, ConversionJobResult, JobStatus, NewConversionJob, WebhookDelivery, WebhookTarget};
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use sqlx::types::Json;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Conversion jobs and the deliveries of their callbacks.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait JobRepo {
+	/// Stores a queued job, returning its identifier.
+	async fn add(&self, job: &NewConversionJob) -> Result<Uuid>;
+	/// Status of the job `job_id`.
+	async fn find(&self, job_id: Uuid) -> Result<Option<ConversionJob>>;
+	/// Result of the job `job_id`.
+	async fn find_result(&self, job_id: Uuid) -> Result<Option<ConversionJobResult>>;
+	/// Starts an attempt of the job `job_id`, returning its input, or `None`
+	/// when the job is over.
+	async fn start(&self, job_id: Uuid) -> Result<Option<ConversionJobInput>>;
+	/// Records the share of the conversion done.
+	async fn set_progress(&self, job_id: Uuid, progress: f32) -> Result<()>;
+	/// Stores the result of the job, and clears its input.
+	async fn succeed(&self, job_id: Uuid, content_type: &str, result: &[u8]) -> Result<()>;
+	/// Records a failed attempt, leaving the job in `status`; the input is
+	/// kept while the job is queued again.
+	async fn fail(&self, job_id: Uuid, status: JobStatus, error: &str) -> Result<()>;
+	/// Dead-letters the jobs running their last attempt for longer than their
+	/// timeout and `grace`, returning their identifiers.
+	async fn sweep_stalled(&self, grace: Duration, error: &str) -> Result<Vec<Uuid>>;
+	/// Adds a pending delivery of the callback of the job, returning its
+	/// identifier, or `None` when the job has no callback.
+	async fn add_delivery(&self, job_id: Uuid) -> Result<Option<Uuid>>;
+	/// Callback of the delivery `delivery_id`.
+	async fn find_webhook(&self, delivery_id: Uuid) -> Result<Option<WebhookTarget>>;
+	/// Deliveries of the callbacks of the job, latest first.
+	async fn find_deliveries(&self, job_id: Uuid) -> Result<Vec<WebhookDelivery>>;
+	/// Records the outcome and the HTTP attempts of a delivery.
+	async fn record_delivery(&self, delivery_id: Uuid, delivered: bool, attempts: &[Attempt]) -> Result<()>;
+}
+
+/// [JobRepo] on the RTF database.
+#[derive(Clone)]
+pub struct JobRepoImpl {
+	pool: RtfDb,
+}
+
+impl JobRepoImpl {
+	/// Repository on the connection pool `pool`.
+	pub fn new(pool: RtfDb) -> Self {
+		Self { pool }
+	}
+}
+
+#[async_trait]
+impl JobRepo for JobRepoImpl {
+
+	async fn add(&self, job: &NewConversionJob) -> Result<Uuid> {
+		let id = sqlx::query_scalar::<_, Uuid>(
+			r#"
+			INSERT INTO conversion_jobs (format, charset, timeout_ms, max_attempts, input, callback_url, callback_secret, callback_include_output)
+			VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+			RETURNING id
+			"#,
+		)
+			.bind(&job.format)
+			.bind(&job.charset)
+			.bind(job.timeout_ms)
+			.bind(job.max_attempts)
+			.bind(&job.input)
+			.bind(&job.callback_url)
+			.bind(&job.callback_secret)
+			.bind(job.callback_include_output)
+			.fetch_one(&*self.pool)
+			.await
+			.context("DB ERROR (insert conversion job)")?;
+		Ok(id)
+	}
+
+	async fn find(&self, job_id: Uuid) -> Result<Option<ConversionJob>> {
+		let row = sqlx::query_as::<_, ConversionJob>(
+			r#"
+			SELECT id, status, progress, format, content_type, error, attempts, max_attempts, callback_url, created_at, started_at, finished_at
+			FROM conversion_jobs
+			WHERE id = $1
+			"#,
+		)
+			.bind(job_id)
+			.fetch_optional(&*self.pool)
+			.await
+			.context("DB ERROR (find conversion job)")?;
+		Ok(row)
+	}
+
+	async fn find_result(&self, job_id: Uuid) -> Result<Option<ConversionJobResult>> {
+		let row = sqlx::query_as::<_, ConversionJobResult>("SELECT status, content_type, result FROM conversion_jobs WHERE id = $1")
+			.bind(job_id)
+			.fetch_optional(&*self.pool)
+			.await
+			.context("DB ERROR (find conversion job result)")?;
+		Ok(row)
+	}
+
+	async fn start(&self, job_id: Uuid) -> Result<Option<ConversionJobInput>> {
+		// A running job is started again when its worker stopped before the end.
+		let row = sqlx::query_as::<_, ConversionJobInput>(
+			r#"
+			UPDATE conversion_jobs
+			SET status = 'running', progress = 0, attempts = attempts + 1, started_at = COALESCE(started_at, NOW()), attempted_at = NOW()
+			WHERE id = $1 AND status IN ('queued', 'running') AND input IS NOT NULL
+			RETURNING id, format, charset, timeout_ms, input, attempts, max_attempts
+			"#,
+		)
+			.bind(job_id)
+			.fetch_optional(&*self.pool)
+			.await
+			.context("DB ERROR (start conversion job)")?;
+		Ok(row)
+	}
+
+	async fn set_progress(&self, job_id: Uuid, progress: f32) -> Result<()> {
+		sqlx::query("UPDATE conversion_jobs SET progress = $2 WHERE id = $1")
+			.bind(job_id)
+			.bind(progress)
+			.execute(&*self.pool)
+			.await
+			.context("DB ERROR (update conversion job progress)")?;
+		Ok(())
+	}
+
+	async fn succeed(&self, job_id: Uuid, content_type: &str, result: &[u8]) -> Result<()> {
+		sqlx::query(
+			r#"
+			UPDATE conversion_jobs
+			SET status = 'succeeded', progress = 1, content_type = $2, result = $3, error = NULL, input = NULL, finished_at = NOW()
+			WHERE id = $1
+			"#,
+		)
+			.bind(job_id)
+			.bind(content_type)
+			.bind(result)
+			.execute(&*self.pool)
+			.await
+			.context("DB ERROR (complete conversion job)")?;
+		Ok(())
+	}
+
+	async fn fail(&self, job_id: Uuid, status: JobStatus, error: &str) -> Result<()> {
+		// The input is kept for the next attempt while the job is queued.
+		sqlx::query(
+			r#"
+			UPDATE conversion_jobs
+			SET status = $2, progress = 0, error = $3,
+			    input = CASE WHEN $4 THEN NULL ELSE input END,
+			    finished_at = CASE WHEN $4 THEN NOW() ELSE NULL END
+			WHERE id = $1
+			"#,
+		)
+			.bind(job_id)
+			.bind(status.as_str())
+			.bind(error)
+			.bind(status.is_final())
+			.execute(&*self.pool)
+			.await
+			.context("DB ERROR (fail conversion job)")?;
+		Ok(())
+	}
+
+	async fn sweep_stalled(&self, grace: Duration, error: &str) -> Result<Vec<Uuid>> {
+		// Earlier attempts are retried by the queue, the last one is not.
+		let ids = sqlx::query_scalar::<_, Uuid>(
+			r#"
+			UPDATE conversion_jobs
+			SET status = 'dead', progress = 0, error = $2, input = NULL, finished_at = NOW()
+			WHERE status = 'running' AND attempts >= max_attempts
+			      AND attempted_at + (timeout_ms + $1) * INTERVAL '1 millisecond' < NOW()
+			RETURNING id
+			"#,
+		)
+			.bind(i64::try_from(grace.as_millis()).unwrap_or(i64::MAX))
+			.bind(error)
+			.fetch_all(&*self.pool)
+			.await
+			.context("DB ERROR (sweep stalled conversion jobs)")?;
+		Ok(ids)
+	}
+
+	async fn add_delivery(&self, job_id: Uuid) -> Result<Option<Uuid>> {
+		// Jobs without callback have nothing to deliver.
+		let id = sqlx::query_scalar::<_, Uuid>(
+			r#"
+			INSERT INTO webhook_deliveries (job_id, url)
+			SELECT id, callback_url FROM conversion_jobs
+			WHERE id = $1 AND callback_url IS NOT NULL
+			RETURNING id
+			"#,
+		)
+			.bind(job_id)
+			.fetch_optional(&*self.pool)
+			.await
+			.context("DB ERROR (insert webhook delivery)")?;
+		Ok(id)
+	}
+
+	async fn find_webhook(&self, delivery_id: Uuid) -> Result<Option<WebhookTarget>> {
+		let row = sqlx::query_as::<_, WebhookTarget>(
+			r#"
+			SELECT d.id, d.job_id, d.url, j.callback_secret AS secret, j.callback_include_output AS include_output
+			FROM webhook_deliveries d
+			JOIN conversion_jobs j ON j.id = d.job_id
+			WHERE d.id = $1
+			"#,
+		)
+			.bind(delivery_id)
+			.fetch_optional(&*self.pool)
+			.await
+			.context("DB ERROR (find webhook delivery)")?;
+		Ok(row)
+	}
+
+	async fn find_deliveries(&self, job_id: Uuid) -> Result<Vec<WebhookDelivery>> {
+		let rows = sqlx::query_as::<_, WebhookDelivery>(
+			r#"
+			SELECT id, job_id, url, status, response_status, attempts, created_at, finished_at
+			FROM webhook_deliveries
+			WHERE job_id = $1
+			ORDER BY created_at DESC
+			"#,
+		)
+			.bind(job_id)
+			.fetch_all(&*self.pool)
+			.await
+			.context("DB ERROR (find webhook deliveries)")?;
+		Ok(rows)
+	}
+
+	async fn record_delivery(&self, delivery_id: Uuid, delivered: bool, attempts: &[Attempt]) -> Result<()> {
+		let response_status = attempts.last().and_then(|attempt| attempt.status).map(i32::from);
+		sqlx::query(
+			r#"
+			UPDATE webhook_deliveries
+			SET status = CASE WHEN $2 THEN 'delivered' ELSE 'failed' END, response_status = $3,
+			    attempts = attempts || $4, finished_at = NOW()
+			WHERE id = $1
+			"#,
+		)
+			.bind(delivery_id)
+			.bind(delivered)
+			.bind(response_status)
+			.bind(Json(attempts))
+			.execute(&*self.pool)
+			.await
+			.context("DB ERROR (record webhook delivery)")?;
+		Ok(())
+	}
+}
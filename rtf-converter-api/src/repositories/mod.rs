@@ -1,43 +1,64 @@
-use crate::infra::db::postgres;
+use crate::infra::db::postgres::{self, MqDb};
 use crate::repositories::{
 	file::{FileRepo, FileRepoImpl},
+	job::{JobRepo, JobRepoImpl},
 };
 use axum::extract::Extension;
 use std::sync::Arc;
 
 pub mod file;
+pub mod job;
 
 pub type RepoExt = Extension<Arc<RepoImpls>>;
 
 pub async fn create_repositories() -> RepoImpls {
 	let rtf_db_pool = Arc::new(postgres::rtf_db_connect().await);
-	RepoImpls::new(FileRepoImpl::new(rtf_db_pool.clone()))
+	let mq_db_pool = Arc::new(postgres::mq_db_connect().await);
+	RepoImpls::new(
+		FileRepoImpl::new(rtf_db_pool.clone()),
+		JobRepoImpl::new(rtf_db_pool.clone()),
+		mq_db_pool,
+	)
 }
 
 #[derive(Clone)]
 pub struct RepoImpls {
 	pub file: FileRepoImpl,
+	pub job: JobRepoImpl,
+	/// Pool of the sqlxmq job queue.
+	pub queue: MqDb,
 }
 impl RepoImpls {
 	pub fn new(
 		file_repo_impl: FileRepoImpl,
+		job_repo_impl: JobRepoImpl,
+		queue: MqDb,
 	) -> Self {
 		Self {
 			file: file_repo_impl,
+			job: job_repo_impl,
+			queue,
 		}
 	}
 }
 
 pub trait Repositories {
 	type FileRepoImpl: FileRepo;
+	type JobRepoImpl: JobRepo;
 
 	fn file(&self) -> &Self::FileRepoImpl;
+	fn job(&self) -> &Self::JobRepoImpl;
 }
 
 impl Repositories for RepoImpls {
 	type FileRepoImpl = FileRepoImpl;
+	type JobRepoImpl = JobRepoImpl;
 
 	fn file(&self) -> &Self::FileRepoImpl {
 		&self.file
 	}
+
+	fn job(&self) -> &Self::JobRepoImpl {
+		&self.job
+	}
 }
\ No newline at end of file
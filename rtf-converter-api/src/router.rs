@@ -3,16 +3,38 @@
 use std::sync::Arc;
 use crate::{
     middleware::logging::{log_request_response, DebugOnlyLogger, Logger},
-    routes::{fallback::notfound_404, health, convert, template},
-    repositories::create_repositories,
+    routes::{fallback::notfound_404, health, analyze, convert, diff, generate, jobs, normalize, sanitize, template},
+    repositories::RepoImpls,
 };
 use axum::{routing::get, routing::post, Router, Extension};
 
-/// Setup main router for application.
-pub async fn setup_app_router() -> Router {
+/// Setup main router for application, on the repositories `repos`.
+pub async fn setup_app_router(repos: Arc<RepoImpls>) -> Router {
     let mut router = Router::new()
+        .route("/analyze", post(analyze::analyze))
         .route("/convert", post(convert::convert))
-        .route("/template", post(template::upload)).layer(Extension(Arc::new(create_repositories().await.clone())))
+        .route("/diff", post(diff::diff))
+        .route("/generate/text", post(generate::text))
+        .route("/generate/markdown", post(generate::markdown))
+        .route("/generate/html", post(generate::html))
+        .route("/jobs/convert", post(jobs::create))
+        .route("/jobs/:id", get(jobs::status))
+        .route("/jobs/:id/result", get(jobs::result))
+        .route("/jobs/:id/deliveries", get(jobs::deliveries).post(jobs::replay))
+        .route("/normalize", post(normalize::normalize))
+        .route("/sanitize", post(sanitize::sanitize))
+        .route("/template", post(template::upload))
+        .route("/template/:id", get(template::metadata).put(template::update).delete(template::delete))
+        .route("/template/:id/content", get(template::content))
+        .route("/template/:id/versions", get(template::versions).post(template::create_version))
+        .route("/template/:id/versions/:version", get(template::version_metadata))
+        .route("/template/:id/versions/:version/content", get(template::version_content))
+        .route("/template/:id/versions/:version/render", post(template::render_version))
+        .route("/template/:id/versions/:version/publish", post(template::publish))
+        .route("/template/:id/versions/:version/rollback", post(template::rollback))
+        .route("/template/:id/render", post(template::render))
+        .route("/template/:id/fields", get(template::fields))
+        .route("/templates", get(template::list)).layer(Extension(repos))
         .fallback(notfound_404);
 
     router = router.layer(axum::middleware::from_fn(log_request_response::<Logger>));
@@ -0,0 +1,48 @@
+//! Document statistics route.
+use crate::{
+    error::AppResult,
+    routes::convert::{conversion_error, parse_charset},
+    rtf::{analyze, options::ConvertOptions, to_text},
+    settings::Limits,
+};
+use axum::{body::Bytes, http::StatusCode, Extension, Json};
+use axum_typed_multipart::{FieldData, TryFromMultipart, TypedMultipart};
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(TryFromMultipart, IntoParams, ToSchema)]
+pub struct AnalyzeRequest {
+    #[schema(value_type = String, format = Binary)]
+    rtf_file: FieldData<Bytes>,
+    /// Encoding label (e.g. `windows-1251`) overriding the document codepage.
+    charset: Option<String>,
+}
+
+#[utoipa::path(
+post,
+path = "/analyze",
+request_body(content = AnalyzeRequest, description = "RTF file content", content_type = "multipart/form-data"),
+responses(
+(status = 200, description = "Word, character, paragraph and page counts, languages and their share of the text, fonts, colors, images, tables, fields, readability and page layout"),
+(status = 400, description = "Unknown charset", body=AppError),
+(status = 413, description = "Document exceeds the maximum input size", body=AppError),
+(status = 422, description = "Document exceeds a conversion limit", body=AppError),
+(status = 500, description = "Conversion failed", body=AppError)
+)
+)]
+pub async fn analyze(Extension(limits): Extension<Limits>, TypedMultipart(AnalyzeRequest { rtf_file, charset }): TypedMultipart<AnalyzeRequest>,
+) -> AppResult<(StatusCode, Json<serde_json::Value>)> {
+    let options = ConvertOptions {
+        charset: parse_charset(charset.as_deref())?,
+        ..ConvertOptions::new(limits)
+    };
+    let analysis = tokio::task::spawn_blocking(move || {
+        let tokens = to_text::tokenize(rtf_file.contents.as_ref(), &options.limits)?;
+        analyze::analyze(&tokens, &options)
+    })
+    .await
+    .map_err(anyhow::Error::from)?
+    .map_err(conversion_error)?;
+
+    Ok((StatusCode::OK, Json(json!(analysis))))
+}
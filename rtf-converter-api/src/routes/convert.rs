@@ -1,9 +1,15 @@
 //! Generic convert route.
-use crate::error::AppResult;
+use crate::{
+    error::{AppError, AppResult},
+    rtf::{error::{LimitExceeded, RtfError, TemplateError}, options::ConvertOptions, to_html, to_pdf, to_text},
+    settings::Limits,
+};
 use axum::{
     body::Bytes,
-    http::StatusCode, Json,
+    http::StatusCode, Extension, Json,
 };
+use base64::Engine;
+use rtf_grimoire::tokenizer::Token;
 use serde_json::json;
 use axum_typed_multipart::{FieldData, TryFromMultipart, TypedMultipart};
 use utoipa::{ToSchema, IntoParams};
@@ -13,35 +19,158 @@ use utoipa::{ToSchema, IntoParams};
 pub struct RequestData {
     #[schema(value_type = String, format = Binary)]
     rtf_file: FieldData<Bytes>,
+    /// Encoding label (e.g. `windows-1251`) overriding the document codepage.
+    charset: Option<String>,
+    /// Output format: `text` (default), `html`, `pdf` (base64-encoded), or
+    /// `segments` for the text split by language, as `{language, text}` objects.
+    format: Option<String>,
+}
+
+/// Output format of the convert route.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    Text,
+    Html,
+    Pdf,
+    Segments,
+}
+
+impl OutputFormat {
+    /// Key of the converted document in the response body.
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Html => "html",
+            OutputFormat::Pdf => "pdf",
+            OutputFormat::Segments => "segments",
+        }
+    }
+}
+
+/// Resolves a `format` request field.
+pub(crate) fn parse_format(format: Option<&str>) -> AppResult<OutputFormat> {
+    match format.map(str::trim) {
+        None | Some("text") => Ok(OutputFormat::Text),
+        Some("html") => Ok(OutputFormat::Html),
+        Some("pdf") => Ok(OutputFormat::Pdf),
+        Some("segments") => Ok(OutputFormat::Segments),
+        Some(other) => Err(AppError::new(
+            StatusCode::BAD_REQUEST,
+            Some(format!("unknown format: {other}")),
+        )),
+    }
+}
+
+/// Resolves a `charset` request field to an encoding.
+pub(crate) fn parse_charset(charset: Option<&str>) -> AppResult<Option<&'static encoding_rs::Encoding>> {
+    charset
+        .map(|label| {
+            encoding_rs::Encoding::for_label(label.trim().as_bytes()).ok_or_else(|| {
+                AppError::new(StatusCode::BAD_REQUEST, Some(format!("unknown charset: {label}")))
+            })
+        })
+        .transpose()
+}
+
+/// Document converted to an [OutputFormat].
+#[derive(Debug)]
+pub(crate) struct Converted {
+    pub(crate) content_type: &'static str,
+    pub(crate) output: Vec<u8>,
+    /// Malformed constructs skipped by the conversion.
+    pub(crate) diagnostics: Vec<RtfError>,
+}
+
+/// Converts an RTF document to `format`.
+pub(crate) fn convert_document(rtf: &[u8], format: OutputFormat, options: &ConvertOptions) -> anyhow::Result<Converted> {
+    let tokens = to_text::tokenize(rtf, &options.limits)?;
+    convert_tokens(&tokens, format, options)
+}
+
+/// Converts the tokens of an RTF document to `format`.
+pub(crate) fn convert_tokens(tokens: &[Token], format: OutputFormat, options: &ConvertOptions) -> anyhow::Result<Converted> {
+    let mut output = Vec::new();
+    let (content_type, diagnostics) = match format {
+        OutputFormat::Text => ("text/plain; charset=utf-8", to_text::write_plaintext(tokens, &mut output, options)?),
+        OutputFormat::Html => ("text/html; charset=utf-8", to_html::write_html(tokens, &mut output, options)?),
+        OutputFormat::Pdf => ("application/pdf", to_pdf::write_pdf(tokens, &mut output, options)?),
+        OutputFormat::Segments => {
+            let document = to_text::read_document(tokens, options)?;
+            output = serde_json::to_vec(&document.body.language_segments())?;
+            ("application/json", document.diagnostics)
+        }
+    };
+    Ok(Converted { content_type, output, diagnostics })
+}
+
+/// Maps conversion failures, keeping the status of an exceeded limit or a
+/// template error.
+pub(crate) fn conversion_error(err: anyhow::Error) -> AppError {
+    let err = match err.downcast::<LimitExceeded>() {
+        Ok(limit) => return limit.into(),
+        Err(err) => err,
+    };
+    match err.downcast::<TemplateError>() {
+        Ok(template) => template.into(),
+        Err(err) => err.into(),
+    }
 }
 
-// #[utoipa::path(
-// post,
-// path = "/convert",
-// request_body(content = RequestData, description = "RTF file content", content_type = "multipart/form-data"),
-// responses(
-// (status = 200, description = "Conversion successful"),
-// (status = 500, description = "Conversion failed", body=AppError)
-// )
-// )]
 #[utoipa::path(
 post,
 path = "/convert",
 request_body(content = RequestData, description = "RTF file content", content_type = "multipart/form-data"),
 responses(
 (status = 200, description = "Conversion successful"),
+(status = 400, description = "Unknown charset or format", body=AppError),
+(status = 413, description = "Document exceeds the maximum input size", body=AppError),
+(status = 422, description = "Document exceeds a conversion limit", body=AppError),
 (status = 500, description = "Conversion failed", body=AppError)
 )
 )]
-pub async fn convert(TypedMultipart(RequestData { rtf_file }): TypedMultipart<RequestData>,
+pub async fn convert(Extension(limits): Extension<Limits>, TypedMultipart(RequestData { rtf_file, charset, format }): TypedMultipart<RequestData>,
 ) -> AppResult<(StatusCode, Json<serde_json::Value>)> {
-    let resp = format!(
-        "file name = '{}', content type = '{}', size = '{}'",
-        rtf_file.metadata.file_name.unwrap_or(String::new()),
-        rtf_file.metadata.content_type.unwrap_or(String::from("text/plain")),
-        rtf_file.contents.len()
-    );
-    Ok((StatusCode::OK, Json(json!({ "msg": resp}))))
+    let options = ConvertOptions {
+        charset: parse_charset(charset.as_deref())?,
+        ..ConvertOptions::new(limits)
+    };
+    let format = parse_format(format.as_deref())?;
+    let converted = tokio::task::spawn_blocking(move || convert_document(rtf_file.contents.as_ref(), format, &options))
+        .await
+        .map_err(anyhow::Error::from)?
+        .map_err(conversion_error)?;
+    Ok((StatusCode::OK, Json(converted_json(format, converted)?)))
+}
+
+/// Body of the convert route: the document under the key of its format, with
+/// the diagnostics of the conversion.
+fn converted_json(format: OutputFormat, converted: Converted) -> anyhow::Result<serde_json::Value> {
+    let output = match format {
+        OutputFormat::Text | OutputFormat::Html => json!(String::from_utf8_lossy(&converted.output)),
+        OutputFormat::Pdf => json!(base64::engine::general_purpose::STANDARD.encode(&converted.output)),
+        OutputFormat::Segments => serde_json::from_slice(&converted.output)?,
+    };
+    let diagnostics: Vec<String> = converted.diagnostics.iter().map(ToString::to_string).collect();
+    Ok(json!({ (format.key()): output, "diagnostics": diagnostics }))
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    fn converted(format: OutputFormat) -> serde_json::Value {
+        let options = ConvertOptions::new(Limits::default());
+        let converted = convert_document(br"{\rtf1{\lang1036 Bonjour}}", format, &options).unwrap();
+        converted_json(format, converted).unwrap()
+    }
+
+    #[test]
+    fn test_converted_json() {
+        assert!(converted(OutputFormat::Text)["text"].as_str().is_some_and(|text| text.starts_with("Bonjour")));
+        assert_eq!(converted(OutputFormat::Text)["diagnostics"], json!([]));
+        assert!(converted(OutputFormat::Html)["html"].as_str().is_some_and(|html| html.contains("Bonjour")));
+        assert_eq!(converted(OutputFormat::Segments)["segments"][0]["language"], json!("fr-FR"));
+        let pdf = converted(OutputFormat::Pdf)["pdf"].as_str().map(|pdf| base64::engine::general_purpose::STANDARD.decode(pdf));
+        assert!(matches!(pdf, Some(Ok(pdf)) if pdf.starts_with(b"%PDF")));
+    }
+}
@@ -0,0 +1,62 @@
+//! Document diff route.
+use std::time::Instant;
+
+use crate::{
+    error::AppResult,
+    routes::convert::{conversion_error, parse_charset},
+    rtf::{diff, options::ConvertOptions, to_text},
+    settings::Limits,
+};
+use axum::{body::Bytes, http::StatusCode, Extension, Json};
+use axum_typed_multipart::{FieldData, TryFromMultipart, TypedMultipart};
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(TryFromMultipart, IntoParams, ToSchema)]
+pub struct DiffRequest {
+    /// Old version of the document.
+    #[schema(value_type = String, format = Binary)]
+    old_file: FieldData<Bytes>,
+    /// New version of the document.
+    #[schema(value_type = String, format = Binary)]
+    new_file: FieldData<Bytes>,
+    /// Encoding label (e.g. `windows-1251`) overriding the documents' codepage.
+    charset: Option<String>,
+}
+
+#[utoipa::path(
+post,
+path = "/diff",
+request_body(content = DiffRequest, description = "Old and new RTF file content", content_type = "multipart/form-data"),
+responses(
+(status = 200, description = "Paragraph and word level diff, as JSON and as redlined HTML"),
+(status = 400, description = "Unknown charset", body=AppError),
+(status = 413, description = "Document exceeds the maximum input size", body=AppError),
+(status = 422, description = "Document exceeds a conversion limit", body=AppError),
+(status = 500, description = "Conversion failed", body=AppError)
+)
+)]
+pub async fn diff(Extension(limits): Extension<Limits>, TypedMultipart(DiffRequest { old_file, new_file, charset }): TypedMultipart<DiffRequest>,
+) -> AppResult<(StatusCode, Json<serde_json::Value>)> {
+    let options = ConvertOptions {
+        charset: parse_charset(charset.as_deref())?,
+        ..ConvertOptions::new(limits)
+    };
+    let diff = tokio::task::spawn_blocking(move || {
+        // Past the conversion budget, the diff is coarser rather than aborted.
+        let deadline = Instant::now() + options.limits.max_duration();
+        let read = |file: &FieldData<Bytes>| {
+            let tokens = to_text::tokenize(file.contents.as_ref(), &options.limits)?;
+            to_text::read_document(&tokens, &options)
+        };
+        let old = read(&old_file)?;
+        let new = read(&new_file)?;
+        Ok::<_, anyhow::Error>(diff::diff_bodies(&old.body, &new.body, Some(deadline)))
+    })
+    .await
+    .map_err(anyhow::Error::from)?
+    .map_err(conversion_error)?;
+
+    let html = diff::diff_html(&diff);
+    Ok((StatusCode::OK, Json(json!({ "diff": diff, "html": html }))))
+}
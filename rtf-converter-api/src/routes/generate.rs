@@ -0,0 +1,77 @@
+//! Routes generating RTF from plain text, Markdown and HTML.
+use crate::{
+    error::AppResult,
+    rtf::{
+        error::{Limit, LimitExceeded},
+        from_html, from_markdown, from_text,
+    },
+    settings::Limits,
+};
+use axum::{
+    http::{header, StatusCode},
+    Extension,
+};
+
+/// Response carrying a generated RTF document.
+type RtfResponse = (StatusCode, [(header::HeaderName, &'static str); 1], String);
+
+/// Converts `source` with `to_rtf`, rejecting sources over the input size limit.
+async fn generate(
+    limits: Limits,
+    source: String,
+    to_rtf: fn(&str, &Limits) -> Result<String, LimitExceeded>,
+) -> AppResult<RtfResponse> {
+    if source.len() > limits.max_input_bytes {
+        return Err(LimitExceeded::new(Limit::InputBytes, limits.max_input_bytes).into());
+    }
+    let rtf = tokio::task::spawn_blocking(move || to_rtf(&source, &limits))
+        .await
+        .map_err(anyhow::Error::from)??;
+    Ok((StatusCode::OK, [(header::CONTENT_TYPE, "application/rtf")], rtf))
+}
+
+/// Generates an RTF document from plain text.
+#[utoipa::path(
+post,
+path = "/generate/text",
+request_body(content = String, description = "Plain text, one paragraph per line", content_type = "text/plain"),
+responses(
+(status = 200, description = "RTF document", content_type = "application/rtf", body = String),
+(status = 413, description = "Source exceeds the maximum input size", body=AppError),
+(status = 500, description = "Generation failed", body=AppError)
+)
+)]
+pub async fn text(Extension(limits): Extension<Limits>, source: String) -> AppResult<RtfResponse> {
+    generate(limits, source, |source, _| Ok(from_text::to_rtf(source))).await
+}
+
+/// Generates an RTF document from Markdown.
+#[utoipa::path(
+post,
+path = "/generate/markdown",
+request_body(content = String, description = "CommonMark source, with tables and strikethrough", content_type = "text/markdown"),
+responses(
+(status = 200, description = "RTF document", content_type = "application/rtf", body = String),
+(status = 413, description = "Source exceeds the maximum input size", body=AppError),
+(status = 500, description = "Generation failed", body=AppError)
+)
+)]
+pub async fn markdown(Extension(limits): Extension<Limits>, source: String) -> AppResult<RtfResponse> {
+    generate(limits, source, |source, _| Ok(from_markdown::to_rtf(source))).await
+}
+
+/// Generates an RTF document from HTML.
+#[utoipa::path(
+post,
+path = "/generate/html",
+request_body(content = String, description = "HTML document or fragment; images must use data: URLs", content_type = "text/html"),
+responses(
+(status = 200, description = "RTF document", content_type = "application/rtf", body = String),
+(status = 413, description = "Source exceeds the maximum input size", body=AppError),
+(status = 422, description = "Elements nested deeper than the maximum group depth", body=AppError),
+(status = 500, description = "Generation failed", body=AppError)
+)
+)]
+pub async fn html(Extension(limits): Extension<Limits>, source: String) -> AppResult<RtfResponse> {
+    generate(limits, source, from_html::to_rtf).await
+}
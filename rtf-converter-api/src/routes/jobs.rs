@@ -0,0 +1,249 @@
+//! Asynchronous conversion routes.
+use crate::{
+    error::{AppError, AppResult},
+    jobs::{convert::enqueue, webhook::{check_callback, notify}},
+    models::job::{JobStatus, NewConversionJob},
+    repositories::{job::JobRepo, RepoExt},
+    routes::convert::{parse_charset, parse_format},
+    rtf::error::{Limit, LimitExceeded},
+    settings::{Jobs, Limits, Webhooks},
+};
+use axum::{
+    body::Bytes,
+    extract::Path,
+    http::{header, StatusCode},
+    Extension, Json,
+};
+use axum_typed_multipart::{FieldData, TryFromMultipart, TypedMultipart};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Document to convert asynchronously, with its conversion and callback
+/// options.
+#[derive(TryFromMultipart, ToSchema)]
+pub struct ConvertJobRequest {
+    #[schema(value_type = String, format = Binary)]
+    rtf_file: FieldData<Bytes>,
+    /// Encoding label (e.g. `windows-1251`) overriding the document codepage.
+    charset: Option<String>,
+    /// Output format: `text` (default), `html`, `pdf` or `segments`.
+    format: Option<String>,
+    /// Time budget of each attempt in milliseconds, capped by the server.
+    timeout_ms: Option<u64>,
+    /// `http` or `https` URL posted, with the job status, when the job is
+    /// over; its host must resolve to public addresses.
+    callback_url: Option<String>,
+    /// Key of the HMAC-SHA256 signature of the callbacks, required with
+    /// `callback_url`.
+    callback_secret: Option<String>,
+    /// Whether callbacks embed the result, base64-encoded, as `output`.
+    callback_output: Option<bool>,
+}
+
+/// Checks the callback of a job request, returning its URL.
+async fn callback(url: Option<String>, secret: Option<&str>, webhooks: &Webhooks) -> AppResult<Option<String>> {
+    let Some(url) = url else {
+        return Ok(None);
+    };
+    let parsed = url::Url::parse(url.trim())
+        .ok()
+        .filter(|parsed| matches!(parsed.scheme(), "http" | "https"))
+        .ok_or_else(|| AppError::new(StatusCode::BAD_REQUEST, Some(format!("invalid callback_url: {url}"))))?;
+    if secret.is_none_or(str::is_empty) {
+        return Err(AppError::new(StatusCode::BAD_REQUEST, Some("callback_secret is required with callback_url")));
+    }
+    check_callback(&parsed, webhooks.allow_private_hosts)
+        .await
+        .map_err(|err| AppError::new(StatusCode::BAD_REQUEST, Some(format!("invalid callback_url: {err}"))))?;
+    Ok(Some(parsed.to_string()))
+}
+
+fn job_not_found(id: Uuid) -> AppError {
+    AppError::new(StatusCode::NOT_FOUND, Some(format!("Conversion job {id} not found")))
+}
+
+/// Queues the conversion of a document, answering with the job identifier
+/// and its `Location`.
+#[utoipa::path(
+post,
+path = "/jobs/convert",
+request_body(content = ConvertJobRequest, description = "RTF file content", content_type = "multipart/form-data"),
+responses(
+(status = 202, description = "Identifier of the queued job, with its Location"),
+(status = 400, description = "Unknown charset or format, or invalid callback, such as one to a private address", body=AppError),
+(status = 413, description = "Document exceeds the maximum input size", body=AppError),
+(status = 500, description = "The job could not be queued", body=AppError)
+)
+)]
+pub async fn create(Extension(repo): RepoExt, Extension(limits): Extension<Limits>, Extension(jobs): Extension<Jobs>, Extension(webhooks): Extension<Webhooks>,
+                    TypedMultipart(ConvertJobRequest { rtf_file, charset, format, timeout_ms, callback_url, callback_secret, callback_output }): TypedMultipart<ConvertJobRequest>)
+    -> AppResult<(StatusCode, [(header::HeaderName, String); 1], Json<serde_json::Value>)> {
+    if rtf_file.contents.len() > limits.max_input_bytes {
+        return Err(LimitExceeded::new(Limit::InputBytes, limits.max_input_bytes).into());
+    }
+    // Rejected now rather than failing the job.
+    parse_charset(charset.as_deref())?;
+    let format = parse_format(format.as_deref())?.key().to_string();
+    let timeout_ms = timeout_ms.unwrap_or(jobs.timeout_ms).min(jobs.timeout_ms);
+    let callback_url = callback(callback_url, callback_secret.as_deref(), &webhooks).await?;
+
+    let job = NewConversionJob {
+        format,
+        charset,
+        timeout_ms: timeout_ms as i64,
+        max_attempts: jobs.max_attempts.max(1),
+        input: rtf_file.contents.to_vec(),
+        callback_secret: callback_url.as_ref().and(callback_secret),
+        callback_include_output: callback_output.unwrap_or(false),
+        callback_url,
+    };
+    let id = enqueue(&repo.job, &repo.queue, &job, &jobs).await
+        .map_err(|error| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, Some(error)))?;
+    Ok((
+        StatusCode::ACCEPTED,
+        [(header::LOCATION, format!("/jobs/{id}"))],
+        Json(json!({ "id": id, "status": JobStatus::Queued })),
+    ))
+}
+
+/// Status, progress and attempts of a conversion job.
+#[utoipa::path(
+get,
+path = "/jobs/{id}",
+params(("id" = Uuid, Path, description = "Conversion job identifier")),
+responses(
+(status = 200, description = "Status, progress and attempts of the job"),
+(status = 404, description = "Job not found", body=AppError),
+(status = 500, description = "Lookup failed", body=AppError)
+)
+)]
+pub async fn status(Extension(repo): RepoExt, Path(id): Path<Uuid>)
+    -> AppResult<(StatusCode, Json<serde_json::Value>)> {
+    let job = repo.job.find(id).await
+        .map_err(|error| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, Some(error)))?
+        .ok_or_else(|| job_not_found(id))?;
+    Ok((StatusCode::OK, Json(json!(job))))
+}
+
+/// Converted document of a succeeded job.
+#[utoipa::path(
+get,
+path = "/jobs/{id}/result",
+params(("id" = Uuid, Path, description = "Conversion job identifier")),
+responses(
+(status = 200, description = "Converted document, with its content type"),
+(status = 404, description = "Job not found", body=AppError),
+(status = 409, description = "Job not succeeded (yet)", body=AppError),
+(status = 500, description = "Lookup failed", body=AppError)
+)
+)]
+pub async fn result(Extension(repo): RepoExt, Path(id): Path<Uuid>)
+    -> AppResult<(StatusCode, [(header::HeaderName, String); 1], Vec<u8>)> {
+    let job = repo.job.find_result(id).await
+        .map_err(|error| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, Some(error)))?
+        .ok_or_else(|| job_not_found(id))?;
+    match (job.content_type, job.result) {
+        (Some(content_type), Some(result)) if job.status == JobStatus::Succeeded.as_str() => {
+            Ok((StatusCode::OK, [(header::CONTENT_TYPE, content_type)], result))
+        }
+        _ => Err(AppError::new(
+            StatusCode::CONFLICT,
+            Some(format!("Conversion job {id} is {}, without result", job.status)),
+        )),
+    }
+}
+
+/// Callback deliveries of a job, with their HTTP attempts.
+#[utoipa::path(
+get,
+path = "/jobs/{id}/deliveries",
+params(("id" = Uuid, Path, description = "Conversion job identifier")),
+responses(
+(status = 200, description = "Callback deliveries of the job, latest first, with their HTTP attempts"),
+(status = 404, description = "Job not found", body=AppError),
+(status = 500, description = "Lookup failed", body=AppError)
+)
+)]
+pub async fn deliveries(Extension(repo): RepoExt, Path(id): Path<Uuid>)
+    -> AppResult<(StatusCode, Json<serde_json::Value>)> {
+    repo.job.find(id).await
+        .map_err(|error| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, Some(error)))?
+        .ok_or_else(|| job_not_found(id))?;
+    let deliveries = repo.job.find_deliveries(id).await
+        .map_err(|error| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, Some(error)))?;
+    Ok((StatusCode::OK, Json(json!({ "results": deliveries }))))
+}
+
+/// Queues a new delivery of the callback of a job which is over.
+#[utoipa::path(
+post,
+path = "/jobs/{id}/deliveries",
+params(("id" = Uuid, Path, description = "Conversion job identifier")),
+responses(
+(status = 202, description = "Identifier of the queued delivery, replaying the callback of the job"),
+(status = 404, description = "Job not found", body=AppError),
+(status = 409, description = "Job not over, or without callback", body=AppError),
+(status = 500, description = "The delivery could not be queued", body=AppError)
+)
+)]
+pub async fn replay(Extension(repo): RepoExt, Path(id): Path<Uuid>)
+    -> AppResult<(StatusCode, Json<serde_json::Value>)> {
+    let job = repo.job.find(id).await
+        .map_err(|error| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, Some(error)))?
+        .ok_or_else(|| job_not_found(id))?;
+    if job.finished_at.is_none() || job.callback_url.is_none() {
+        return Err(AppError::new(
+            StatusCode::CONFLICT,
+            Some(format!("Conversion job {id} is {}, without callback to replay", job.status)),
+        ));
+    }
+    let delivery = notify(&repo.queue, &repo.job, id).await
+        .map_err(|error| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, Some(error)))?
+        .ok_or_else(|| AppError::new(StatusCode::CONFLICT, Some(format!("Conversion job {id} has no callback"))))?;
+    Ok((StatusCode::ACCEPTED, Json(json!({ "id": delivery, "job_id": id }))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::IntoResponse;
+
+    async fn checked(url: &str, secret: Option<&str>, allow_private_hosts: bool) -> Result<Option<String>, StatusCode> {
+        let webhooks = Webhooks {
+            allow_private_hosts,
+            ..Webhooks::default()
+        };
+        callback(Some(url.to_string()), secret, &webhooks).await.map_err(|err| err.into_response().status())
+    }
+
+    #[tokio::test]
+    async fn callback_checks() {
+        assert_eq!(callback(None, None, &Webhooks::default()).await.ok(), Some(None));
+        for url in [
+            "ftp://93.184.216.34/",
+            "http://127.0.0.1:8080/",
+            "http://10.1.2.3/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://0.0.0.0/",
+            "http://[::1]/",
+            "http://[::ffff:192.168.0.1]/",
+            "http://100.64.0.1/",
+            "http://198.18.0.1/",
+            "http://224.0.0.1/",
+            "http://0.1.2.3/",
+            "http://[64:ff9b::a00:1]/",
+            "http://[2002:a00:1::]/",
+            "http://[::10.0.0.1]/",
+            "http://localhost/",
+        ] {
+            assert_eq!(checked(url, Some("s"), false).await, Err(StatusCode::BAD_REQUEST), "{url}");
+        }
+        assert_eq!(checked("https://93.184.216.34/hook", None, false).await, Err(StatusCode::BAD_REQUEST));
+        assert_eq!(
+            checked("https://93.184.216.34/hook", Some("s"), false).await,
+            Ok(Some("https://93.184.216.34/hook".to_string()))
+        );
+        assert_eq!(checked("http://127.0.0.1:8080/", Some("s"), true).await, Ok(Some("http://127.0.0.1:8080/".to_string())));
+    }
+}
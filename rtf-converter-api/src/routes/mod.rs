@@ -2,6 +2,12 @@
 
 pub mod fallback;
 pub mod health;
+pub mod analyze;
 pub mod convert;
+pub mod diff;
+pub mod generate;
+pub mod jobs;
+pub mod normalize;
+pub mod sanitize;
 pub mod template;
 
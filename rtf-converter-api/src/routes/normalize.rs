@@ -0,0 +1,58 @@
+//! RTF normalization route.
+use crate::{
+    error::AppResult,
+    routes::convert::conversion_error,
+    rtf::{
+        normalize::{self, NormalizeOptions},
+        to_text,
+    },
+    settings::Limits,
+};
+use axum::{body::Bytes, http::StatusCode, Extension, Json};
+use axum_typed_multipart::{FieldData, TryFromMultipart, TypedMultipart};
+use base64::Engine;
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(TryFromMultipart, IntoParams, ToSchema)]
+pub struct NormalizeRequest {
+    #[schema(value_type = String, format = Binary)]
+    rtf_file: FieldData<Bytes>,
+    /// Pictures wider or taller than this many pixels are downsampled to fit.
+    max_image_dimension: Option<u32>,
+}
+
+#[utoipa::path(
+post,
+path = "/normalize",
+request_body(content = NormalizeRequest, description = "RTF file content", content_type = "multipart/form-data"),
+responses(
+(status = 200, description = "Normalized RTF (base64-encoded), with the bytes saved and what was removed"),
+(status = 413, description = "Document exceeds the maximum input size", body=AppError),
+(status = 422, description = "Document exceeds a conversion limit", body=AppError),
+(status = 500, description = "Normalization failed", body=AppError)
+)
+)]
+pub async fn normalize(Extension(limits): Extension<Limits>, TypedMultipart(NormalizeRequest { rtf_file, max_image_dimension }): TypedMultipart<NormalizeRequest>,
+) -> AppResult<(StatusCode, Json<serde_json::Value>)> {
+    let options = NormalizeOptions { max_image_dimension, limits };
+    let input_bytes = rtf_file.contents.len();
+    let (rtf, report) = tokio::task::spawn_blocking(move || {
+        let tokens = to_text::tokenize(rtf_file.contents.as_ref(), &options.limits)?;
+        Ok::<_, anyhow::Error>(normalize::normalize(&tokens, &options)?)
+    })
+    .await
+    .map_err(anyhow::Error::from)?
+    .map_err(conversion_error)?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "rtf": base64::engine::general_purpose::STANDARD.encode(&rtf),
+            "input_bytes": input_bytes,
+            "output_bytes": rtf.len(),
+            "bytes_saved": input_bytes.saturating_sub(rtf.len()),
+            "report": report,
+        })),
+    ))
+}
@@ -0,0 +1,45 @@
+//! RTF sanitization route.
+use crate::{
+    error::AppResult,
+    routes::convert::conversion_error,
+    rtf::{sanitize, to_text},
+    settings::Limits,
+};
+use axum::{body::Bytes, http::StatusCode, Extension, Json};
+use axum_typed_multipart::{FieldData, TryFromMultipart, TypedMultipart};
+use base64::Engine;
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(TryFromMultipart, IntoParams, ToSchema)]
+pub struct SanitizeRequest {
+    #[schema(value_type = String, format = Binary)]
+    rtf_file: FieldData<Bytes>,
+}
+
+#[utoipa::path(
+post,
+path = "/sanitize",
+request_body(content = SanitizeRequest, description = "RTF file content", content_type = "multipart/form-data"),
+responses(
+(status = 200, description = "Sanitized RTF (base64-encoded), with the content removed and the exploit patterns found"),
+(status = 413, description = "Document exceeds the maximum input size", body=AppError),
+(status = 422, description = "Document exceeds a conversion limit", body=AppError),
+(status = 500, description = "Sanitization failed", body=AppError)
+)
+)]
+pub async fn sanitize(Extension(limits): Extension<Limits>, TypedMultipart(SanitizeRequest { rtf_file }): TypedMultipart<SanitizeRequest>,
+) -> AppResult<(StatusCode, Json<serde_json::Value>)> {
+    let (rtf, report) = tokio::task::spawn_blocking(move || {
+        let tokens = to_text::tokenize(rtf_file.contents.as_ref(), &limits)?;
+        Ok::<_, anyhow::Error>(sanitize::sanitize(&tokens, &limits)?)
+    })
+    .await
+    .map_err(anyhow::Error::from)?
+    .map_err(conversion_error)?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({ "rtf": base64::engine::general_purpose::STANDARD.encode(&rtf), "report": report })),
+    ))
+}
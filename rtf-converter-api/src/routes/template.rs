@@ -1,21 +1,30 @@
-use std::ops::Add;
 use humantime::Duration;
 use async_trait::async_trait;
 use crate::error::{AppError, AppResult};
-use axum::{body::Bytes as BodyBytes, Extension, http::StatusCode, Json};
-use axum::extract::multipart::Field;
+use axum::{body::Bytes as BodyBytes, Extension, http::{header, StatusCode}, Json};
+use axum::extract::{multipart::Field, Path, Query};
 use axum_macros::{debug_handler, FromRequestParts};
 use serde_json::json;
-use serde;
+use serde::{self, Deserialize};
 use axum_typed_multipart::{FieldData, TryFromField, TryFromMultipart, TypedMultipart, TypedMultipartError};
 use chrono::{DateTime, Utc};
 use derive_more::{From, Into};
 use utoipa::{ToSchema, IntoParams};
 use uuid::Uuid;
+use validator::Validate;
 
-use crate::models::file::{FileIdentifier, NewFile};
+use crate::extract::if_match::{etag, IfMatch};
+use crate::models::file::{is_expired, FileConditions, FileIdentifier, FileSearch, FileUpdate, NewFile, VersionSelector, VersionedWrite};
 use crate::repositories::{RepoExt, RepoImpls};
 use crate::repositories::file::FileRepo;
+use crate::routes::convert::{conversion_error, convert_document, parse_format, Converted};
+use crate::models::file::FileContent;
+use crate::rtf::engine::{find_engine, TemplateEngine};
+use crate::rtf::{options::ConvertOptions, schema, to_text};
+use crate::settings::Limits;
+
+/// Number of templates listed or search results returned when no limit is given.
+const DEFAULT_LIST_LIMIT: i64 = 20;
 
 #[derive(ToSchema, From, Into)]
 pub struct ReqUuid(Uuid);
@@ -68,7 +77,20 @@ pub struct TemplateUploadRequest {
     tenant_id: Option<ReqUuid>,
     owner_id: Option<ReqUuid>,
     max_age: Option<ReqHumanDuration>,
+    /// Templating engine: `placeholder` (default), `handlebars` or `mergefield`.
+    templating_engine: Option<String>,
+    /// Version of the templating engine, the latest when omitted.
+    templating_engine_version: Option<i32>,
+}
+
+#[derive(TryFromMultipart, ToSchema)]
+pub struct TemplateVersionRequest {
+    #[schema(value_type = String, format = Binary)]
+    file: FieldData<BodyBytes>,
+    /// Templating engine, that of the latest version when omitted.
     templating_engine: Option<String>,
+    /// Version of the templating engine: that of the latest version when the engine is omitted, the latest
+    /// version of the engine otherwise.
     templating_engine_version: Option<i32>,
 }
 
@@ -77,47 +99,887 @@ pub struct TemplateUploadResponse {
     id: FileIdentifier
 }
 
+#[derive(Deserialize, IntoParams)]
+pub struct TemplateListParams {
+    /// Search terms, in web search syntax (`"exact phrase"`, `or`, `-excluded`): returns the matching
+    /// templates, best first, instead of listing them.
+    q: Option<String>,
+    /// Tenant owning the templates; templates without a tenant when omitted.
+    tenant_id: Option<Uuid>,
+    /// Owner of the templates.
+    owner_id: Option<Uuid>,
+    /// Part of the file name, case-insensitive.
+    file_name: Option<String>,
+    /// Templating engine of the templates.
+    templating_engine: Option<String>,
+    /// Maximum number of results, from 1 to 100 (default 20).
+    limit: Option<i64>,
+    /// Number of results skipped (default 0).
+    offset: Option<i64>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TemplateUpdateRequest {
+    /// New file name.
+    file_name: Option<String>,
+    /// New owner.
+    owner_id: Option<Uuid>,
+    /// New retention, from now, as a duration such as `30days` or `1year`.
+    max_age: Option<String>,
+    /// Keeps the template past its retention, until lifted.
+    legal_hold: Option<bool>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TemplateRenderRequest {
+    /// Values of the template's placeholders or fields; names are dotted paths into this object.
+    /// Values are written as text, except pictures, `{"type": "image", "data": "<base64 PNG or JPEG>",
+    /// "width": 120, "height": 40}` (sizes in pixels, optional), and rich-text fragments,
+    /// `{"type": "rtf" | "html" | "markdown", "content": "..."}`.
+    #[schema(value_type = Object)]
+    data: serde_json::Value,
+    /// Output format: `rtf` (default), `text`, `html`, `pdf` or `segments`.
+    format: Option<String>,
+    /// Rejects the request when placeholders have no value, instead of leaving them empty.
+    #[serde(default)]
+    strict: bool,
+}
+
+/// Response carrying a rendered document.
+type RenderResponse = (StatusCode, [(header::HeaderName, &'static str); 1], Vec<u8>);
+
+/// Response carrying a stored file, with its content type, file name and ETag.
+type ContentResponse = (StatusCode, [(header::HeaderName, String); 3], Vec<u8>);
+
+/// JSON response about a version of a template, with its ETag.
+type VersionResponse = (StatusCode, [(header::HeaderName, String); 1], Json<serde_json::Value>);
+
+/// Error of a repository call.
+fn repo_error(error: anyhow::Error) -> AppError {
+    AppError::new(StatusCode::INTERNAL_SERVER_ERROR, Some(error))
+}
+
+fn template_not_found(id: Uuid) -> AppError {
+    AppError::new(StatusCode::NOT_FOUND, Some(format!("Template {id} not found")))
+}
+
+fn version_not_found(id: Uuid, version: VersionSelector) -> AppError {
+    match version {
+        VersionSelector::Number(version) => {
+            AppError::new(StatusCode::NOT_FOUND, Some(format!("Template {id} has no version {version}")))
+        }
+        _ => template_not_found(id),
+    }
+}
+
+/// Expiry date of a template kept for `age` from now.
+fn expiry_date(age: std::time::Duration) -> AppResult<DateTime<Utc>> {
+    chrono::Duration::from_std(age)
+        .ok()
+        .and_then(|age| Utc::now().checked_add_signed(age))
+        .ok_or_else(|| AppError::new(StatusCode::BAD_REQUEST, Some("max_age: duration too long")))
+}
+
+/// Rejects reads of a template past its `max_age`, until it is purged.
+fn check_expiry(id: Uuid, max_age: Option<DateTime<Utc>>, legal_hold: bool) -> AppResult<()> {
+    if is_expired(max_age, legal_hold) {
+        return Err(AppError::new(StatusCode::GONE, Some(format!("Template {id} has expired"))));
+    }
+    Ok(())
+}
+
+/// Result of a write guarded by an `If-Match` header.
+fn versioned<T>(id: Uuid, outcome: VersionedWrite<T>, not_found: AppError) -> AppResult<T> {
+    match outcome {
+        VersionedWrite::Done(value) => Ok(value),
+        VersionedWrite::NotFound => Err(not_found),
+        VersionedWrite::VersionMismatch { latest } => Err(AppError::new(
+            StatusCode::PRECONDITION_FAILED,
+            Some(format!("Template {id} is at version {latest}")),
+        )),
+        VersionedWrite::Conflict => Err(AppError::new(
+            StatusCode::CONFLICT,
+            Some(format!("Template {id} was changed concurrently")),
+        )),
+        VersionedWrite::Held => Err(AppError::new(
+            StatusCode::CONFLICT,
+            Some(format!("Template {id} is under legal hold")),
+        )),
+    }
+}
+
+fn tagged(status: StatusCode, version: i32, body: serde_json::Value) -> VersionResponse {
+    (status, [(header::ETAG, etag(version))], Json(body))
+}
+
+/// `Content-Disposition` of a download named `file_name`, keeping its
+/// printable ASCII characters.
+fn attachment(file_name: Option<&str>) -> String {
+    let name: String = file_name
+        .unwrap_or("template.rtf")
+        .chars()
+        .map(|c| if c == ' ' || (c.is_ascii_graphic() && c != '"' && c != '\\') { c } else { '_' })
+        .collect();
+    format!("attachment; filename=\"{name}\"")
+}
+
+/// Fields of a template, checking that it compiles with `engine`.
+async fn template_fields(contents: BodyBytes, limits: Limits, engine: &'static dyn TemplateEngine)
+    -> AppResult<Vec<schema::TemplateField>> {
+    tokio::task::spawn_blocking(move || {
+        let tokens = to_text::tokenize(contents.as_ref(), &limits)?;
+        engine.compile(&tokens, &limits)?;
+        Ok::<_, anyhow::Error>(engine.fields(&tokens, &limits)?)
+    })
+    .await
+    .map_err(anyhow::Error::from)?
+    .map_err(conversion_error)
+}
+
+/// Engine a template was stored with.
+fn stored_engine(name: Option<&str>, version: Option<&str>) -> AppResult<&'static dyn TemplateEngine> {
+    let version = match version.map(str::trim) {
+        None | Some("") => None,
+        Some(version) => Some(version.parse::<i32>().map_err(|_| {
+            AppError::new(StatusCode::INTERNAL_SERVER_ERROR, Some(format!("invalid templating engine version {version:?}")))
+        })?),
+    };
+    Ok(find_engine(name, version)?)
+}
+
+/// `version` of the template `id`, with its engine.
+async fn find_template(repo: &RepoImpls, id: Uuid, version: VersionSelector)
+    -> AppResult<(FileContent, &'static dyn TemplateEngine)> {
+    let stored = repo.file.find_content(id, version).await.map_err(repo_error)?
+        .ok_or_else(|| version_not_found(id, version))?;
+    check_expiry(id, stored.max_age, stored.legal_hold)?;
+    let engine = stored_engine(stored.templating_engine.as_deref(), stored.templating_engine_version.as_deref())?;
+    Ok((stored, engine))
+}
+
+/// New version of a template from an uploaded `file`, checking that it
+/// compiles with `engine`; it has no tenant, owner or retention.
+async fn new_file(file: FieldData<BodyBytes>, limits: Limits, engine: &'static dyn TemplateEngine) -> AppResult<NewFile> {
+    let file_contents = file.contents;
+    let fields = template_fields(file_contents.clone(), limits.clone(), engine).await?;
+    let file_size = file_contents.len() as i64;
+    let extracted_text = extract_text(file_contents.clone(), limits).await;
+
+    Ok(NewFile {
+        file_name: file.metadata.file_name.unwrap_or_default(),
+        content_type: file.metadata.content_type.unwrap_or_default(),
+        file_binary_content: Vec::from(file_contents),
+        file_size,
+        owner_id: None,
+        max_age: None,
+        tenant_id: None,
+        insertion_date: Some(chrono::offset::Utc::now()),
+        templating_engine: Some(engine.name().to_string()),
+        templating_engine_version: Some(engine.version()),
+        extracted_text,
+        field_schema: Some(schema::json_schema(&fields)),
+    })
+}
+
+/// Plain text of an uploaded file, indexed for search.
+///
+/// Files which do not convert are stored without text rather than rejected.
+pub(crate) async fn extract_text(contents: BodyBytes, limits: Limits) -> Option<String> {
+    let result = tokio::task::spawn_blocking(move || {
+        let options = ConvertOptions::new(limits);
+        let tokens = to_text::tokenize(contents.as_ref(), &options.limits)?;
+        let document = to_text::read_document(&tokens, &options)?;
+        Ok::<_, anyhow::Error>(to_text::body_text(&document.body))
+    })
+    .await;
+    match result {
+        Ok(Ok(text)) => Some(text),
+        Ok(Err(error)) => {
+            tracing::warn!("Text extraction failed: {:#}", error);
+            None
+        }
+        Err(error) => {
+            tracing::warn!("Text extraction panicked: {}", error);
+            None
+        }
+    }
+}
+
 //#[debug_handler]
 #[utoipa::path(
 post,
 path = "/template",
 request_body(content = TemplateUploadRequest, description = "RTF file content", content_type = "multipart/form-data"),
 responses(
-(status = 200, description = "Conversion successful"),
+(status = 200, description = "Identifier of the template, at version 1, with its ETag"),
+(status = 400, description = "Unknown templating engine or version, or retention too long", body=AppError),
+(status = 413, description = "Template exceeds the maximum input size", body=AppError),
+(status = 422, description = "Template does not compile with its engine", body=AppError),
 (status = 500, description = "Conversion failed", body=AppError)
 )
 )]
-pub async fn upload(Extension(repo): RepoExt, TypedMultipart(
+pub async fn upload(Extension(repo): RepoExt, Extension(limits): Extension<Limits>, TypedMultipart(
                         TemplateUploadRequest {file, tenant_id, templating_engine, templating_engine_version, owner_id, max_age }): TypedMultipart<TemplateUploadRequest>)
-    -> AppResult<(StatusCode, Json<serde_json::Value>)> {
+    -> AppResult<VersionResponse> {
     let repo = repo.clone();
 
-    let age = if let Some(age) = max_age {
-        let date = chrono::Duration::from_std(age.0.into()).expect("Overflow datetime");
-        Some(Utc::now().add(date))
-    } else {
-        None
-    };
+    let age = max_age.map(|age| expiry_date(age.0.into())).transpose()?;
 
-    let file_contents = file.contents;
-    let file_size = file_contents.len() as i64;
+    let engine = find_engine(templating_engine.as_deref(), templating_engine_version)?;
+    let file = new_file(file, limits, engine).await?;
 
     let result = repo.file.add(&NewFile {
-        file_name: file.metadata.file_name.clone().unwrap(),
-        content_type: file.metadata.content_type.unwrap(),
-        file_binary_content: Vec::from(file_contents),
-        file_size,
         owner_id: if let Some(owner) = owner_id { Some(owner.0) } else { None },
         max_age: age,
         tenant_id: if let Some(tenant) = tenant_id {Some(tenant.0)} else { None },
-        insertion_date: Some(chrono::offset::Utc::now()),
-        templating_engine,
-        templating_engine_version,
+        ..file
     }).await;
 
     match result {
-        Ok(result) => Ok((StatusCode::OK, Json(json!(FileIdentifier{id: result.id, version: result.version })))),
+        Ok(result) => Ok(tagged(StatusCode::OK, result.version, json!(FileIdentifier{id: result.id, version: result.version }))),
         Err(error) => Err(AppError::new(StatusCode::INTERNAL_SERVER_ERROR, Some(error)))
     }
 
-}
\ No newline at end of file
+}
+
+#[utoipa::path(
+post,
+path = "/template/{id}/versions",
+params(("id" = Uuid, Path, description = "Template identifier"),
+       ("If-Match" = Option<String>, Header, description = "ETag of the latest version, failing the request when another version was added since")),
+request_body(content = TemplateVersionRequest, description = "RTF file content", content_type = "multipart/form-data"),
+responses(
+(status = 201, description = "Identifier of the new version, with its ETag"),
+(status = 400, description = "Unknown templating engine or version, or invalid If-Match", body=AppError),
+(status = 404, description = "Template not found", body=AppError),
+(status = 410, description = "Template past its retention, not yet purged", body=AppError),
+(status = 412, description = "Latest version not matching If-Match", body=AppError),
+(status = 413, description = "Template exceeds the maximum input size", body=AppError),
+(status = 422, description = "Template does not compile with its engine", body=AppError),
+(status = 500, description = "Insertion failed", body=AppError)
+)
+)]
+pub async fn create_version(Extension(repo): RepoExt, Extension(limits): Extension<Limits>, Path(id): Path<Uuid>,
+                            IfMatch(expected): IfMatch,
+                            TypedMultipart(TemplateVersionRequest { file, templating_engine, templating_engine_version }): TypedMultipart<TemplateVersionRequest>)
+    -> AppResult<VersionResponse> {
+    let latest = repo.file.find_by_id(id).await.map_err(repo_error)?.ok_or_else(|| template_not_found(id))?;
+    check_expiry(id, latest.max_age, latest.legal_hold)?;
+    let engine = match templating_engine {
+        Some(name) => find_engine(Some(name.as_str()), templating_engine_version)?,
+        None if templating_engine_version.is_some() => find_engine(latest.templating_engine.as_deref(), templating_engine_version)?,
+        None => stored_engine(latest.templating_engine.as_deref(), latest.templating_engine_version.as_deref())?,
+    };
+    let file = new_file(file, limits, engine).await?;
+    let outcome = repo.file.add_version(id, expected, &file).await.map_err(repo_error)?;
+    let created = versioned(id, outcome, template_not_found(id))?;
+    Ok(tagged(StatusCode::CREATED, created.version, json!(created)))
+}
+
+#[utoipa::path(
+get,
+path = "/template/{id}/versions",
+params(("id" = Uuid, Path, description = "Template identifier")),
+responses(
+(status = 200, description = "Metadata of the versions of the template, latest first"),
+(status = 404, description = "Template not found", body=AppError),
+(status = 410, description = "Template past its retention, not yet purged", body=AppError),
+(status = 500, description = "Lookup failed", body=AppError)
+)
+)]
+pub async fn versions(Extension(repo): RepoExt, Path(id): Path<Uuid>)
+    -> AppResult<(StatusCode, Json<serde_json::Value>)> {
+    let versions = repo.file.find_versions(id).await.map_err(repo_error)?;
+    let Some(latest) = versions.first() else {
+        return Err(template_not_found(id));
+    };
+    check_expiry(id, latest.max_age, latest.legal_hold)?;
+    Ok((StatusCode::OK, Json(json!({ "results": versions }))))
+}
+
+#[utoipa::path(
+get,
+path = "/template/{id}/versions/{version}",
+params(("id" = Uuid, Path, description = "Template identifier"), ("version" = i32, Path, description = "Version number")),
+responses(
+(status = 200, description = "Metadata of the version, with its ETag"),
+(status = 404, description = "Template or version not found", body=AppError),
+(status = 410, description = "Template past its retention, not yet purged", body=AppError),
+(status = 500, description = "Lookup failed", body=AppError)
+)
+)]
+pub async fn version_metadata(Extension(repo): RepoExt, Path((id, version)): Path<(Uuid, i32)>)
+    -> AppResult<VersionResponse> {
+    let file = repo.file.find_version(id, version).await.map_err(repo_error)?
+        .ok_or_else(|| version_not_found(id, VersionSelector::Number(version)))?;
+    check_expiry(id, file.max_age, file.legal_hold)?;
+    Ok(tagged(StatusCode::OK, version, json!(file)))
+}
+
+#[utoipa::path(
+get,
+path = "/template/{id}/versions/{version}/content",
+params(("id" = Uuid, Path, description = "Template identifier"), ("version" = i32, Path, description = "Version number")),
+responses(
+(status = 200, description = "The version, as uploaded, with its original content type"),
+(status = 404, description = "Template or version not found", body=AppError),
+(status = 410, description = "Template past its retention, not yet purged", body=AppError),
+(status = 500, description = "Lookup failed", body=AppError)
+)
+)]
+pub async fn version_content(Extension(repo): RepoExt, Path((id, version)): Path<(Uuid, i32)>)
+    -> AppResult<ContentResponse> {
+    stored_content(&repo.file, id, VersionSelector::Number(version)).await
+}
+
+#[utoipa::path(
+post,
+path = "/template/{id}/versions/{version}/publish",
+params(("id" = Uuid, Path, description = "Template identifier"), ("version" = i32, Path, description = "Version number"),
+       ("If-Match" = Option<String>, Header, description = "ETag of the latest version, failing the request when another version was added since")),
+responses(
+(status = 200, description = "Metadata of the version, now rendered when no version is requested"),
+(status = 400, description = "Invalid If-Match", body=AppError),
+(status = 404, description = "Template or version not found", body=AppError),
+(status = 409, description = "Another version published concurrently", body=AppError),
+(status = 410, description = "Template past its retention, not yet purged", body=AppError),
+(status = 412, description = "Latest version not matching If-Match", body=AppError),
+(status = 500, description = "Publication failed", body=AppError)
+)
+)]
+pub async fn publish(Extension(repo): RepoExt, Path((id, version)): Path<(Uuid, i32)>, IfMatch(expected): IfMatch)
+    -> AppResult<VersionResponse> {
+    publish_version(&repo.file, id, expected, version).await
+}
+
+/// Publishes `version` of the template `id`.
+async fn publish_version(files: &impl FileRepo, id: Uuid, expected: Option<i32>, version: i32)
+    -> AppResult<VersionResponse> {
+    let latest = files.find_by_id(id).await.map_err(repo_error)?.ok_or_else(|| template_not_found(id))?;
+    check_expiry(id, latest.max_age, latest.legal_hold)?;
+    let outcome = files.publish(id, expected, version).await.map_err(repo_error)?;
+    let file = versioned(id, outcome, version_not_found(id, VersionSelector::Number(version)))?;
+    Ok(tagged(StatusCode::OK, version, json!(file)))
+}
+
+#[utoipa::path(
+post,
+path = "/template/{id}/versions/{version}/rollback",
+params(("id" = Uuid, Path, description = "Template identifier"), ("version" = i32, Path, description = "Version to restore"),
+       ("If-Match" = Option<String>, Header, description = "ETag of the latest version, failing the request when another version was added since")),
+responses(
+(status = 201, description = "Identifier of the new latest version, a copy of the restored one, with its ETag"),
+(status = 400, description = "Invalid If-Match", body=AppError),
+(status = 404, description = "Template or version not found", body=AppError),
+(status = 410, description = "Template past its retention, not yet purged", body=AppError),
+(status = 412, description = "Latest version not matching If-Match", body=AppError),
+(status = 500, description = "Insertion failed", body=AppError)
+)
+)]
+pub async fn rollback(Extension(repo): RepoExt, Path((id, version)): Path<(Uuid, i32)>, IfMatch(expected): IfMatch)
+    -> AppResult<VersionResponse> {
+    rollback_version(&repo.file, id, expected, version).await
+}
+
+/// Copies `version` of the template `id` as its new latest version.
+async fn rollback_version(files: &impl FileRepo, id: Uuid, expected: Option<i32>, version: i32)
+    -> AppResult<VersionResponse> {
+    let latest = files.find_by_id(id).await.map_err(repo_error)?.ok_or_else(|| template_not_found(id))?;
+    check_expiry(id, latest.max_age, latest.legal_hold)?;
+    let outcome = files.rollback(id, expected, version).await.map_err(repo_error)?;
+    let created = versioned(id, outcome, version_not_found(id, VersionSelector::Number(version)))?;
+    Ok(tagged(StatusCode::CREATED, created.version, json!(created)))
+}
+
+#[utoipa::path(
+post,
+path = "/template/{id}/render",
+params(("id" = Uuid, Path, description = "Template identifier")),
+request_body(content = TemplateRenderRequest, description = "Placeholder values and output format", content_type = "application/json"),
+responses(
+(status = 200, description = "Rendered document from the published version, or the latest when none is, as RTF or in the requested format"),
+(status = 400, description = "Unknown format", body=AppError),
+(status = 404, description = "Template not found", body=AppError),
+(status = 410, description = "Template past its retention, not yet purged", body=AppError),
+(status = 413, description = "Template exceeds the maximum input size", body=AppError),
+(status = 422, description = "Data not matching the template fields, placeholders without a value in strict mode, template not compiling, or a conversion limit exceeded", body=AppError),
+(status = 500, description = "Rendering failed", body=AppError)
+)
+)]
+pub async fn render(Extension(repo): RepoExt, Extension(limits): Extension<Limits>, Path(id): Path<Uuid>,
+                    Json(request): Json<TemplateRenderRequest>)
+    -> AppResult<RenderResponse> {
+    render_template(&repo, limits, id, VersionSelector::Published, request).await
+}
+
+#[utoipa::path(
+post,
+path = "/template/{id}/versions/{version}/render",
+params(("id" = Uuid, Path, description = "Template identifier"), ("version" = i32, Path, description = "Version number")),
+request_body(content = TemplateRenderRequest, description = "Placeholder values and output format", content_type = "application/json"),
+responses(
+(status = 200, description = "Rendered document, as RTF or in the requested format"),
+(status = 400, description = "Unknown format", body=AppError),
+(status = 404, description = "Template or version not found", body=AppError),
+(status = 410, description = "Template past its retention, not yet purged", body=AppError),
+(status = 413, description = "Template exceeds the maximum input size", body=AppError),
+(status = 422, description = "Data not matching the template fields, placeholders without a value in strict mode, template not compiling, or a conversion limit exceeded", body=AppError),
+(status = 500, description = "Rendering failed", body=AppError)
+)
+)]
+pub async fn render_version(Extension(repo): RepoExt, Extension(limits): Extension<Limits>, Path((id, version)): Path<(Uuid, i32)>,
+                            Json(request): Json<TemplateRenderRequest>)
+    -> AppResult<RenderResponse> {
+    render_template(&repo, limits, id, VersionSelector::Number(version), request).await
+}
+
+/// Renders `version` of the template `id`.
+async fn render_template(repo: &RepoImpls, limits: Limits, id: Uuid, version: VersionSelector,
+                         TemplateRenderRequest { data, format, strict }: TemplateRenderRequest)
+    -> AppResult<RenderResponse> {
+    let format = match format.as_deref().map(str::trim) {
+        None | Some("rtf") => None,
+        Some(format) => Some(parse_format(Some(format))?),
+    };
+    let (stored, engine) = find_template(repo, id, version).await?;
+    if let Some(field_schema) = &stored.field_schema {
+        let errors = schema::validate(field_schema, &data);
+        if !errors.is_empty() {
+            return Err(AppError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Some(format!("data does not match the template fields: {}", errors.join("; "))),
+            ));
+        }
+    }
+    let contents = stored.file_binary_content.unwrap_or_default();
+
+    let options = ConvertOptions::new(limits);
+    let (rtf, report) = tokio::task::spawn_blocking({
+        let options = options.clone();
+        move || {
+            let tokens = to_text::tokenize(contents.as_slice(), &options.limits)?;
+            Ok::<_, anyhow::Error>(engine.render(&tokens, &data, &options.limits)?)
+        }
+    })
+    .await
+    .map_err(anyhow::Error::from)?
+    .map_err(conversion_error)?;
+
+    if strict && !report.missing.is_empty() {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Some(format!("no value for placeholders: {}", report.missing.join(", "))),
+        ));
+    }
+    let Some(format) = format else {
+        return Ok((StatusCode::OK, [(header::CONTENT_TYPE, "application/rtf")], rtf));
+    };
+    let Converted { content_type, output, .. } = tokio::task::spawn_blocking(move || convert_document(&rtf, format, &options))
+        .await
+        .map_err(anyhow::Error::from)?
+        .map_err(conversion_error)?;
+    Ok((StatusCode::OK, [(header::CONTENT_TYPE, content_type)], output))
+}
+
+#[utoipa::path(
+get,
+path = "/template/{id}/fields",
+params(("id" = Uuid, Path, description = "Template identifier")),
+responses(
+(status = 200, description = "Placeholders and merge fields of the published version of the template, or the latest when none is, with their locations and types, and the JSON Schema of its data"),
+(status = 404, description = "Template not found", body=AppError),
+(status = 410, description = "Template past its retention, not yet purged", body=AppError),
+(status = 413, description = "Template exceeds the maximum input size", body=AppError),
+(status = 422, description = "Template does not compile with its engine", body=AppError),
+(status = 500, description = "Parsing failed", body=AppError)
+)
+)]
+pub async fn fields(Extension(repo): RepoExt, Extension(limits): Extension<Limits>, Path(id): Path<Uuid>)
+    -> AppResult<(StatusCode, Json<serde_json::Value>)> {
+    let (stored, engine) = find_template(&repo, id, VersionSelector::Published).await?;
+    let contents = BodyBytes::from(stored.file_binary_content.unwrap_or_default());
+    let fields = template_fields(contents, limits, engine).await?;
+    let field_schema = schema::json_schema(&fields);
+    Ok((StatusCode::OK, Json(json!({
+        "templating_engine": engine.name(),
+        "templating_engine_version": engine.version(),
+        "fields": fields,
+        "schema": field_schema,
+    }))))
+}
+
+#[utoipa::path(
+get,
+path = "/templates",
+params(TemplateListParams),
+responses(
+(status = 200, description = "Latest version of the templates, newest first; or, with `q`, the matching templates, best first, with highlighted snippets; with the total count"),
+(status = 400, description = "Empty query, or limit or offset out of range", body=AppError),
+(status = 500, description = "Listing failed", body=AppError)
+)
+)]
+pub async fn list(Extension(repo): RepoExt, Query(params): Query<TemplateListParams>)
+    -> AppResult<(StatusCode, Json<serde_json::Value>)> {
+    list_templates(&repo.file, params).await
+}
+
+/// Page of the templates matching `params`, with their total count.
+async fn list_templates(files: &impl FileRepo, params: TemplateListParams)
+    -> AppResult<(StatusCode, Json<serde_json::Value>)> {
+    let limit = params.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+    let offset = params.offset.unwrap_or(0);
+    let conditions = FileConditions {
+        file_name: params.file_name,
+        tenant_id: params.tenant_id,
+        owner_id: params.owner_id,
+        templating_engine: params.templating_engine,
+        limit,
+        offset,
+    };
+    if let Some(q) = params.q {
+        let search = FileSearch {
+            query: q.trim().to_string(),
+            conditions,
+        };
+        if let Err(errors) = search.validate() {
+            return Err(AppError::new(StatusCode::BAD_REQUEST, Some(errors)));
+        }
+        let hits = files.search(&search).await.map_err(repo_error)?;
+        let total = files.count_search(&search).await.map_err(repo_error)?;
+        return Ok((StatusCode::OK, Json(json!({ "results": hits, "total": total, "limit": limit, "offset": offset }))));
+    }
+
+    if let Err(errors) = conditions.validate() {
+        return Err(AppError::new(StatusCode::BAD_REQUEST, Some(errors)));
+    }
+    let found = files.find_all(&conditions).await.map_err(repo_error)?;
+    let total = files.count(&conditions).await.map_err(repo_error)?;
+    Ok((StatusCode::OK, Json(json!({ "results": found, "total": total, "limit": limit, "offset": offset }))))
+}
+
+#[utoipa::path(
+get,
+path = "/template/{id}",
+params(("id" = Uuid, Path, description = "Template identifier")),
+responses(
+(status = 200, description = "Metadata of the latest version of the template, with its ETag"),
+(status = 404, description = "Template not found", body=AppError),
+(status = 410, description = "Template past its retention, not yet purged", body=AppError),
+(status = 500, description = "Lookup failed", body=AppError)
+)
+)]
+pub async fn metadata(Extension(repo): RepoExt, Path(id): Path<Uuid>)
+    -> AppResult<VersionResponse> {
+    template_metadata(&repo.file, id).await
+}
+
+/// Metadata of the latest version of the template `id`.
+async fn template_metadata(files: &impl FileRepo, id: Uuid) -> AppResult<VersionResponse> {
+    let file = files.find_by_id(id).await.map_err(repo_error)?.ok_or_else(|| template_not_found(id))?;
+    check_expiry(id, file.max_age, file.legal_hold)?;
+    Ok(tagged(StatusCode::OK, file.id.version, json!(file)))
+}
+
+#[utoipa::path(
+get,
+path = "/template/{id}/content",
+params(("id" = Uuid, Path, description = "Template identifier")),
+responses(
+(status = 200, description = "Latest version of the template, as uploaded, with its original content type and its ETag"),
+(status = 404, description = "Template not found", body=AppError),
+(status = 410, description = "Template past its retention, not yet purged", body=AppError),
+(status = 500, description = "Lookup failed", body=AppError)
+)
+)]
+pub async fn content(Extension(repo): RepoExt, Path(id): Path<Uuid>) -> AppResult<ContentResponse> {
+    stored_content(&repo.file, id, VersionSelector::Latest).await
+}
+
+/// Download of `version` of the template `id`.
+async fn stored_content(files: &impl FileRepo, id: Uuid, version: VersionSelector) -> AppResult<ContentResponse> {
+    let stored = files.find_content(id, version).await.map_err(repo_error)?
+        .ok_or_else(|| version_not_found(id, version))?;
+    check_expiry(id, stored.max_age, stored.legal_hold)?;
+    let content_type = stored.content_type.filter(|content_type| !content_type.is_empty())
+        .unwrap_or_else(|| "application/rtf".to_string());
+    let disposition = attachment(stored.file_name.as_deref());
+    Ok((
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, content_type), (header::CONTENT_DISPOSITION, disposition), (header::ETAG, etag(stored.version))],
+        stored.file_binary_content.unwrap_or_default(),
+    ))
+}
+
+#[utoipa::path(
+put,
+path = "/template/{id}",
+params(("id" = Uuid, Path, description = "Template identifier"),
+       ("If-Match" = Option<String>, Header, description = "ETag of the latest version, failing the request when another version was added since")),
+request_body(content = TemplateUpdateRequest, description = "Metadata to change, in all versions; omitted fields are kept", content_type = "application/json"),
+responses(
+(status = 200, description = "Updated metadata of the latest version of the template, with its ETag"),
+(status = 400, description = "Invalid file name, retention or If-Match", body=AppError),
+(status = 404, description = "Template not found", body=AppError),
+(status = 412, description = "Latest version not matching If-Match", body=AppError),
+(status = 500, description = "Update failed", body=AppError)
+)
+)]
+pub async fn update(Extension(repo): RepoExt, Path(id): Path<Uuid>, IfMatch(expected): IfMatch,
+                    Json(request): Json<TemplateUpdateRequest>)
+    -> AppResult<VersionResponse> {
+    update_template(&repo.file, id, expected, request).await
+}
+
+/// Changes the metadata of all versions of the template `id`.
+async fn update_template(files: &impl FileRepo, id: Uuid, expected: Option<i32>,
+                         TemplateUpdateRequest { file_name, owner_id, max_age, legal_hold }: TemplateUpdateRequest)
+    -> AppResult<VersionResponse> {
+    let max_age = match max_age {
+        Some(age) => {
+            let age = age.trim().parse::<humantime::Duration>()
+                .map_err(|e| AppError::new(StatusCode::BAD_REQUEST, Some(format!("max_age: {e}"))))?;
+            Some(expiry_date(age.into())?)
+        }
+        None => None,
+    };
+    let changes = FileUpdate { file_name, owner_id, max_age, legal_hold };
+    if let Err(errors) = changes.validate() {
+        return Err(AppError::new(StatusCode::BAD_REQUEST, Some(errors)));
+    }
+    let outcome = files.update(id, expected, &changes).await.map_err(repo_error)?;
+    let file = versioned(id, outcome, template_not_found(id))?;
+    Ok(tagged(StatusCode::OK, file.id.version, json!(file)))
+}
+
+#[utoipa::path(
+delete,
+path = "/template/{id}",
+params(("id" = Uuid, Path, description = "Template identifier"),
+       ("If-Match" = Option<String>, Header, description = "ETag of the latest version, failing the request when another version was added since")),
+responses(
+(status = 204, description = "Template deleted, with all its versions"),
+(status = 400, description = "Invalid If-Match", body=AppError),
+(status = 404, description = "Template not found", body=AppError),
+(status = 409, description = "Template under legal hold", body=AppError),
+(status = 412, description = "Latest version not matching If-Match", body=AppError),
+(status = 500, description = "Deletion failed", body=AppError)
+)
+)]
+pub async fn delete(Extension(repo): RepoExt, Path(id): Path<Uuid>, IfMatch(expected): IfMatch) -> AppResult<StatusCode> {
+    delete_template(&repo.file, id, expected).await
+}
+
+/// Deletes the template `id`, with all its versions.
+async fn delete_template(files: &impl FileRepo, id: Uuid, expected: Option<i32>) -> AppResult<StatusCode> {
+    let outcome = files.delete(id, expected).await.map_err(repo_error)?;
+    versioned(id, outcome, template_not_found(id))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::IntoResponse;
+    use crate::models::file::{File, FileSearchHit};
+    use crate::repositories::file::MockFileRepo;
+
+    fn file(id: Uuid, max_age: Option<DateTime<Utc>>) -> File {
+        File {
+            id: FileIdentifier { id, version: 2 },
+            tenant_id: None,
+            owner_id: None,
+            content_type: None,
+            file_name: Some("letter.rtf".to_string()),
+            file_size: Some(10),
+            insertion_date: None,
+            max_age,
+            templating_engine: None,
+            templating_engine_version: None,
+            published: false,
+            legal_hold: false,
+        }
+    }
+
+    fn status<T>(result: AppResult<T>) -> StatusCode {
+        match result {
+            Ok(_) => StatusCode::OK,
+            Err(err) => err.into_response().status(),
+        }
+    }
+
+    fn update_request(max_age: Option<&str>) -> TemplateUpdateRequest {
+        TemplateUpdateRequest {
+            file_name: None,
+            owner_id: None,
+            max_age: max_age.map(str::to_string),
+            legal_hold: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn metadata_not_found_and_expired() {
+        let expired = Uuid::new_v4();
+        let mut files = MockFileRepo::new();
+        files.expect_find_by_id().returning(move |id| {
+            Ok((id == expired).then(|| file(id, Some(Utc::now() - chrono::Duration::days(1)))))
+        });
+        assert_eq!(status(template_metadata(&files, Uuid::new_v4()).await), StatusCode::NOT_FOUND);
+        assert_eq!(status(template_metadata(&files, expired).await), StatusCode::GONE);
+    }
+
+    #[tokio::test]
+    async fn content_expired() {
+        let mut files = MockFileRepo::new();
+        files.expect_find_content().returning(|id, _| {
+            Ok(Some(FileContent {
+                id,
+                version: 1,
+                content_type: None,
+                file_name: None,
+                file_binary_content: Some(b"{\\rtf1}".to_vec()),
+                templating_engine: None,
+                templating_engine_version: None,
+                field_schema: None,
+                max_age: Some(Utc::now() - chrono::Duration::days(1)),
+                legal_hold: false,
+            }))
+        });
+        assert_eq!(status(stored_content(&files, Uuid::new_v4(), VersionSelector::Latest).await), StatusCode::GONE);
+    }
+
+    #[tokio::test]
+    async fn update_retention() {
+        let id = Uuid::new_v4();
+        let mut files = MockFileRepo::new();
+        files.expect_update()
+            .withf(|_, expected, changes| {
+                *expected == Some(2)
+                    && changes.max_age.is_some_and(|max_age| max_age > Utc::now() + chrono::Duration::days(29))
+            })
+            .times(1)
+            .returning(|id, _, changes| Ok(VersionedWrite::Done(file(id, changes.max_age))));
+        let (status, headers, _) = update_template(&files, id, Some(2), update_request(Some("30days"))).await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(headers[0].1, etag(2));
+    }
+
+    #[tokio::test]
+    async fn update_errors() {
+        let mut files = MockFileRepo::new();
+        files.expect_update().returning(|_, expected, _| {
+            Ok(match expected {
+                Some(latest) => VersionedWrite::VersionMismatch { latest: latest + 1 },
+                None => VersionedWrite::NotFound,
+            })
+        });
+        let id = Uuid::new_v4();
+        assert_eq!(status(update_template(&files, id, None, update_request(None)).await), StatusCode::NOT_FOUND);
+        assert_eq!(
+            status(update_template(&files, id, Some(1), update_request(None)).await),
+            StatusCode::PRECONDITION_FAILED
+        );
+        // Rejected before the repository is called.
+        for max_age in ["soon", "300000years"] {
+            assert_eq!(
+                status(update_template(&files, id, None, update_request(Some(max_age))).await),
+                StatusCode::BAD_REQUEST
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn publish_and_rollback() {
+        let id = Uuid::new_v4();
+        let mut files = MockFileRepo::new();
+        files.expect_find_by_id().returning(|id| Ok(Some(file(id, None))));
+        files.expect_publish().returning(|id, expected, version| {
+            Ok(match (expected, version) {
+                (Some(latest), _) if latest != 2 => VersionedWrite::VersionMismatch { latest: 2 },
+                (_, 3..) => VersionedWrite::NotFound,
+                (_, 0) => VersionedWrite::Conflict,
+                _ => VersionedWrite::Done(file(id, None)),
+            })
+        });
+        files.expect_rollback().returning(|id, _, version| Ok(VersionedWrite::Done(FileIdentifier { id, version: version + 2 })));
+        assert_eq!(status(publish_version(&files, id, Some(2), 1).await), StatusCode::OK);
+        assert_eq!(status(publish_version(&files, id, Some(1), 1).await), StatusCode::PRECONDITION_FAILED);
+        assert_eq!(status(publish_version(&files, id, None, 5).await), StatusCode::NOT_FOUND);
+        assert_eq!(status(publish_version(&files, id, None, 0).await), StatusCode::CONFLICT);
+        let (status, headers, _) = rollback_version(&files, id, None, 1).await.unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(headers[0].1, etag(3));
+    }
+
+    #[tokio::test]
+    async fn publish_and_rollback_expired() {
+        let mut files = MockFileRepo::new();
+        files.expect_find_by_id().returning(|id| Ok(Some(file(id, Some(Utc::now() - chrono::Duration::days(1))))));
+        files.expect_publish().never();
+        files.expect_rollback().never();
+        let id = Uuid::new_v4();
+        assert_eq!(status(publish_version(&files, id, None, 1).await), StatusCode::GONE);
+        assert_eq!(status(rollback_version(&files, id, None, 1).await), StatusCode::GONE);
+    }
+
+    fn params(q: Option<&str>) -> TemplateListParams {
+        TemplateListParams {
+            q: q.map(str::to_string),
+            tenant_id: None,
+            owner_id: Some(Uuid::nil()),
+            file_name: Some("report".to_string()),
+            templating_engine: Some("handlebars".to_string()),
+            limit: Some(10),
+            offset: None,
+        }
+    }
+
+    fn filtered(conditions: &FileConditions) -> bool {
+        conditions.owner_id == Some(Uuid::nil())
+            && conditions.file_name.as_deref() == Some("report")
+            && conditions.templating_engine.as_deref() == Some("handlebars")
+            && (conditions.limit, conditions.offset) == (10, 0)
+    }
+
+    #[tokio::test]
+    async fn search_filters_and_total() {
+        let mut files = MockFileRepo::new();
+        files.expect_search()
+            .withf(|search| search.query == "invoice" && filtered(&search.conditions))
+            .times(1)
+            .returning(|_| Ok(vec![FileSearchHit { id: Uuid::nil(), version: 1, file_name: None, rank: 0.5, snippet: String::new() }]));
+        files.expect_count_search().withf(|search| filtered(&search.conditions)).times(1).returning(|_| Ok(42));
+        files.expect_find_all().never();
+        let (_, Json(body)) = list_templates(&files, params(Some(" invoice "))).await.unwrap();
+        assert_eq!(body["results"].as_array().map(Vec::len), Some(1));
+        assert_eq!((body["total"].clone(), body["limit"].clone(), body["offset"].clone()), (json!(42), json!(10), json!(0)));
+
+        assert_eq!(status(list_templates(&files, params(Some("  "))).await), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn list_filters_and_total() {
+        let mut files = MockFileRepo::new();
+        files.expect_find_all().withf(filtered).times(1).returning(|_| Ok(vec![]));
+        files.expect_count().withf(filtered).times(1).returning(|_| Ok(7));
+        files.expect_search().never();
+        let (_, Json(body)) = list_templates(&files, params(None)).await.unwrap();
+        assert_eq!(body["total"], json!(7));
+    }
+
+    #[tokio::test]
+    async fn delete_held() {
+        let held = Uuid::new_v4();
+        let mut files = MockFileRepo::new();
+        files.expect_delete().returning(move |id, _| {
+            Ok(if id == held { VersionedWrite::Held } else { VersionedWrite::Done(()) })
+        });
+        assert_eq!(status(delete_template(&files, held, None).await), StatusCode::CONFLICT);
+        assert_eq!(delete_template(&files, Uuid::new_v4(), None).await.ok(), Some(StatusCode::NO_CONTENT));
+    }
+
+    #[test]
+    fn test_expiry_date() {
+        assert!(expiry_date(std::time::Duration::from_secs(60)).is_ok());
+        assert!(expiry_date(std::time::Duration::MAX).is_err());
+    }
+}
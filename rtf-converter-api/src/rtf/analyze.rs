@@ -0,0 +1,500 @@
+//! Content statistics and readability of a document.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+use rtf_grimoire::tokenizer::Token;
+use serde::Serialize;
+
+use super::document::{Body, Layout, SectionBreak};
+use super::normalize::{destination, parse, subgroup, Node, COLOR_REFERENCES};
+use super::options::ConvertOptions;
+use super::sanitize::field_instruction;
+use super::to_text::read_document;
+
+/// Destinations holding no body text.
+const NON_TEXT_DESTINATIONS: &[&str] = &[
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "info",
+    "pict",
+    "object",
+    "listtable",
+    "listoverridetable",
+];
+
+/// Readability of the extracted text.
+///
+/// The Flesch formulas were calibrated on English; syllables are estimated
+/// from vowel groups, so scores for other languages are only indicative.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Readability {
+    /// Sentences, ended by `.`, `!` or `?`.
+    pub sentences: usize,
+    /// Estimated syllables.
+    pub syllables: usize,
+    /// Average words per sentence.
+    pub words_per_sentence: f64,
+    /// Flesch reading ease, higher is easier (0-100 for most texts).
+    pub flesch_reading_ease: f64,
+    /// Flesch-Kincaid grade level.
+    pub flesch_kincaid_grade: f64,
+}
+
+/// Share of the text written in one language.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LanguageShare {
+    /// BCP-47 tag of the language, `None` for text without one.
+    pub language: Option<&'static str>,
+    /// Characters in the language, whitespace excluded.
+    pub characters: usize,
+    /// Fraction of the characters of the document (0-1).
+    pub share: f64,
+}
+
+/// Statistics of a document.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Analysis {
+    /// Whitespace-separated words.
+    pub words: usize,
+    /// Characters, line breaks excluded.
+    pub characters: usize,
+    /// Characters, whitespace excluded.
+    pub characters_no_spaces: usize,
+    /// Paragraphs (`\par`).
+    pub paragraphs: usize,
+    /// Non-empty lines, ended by paragraphs, line breaks and table rows.
+    pub lines: usize,
+    /// Pages, from page breaks and the sections starting on a new page.
+    pub pages: usize,
+    /// Pages recorded by the editor in `\info` (`\nofpages`).
+    pub declared_pages: Option<i32>,
+    /// BCP-47 tags of the languages of the text.
+    pub languages: Vec<&'static str>,
+    /// Characters per language, most used first.
+    pub language_mix: Vec<LanguageShare>,
+    /// Names of the fonts applied to text.
+    pub fonts: Vec<String>,
+    /// Colors applied to text or its background, as `#rrggbb`.
+    pub colors: Vec<String>,
+    /// Pictures (`\pict`), fallback copies excluded.
+    pub images: usize,
+    /// Tables.
+    pub tables: usize,
+    /// Table rows.
+    pub table_rows: usize,
+    /// Fields, by type (e.g. `HYPERLINK`).
+    pub fields: BTreeMap<String, usize>,
+    /// Readability scores, when the document has text.
+    pub readability: Option<Readability>,
+    /// Page setup and sections.
+    pub layout: Layout,
+}
+
+/// Counts gathered while walking the body text.
+#[derive(Debug, Default)]
+struct BodyWalk {
+    paragraphs: usize,
+    page_breaks: usize,
+    fonts: BTreeSet<i32>,
+    colors: BTreeSet<i32>,
+    tables: usize,
+    table_rows: usize,
+    in_paragraph_table: bool,
+    in_table: bool,
+}
+
+impl BodyWalk {
+    fn start_table_paragraph(&mut self) {
+        if !self.in_table {
+            self.tables += 1;
+            self.in_table = true;
+        }
+    }
+
+    fn walk(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            let (name, arg) = match node {
+                Node::Group(group) => {
+                    let skipped = matches!(group.first(), Some(Node::Token(Token::ControlSymbol('*'))))
+                        || destination(group).is_some_and(|name| NON_TEXT_DESTINATIONS.contains(&name));
+                    if !skipped {
+                        self.walk(group);
+                    }
+                    continue;
+                }
+                node => match node.word() {
+                    Some(word) => word,
+                    None => continue,
+                },
+            };
+            match name {
+                "par" => {
+                    self.paragraphs += 1;
+                    // A paragraph outside of a table ends the current one.
+                    self.in_table &= self.in_paragraph_table;
+                }
+                "page" => self.page_breaks += 1,
+                "intbl" => {
+                    self.in_paragraph_table = true;
+                    self.start_table_paragraph();
+                }
+                "trowd" => self.start_table_paragraph(),
+                "pard" => self.in_paragraph_table = false,
+                "row" => self.table_rows += 1,
+                "f" | "af" => {
+                    self.fonts.insert(arg.unwrap_or(0));
+                }
+                name if COLOR_REFERENCES.contains(&name) => {
+                    self.colors.insert(arg.unwrap_or(0));
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Counts gathered over the whole document, including non-text destinations.
+#[derive(Debug, Default)]
+struct DocumentWalk {
+    declared_pages: Option<i32>,
+    default_font: Option<i32>,
+    images: usize,
+    fields: BTreeMap<String, usize>,
+}
+
+impl DocumentWalk {
+    fn walk(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            let group = match node {
+                Node::Group(group) => group,
+                node => {
+                    match node.word() {
+                        Some(("nofpages", arg)) => self.declared_pages = arg,
+                        Some(("deff", arg)) => self.default_font = arg,
+                        _ => (),
+                    }
+                    continue;
+                }
+            };
+            match destination(group) {
+                // Picture written for readers without support for the one in `\shppict`.
+                Some("nonshppict") => continue,
+                Some("pict") => self.images += 1,
+                Some("field") => {
+                    let instruction = field_instruction(group).unwrap_or_default();
+                    let kind = instruction
+                        .split_whitespace()
+                        .next()
+                        .unwrap_or_default()
+                        .to_ascii_uppercase();
+                    *self.fields.entry(kind).or_default() += 1;
+                }
+                _ => (),
+            }
+            self.walk(group);
+        }
+    }
+}
+
+/// Font names by font number, from the font table.
+fn font_names(document: &[Node]) -> BTreeMap<i32, String> {
+    let mut names = BTreeMap::new();
+    let fonttbl = document
+        .iter()
+        .filter_map(|node| match node {
+            Node::Group(rtf) => subgroup(rtf, "fonttbl"),
+            Node::Token(_) => None,
+        })
+        .next();
+    for entry in fonttbl.into_iter().flatten() {
+        let Node::Group(entry) = entry else {
+            continue;
+        };
+        let number = entry.iter().find_map(|node| match node.word() {
+            Some(("f", arg)) => Some(arg.unwrap_or(0)),
+            _ => None,
+        });
+        // The name is the text of the entry itself, not of its subgroups.
+        let name: Vec<u8> = entry
+            .iter()
+            .filter_map(|node| match node {
+                Node::Token(Token::Text(text)) => Some(text.as_slice()),
+                _ => None,
+            })
+            .flatten()
+            .copied()
+            .take_while(|&byte| byte != b';')
+            .collect();
+        if let Some(number) = number {
+            names.insert(number, String::from_utf8_lossy(&name).trim().to_string());
+        }
+    }
+    names
+}
+
+/// Colors of the color table, as `#rrggbb`; `None` for "auto" entries.
+fn color_table(document: &[Node]) -> Vec<Option<String>> {
+    let colortbl = document
+        .iter()
+        .filter_map(|node| match node {
+            Node::Group(rtf) => subgroup(rtf, "colortbl"),
+            Node::Token(_) => None,
+        })
+        .next();
+    let mut colors = Vec::new();
+    let mut rgb: [Option<i32>; 3] = [None; 3];
+    for node in colortbl.into_iter().flatten() {
+        match node {
+            Node::Token(Token::Text(text)) => {
+                for _ in text.iter().filter(|&&byte| byte == b';') {
+                    colors.push(match rgb {
+                        [None, None, None] => None,
+                        [red, green, blue] => Some(format!(
+                            "#{:02x}{:02x}{:02x}",
+                            red.unwrap_or(0) & 0xFF,
+                            green.unwrap_or(0) & 0xFF,
+                            blue.unwrap_or(0) & 0xFF
+                        )),
+                    });
+                    rgb = [None; 3];
+                }
+            }
+            node => match node.word() {
+                Some(("red", arg)) => rgb[0] = arg,
+                Some(("green", arg)) => rgb[1] = arg,
+                Some(("blue", arg)) => rgb[2] = arg,
+                _ => (),
+            },
+        }
+    }
+    colors
+}
+
+/// Syllables of `word`, estimated from its vowel groups.
+fn syllables(word: &str) -> usize {
+    let word = word.to_lowercase();
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for c in word.chars() {
+        let vowel = matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+        if vowel && !previous_vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+    // Silent final e, as in "make".
+    if count > 1 && word.ends_with('e') && !word.ends_with("le") {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+fn round(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+/// Readability of `text`, `None` when it has no words.
+pub fn readability(text: &str) -> Option<Readability> {
+    let words: Vec<&str> = text
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    let sentences = text
+        .split(['.', '!', '?'])
+        .filter(|sentence| sentence.chars().any(char::is_alphanumeric))
+        .count()
+        .max(1);
+    let syllables: usize = words.iter().map(|word| syllables(word)).sum();
+
+    let words_per_sentence = words.len() as f64 / sentences as f64;
+    let syllables_per_word = syllables as f64 / words.len() as f64;
+    Some(Readability {
+        sentences,
+        syllables,
+        words_per_sentence: round(words_per_sentence),
+        flesch_reading_ease: round(206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word),
+        flesch_kincaid_grade: round(0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59),
+    })
+}
+
+/// Text of `body`, one line per paragraph and without direction marks.
+fn plain_lines(body: &Body) -> Vec<String> {
+    body.paragraphs
+        .iter()
+        .map(|paragraph| paragraph.runs.iter().map(|run| run.text.as_str()).collect())
+        .collect()
+}
+
+/// Characters of `body` per language, most used first.
+fn language_mix(body: &Body) -> Vec<LanguageShare> {
+    let mut characters: BTreeMap<Option<&'static str>, usize> = BTreeMap::new();
+    for run in body.paragraphs.iter().flat_map(|paragraph| &paragraph.runs) {
+        let count = run.text.chars().filter(|c| !c.is_whitespace()).count();
+        if count > 0 {
+            *characters.entry(run.props.language).or_default() += count;
+        }
+    }
+    let total: usize = characters.values().sum();
+    let mut mix: Vec<LanguageShare> = characters
+        .into_iter()
+        .map(|(language, characters)| LanguageShare {
+            language,
+            characters,
+            share: (characters as f64 / total as f64 * 1000.0).round() / 1000.0,
+        })
+        .collect();
+    mix.sort_by_key(|share| Reverse(share.characters));
+    mix
+}
+
+/// Computes the statistics of a document.
+///
+/// Text statistics are computed on the text [read_document] extracts; the
+/// other counts come from the document structure.
+pub fn analyze(token_stream: &[Token], options: &ConvertOptions) -> Result<Analysis> {
+    let document = read_document(token_stream, options)?;
+    let lines = plain_lines(&document.body);
+    let text = lines.join("\n");
+
+    let tree = parse(token_stream, options.limits.max_group_depth)?;
+    let mut body = BodyWalk::default();
+    body.walk(&tree);
+    let mut whole = DocumentWalk::default();
+    whole.walk(&tree);
+
+    let language_mix = language_mix(&document.body);
+    let mut used_fonts = body.fonts;
+    if !text.trim().is_empty() {
+        // Text without a font of its own is set in the default font.
+        used_fonts.extend(whole.default_font);
+    }
+    let fonts = font_names(&tree);
+    let colors = color_table(&tree);
+    let non_empty_lines = lines.iter().filter(|line| !line.trim().is_empty()).count();
+    // The first section starts the document, whatever its break.
+    let section_pages = document
+        .layout
+        .sections
+        .iter()
+        .skip(1)
+        .filter(|section| !matches!(section.break_kind, SectionBreak::None | SectionBreak::Column))
+        .count();
+
+    Ok(Analysis {
+        words: text.split_whitespace().count(),
+        characters: text.chars().filter(|&c| c != '\n').count(),
+        characters_no_spaces: text.chars().filter(|c| !c.is_whitespace()).count(),
+        paragraphs: if body.paragraphs == 0 && non_empty_lines > 0 {
+            1
+        } else {
+            body.paragraphs
+        },
+        lines: non_empty_lines,
+        pages: body.page_breaks + section_pages + 1,
+        declared_pages: whole.declared_pages,
+        languages: language_mix
+            .iter()
+            .filter_map(|share| share.language)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect(),
+        language_mix,
+        fonts: used_fonts
+            .iter()
+            .filter_map(|number| fonts.get(number).cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect(),
+        colors: body
+            .colors
+            .iter()
+            .filter_map(|&index| colors.get(index as usize).cloned().flatten())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect(),
+        images: whole.images,
+        tables: body.tables,
+        table_rows: body.table_rows,
+        fields: whole.fields,
+        readability: readability(&text),
+        layout: document.layout,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::to_text::tokenize;
+
+    #[test]
+    fn test_analyze() {
+        let rtf = br#"{\rtf1\ansi\deff0\deflang1033{\fonttbl{\f0\fswiss Arial;}{\f1\fmodern{\*\panose 0}Courier New;}}
+{\colortbl;\red255\green0\blue0;\red0\green0\blue255;}
+{\info{\nofpages3}}
+\pard\lang1036 Le chat dort.\par
+\pard{\f1\cf1 Red code} and {\field{\*\fldinst HYPERLINK "https://example.com"}{\fldrslt a link}}.\line Next line.\par
+\page
+\trowd\cellx1000\cellx2000\pard\intbl A\cell B\cell\row
+\trowd\cellx1000\cellx2000\pard\intbl C\cell D\cell\row
+\pard After the table.\par
+{\*\shppict{\pict\pngblip 89}}{\nonshppict{\pict\wmetafile8 01}}\par}"#;
+        let options = ConvertOptions::default();
+        let tokens = tokenize(&rtf[..], &options.limits).unwrap();
+        let analysis = analyze(&tokens, &options).unwrap();
+
+        assert_eq!(analysis.paragraphs, 4);
+        assert_eq!(analysis.pages, 2);
+        assert_eq!(analysis.declared_pages, Some(3));
+        assert_eq!(analysis.languages, ["fr-FR"]);
+        assert_eq!(analysis.language_mix.len(), 1);
+        assert_eq!(analysis.language_mix[0].share, 1.0);
+        assert_eq!(analysis.fonts, ["Arial", "Courier New"]);
+        assert_eq!(analysis.colors, ["#ff0000"]);
+        assert_eq!(analysis.images, 1);
+        assert_eq!(analysis.tables, 1);
+        assert_eq!(analysis.table_rows, 2);
+        assert_eq!(analysis.fields.get("HYPERLINK"), Some(&1));
+        // Field results are not part of the extracted text.
+        assert_eq!(analysis.words, 16);
+        assert_eq!(analysis.characters, 63);
+        assert_eq!(analysis.characters_no_spaces, 49);
+        assert_eq!(analysis.lines, 6);
+        assert_eq!(analysis.readability.map(|readability| readability.sentences), Some(4));
+    }
+
+    #[test]
+    fn test_section_pages() {
+        let rtf = br"{\rtf1\ansi A\par\sect\sectd\sbknone B\par\sect\sectd\sbkcol C\par\sect\sectd D\page E\par
+\sect\sectd\sbkodd F\par}";
+        let options = ConvertOptions::default();
+        let tokens = tokenize(&rtf[..], &options.limits).unwrap();
+        let analysis = analyze(&tokens, &options).unwrap();
+        assert_eq!(analysis.layout.sections.len(), 5);
+        assert_eq!(analysis.pages, 4);
+    }
+
+    #[test]
+    fn test_readability() {
+        let simple = readability("The cat sat on the mat. The dog ran.").unwrap();
+        assert_eq!(simple.sentences, 2);
+        assert_eq!(simple.syllables, 9);
+        assert_eq!(simple.words_per_sentence, 4.5);
+        assert_eq!(simple.flesch_reading_ease, 117.7);
+        assert_eq!(simple.flesch_kincaid_grade, -2.0);
+
+        let hard = readability(
+            "Comprehensive organizational restructuring necessitates considerable administrative deliberation.",
+        )
+        .unwrap();
+        assert!(hard.flesch_reading_ease < simple.flesch_reading_ease);
+        assert!(hard.flesch_kincaid_grade > simple.flesch_kincaid_grade);
+        assert_eq!(readability(" \n "), None);
+    }
+}
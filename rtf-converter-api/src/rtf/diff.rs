@@ -0,0 +1,435 @@
+//! Paragraph and word level comparison of two document bodies.
+//!
+//! Paragraphs are first matched by their text; paragraphs replaced by others
+//! are then compared word by word. Words with the same text but different
+//! [RunProps] are reported as formatting changes.
+//!
+//! Past its deadline, a diff falls back to coarser changes, e.g. a whole
+//! paragraph replaced rather than the words changed in it.
+
+use std::hash::Hash;
+use std::time::Instant;
+
+use serde::Serialize;
+use similar::{capture_diff_deadline, Algorithm, DiffOp, DiffTag};
+
+use super::document::{Body, Paragraph, RunProps};
+use super::to_html::escape_html;
+
+/// Kind of change of a diff segment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    /// Same text and formatting in both documents.
+    Equal,
+    /// Only in the new document.
+    Insert,
+    /// Only in the old document.
+    Delete,
+    /// Same text, different formatting.
+    Format,
+}
+
+/// Stretch of a modified paragraph with a single kind of change.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Segment {
+    /// Kind of change.
+    pub change: Change,
+    /// Text of the segment.
+    pub text: String,
+    /// Formatting in the old document, for formatting changes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_format: Option<RunProps>,
+    /// Formatting in the new document, for formatting changes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_format: Option<RunProps>,
+}
+
+/// Change of a paragraph.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum ParagraphDiff {
+    /// Same text and formatting in both documents.
+    Equal {
+        /// Text of the paragraph.
+        text: String,
+    },
+    /// Paragraph only in the new document.
+    Insert {
+        /// Text of the paragraph.
+        text: String,
+    },
+    /// Paragraph only in the old document.
+    Delete {
+        /// Text of the paragraph.
+        text: String,
+    },
+    /// Paragraph changed between the documents.
+    Modify {
+        /// Word level changes, in order.
+        segments: Vec<Segment>,
+    },
+}
+
+/// Counts of changes, in words (whitespace excluded) and paragraphs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DiffStats {
+    /// Paragraphs only in the new document.
+    pub inserted_paragraphs: usize,
+    /// Paragraphs only in the old document.
+    pub deleted_paragraphs: usize,
+    /// Paragraphs changed between the documents.
+    pub modified_paragraphs: usize,
+    /// Words inserted in modified paragraphs.
+    pub inserted_words: usize,
+    /// Words deleted from modified paragraphs.
+    pub deleted_words: usize,
+    /// Words whose formatting changed.
+    pub reformatted_words: usize,
+}
+
+/// Differences between two document bodies.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DocumentDiff {
+    /// Paragraphs of both documents, in order.
+    pub paragraphs: Vec<ParagraphDiff>,
+    /// Counts of changes.
+    pub stats: DiffStats,
+}
+
+/// Word or whitespace stretch of a paragraph, with its formatting.
+#[derive(Clone, Copy, Debug)]
+struct Word<'a> {
+    text: &'a str,
+    props: RunProps,
+}
+
+impl Word<'_> {
+    fn is_word(&self) -> bool {
+        !self.text.trim().is_empty()
+    }
+}
+
+/// Splits the runs of `paragraph` into words and whitespace.
+fn words(paragraph: &Paragraph) -> Vec<Word<'_>> {
+    let mut words = Vec::new();
+    for run in &paragraph.runs {
+        let mut start = 0;
+        let mut space = None;
+        for (i, c) in run.text.char_indices() {
+            if space == Some(!c.is_whitespace()) {
+                words.push(Word {
+                    text: &run.text[start..i],
+                    props: run.props,
+                });
+                start = i;
+            }
+            space = Some(c.is_whitespace());
+        }
+        if start < run.text.len() {
+            words.push(Word {
+                text: &run.text[start..],
+                props: run.props,
+            });
+        }
+    }
+    words
+}
+
+fn paragraph_text(paragraph: &Paragraph) -> String {
+    paragraph.runs.iter().map(|run| run.text.as_str()).collect()
+}
+
+/// Myers diff of `old` and `new`, coarser past `deadline`.
+fn diff_slices<T: Eq + Hash + Ord>(old: &[T], new: &[T], deadline: Option<Instant>) -> Vec<DiffOp> {
+    capture_diff_deadline(Algorithm::Myers, old, 0..old.len(), new, 0..new.len(), deadline)
+}
+
+/// Appends a segment, merging it with the previous one when they match.
+fn push_segment(
+    segments: &mut Vec<Segment>,
+    change: Change,
+    text: &str,
+    formats: Option<(RunProps, RunProps)>,
+) {
+    let (old_format, new_format) = formats.unzip();
+    if let Some(last) = segments.last_mut() {
+        if last.change == change && last.old_format == old_format && last.new_format == new_format {
+            last.text.push_str(text);
+            return;
+        }
+    }
+    segments.push(Segment {
+        change,
+        text: text.to_string(),
+        old_format,
+        new_format,
+    });
+}
+
+/// Word level diff of two paragraphs, `None` when they are identical.
+fn diff_words(
+    old: &Paragraph,
+    new: &Paragraph,
+    stats: &mut DiffStats,
+    deadline: Option<Instant>,
+) -> Option<Vec<Segment>> {
+    let old_words = words(old);
+    let new_words = words(new);
+    let old_texts: Vec<&str> = old_words.iter().map(|word| word.text).collect();
+    let new_texts: Vec<&str> = new_words.iter().map(|word| word.text).collect();
+
+    let mut segments = Vec::new();
+    let mut changed = false;
+    for op in diff_slices(&old_texts, &new_texts, deadline) {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        match tag {
+            DiffTag::Equal => {
+                for (old_word, new_word) in old_words[old_range].iter().zip(&new_words[new_range]) {
+                    if old_word.props == new_word.props {
+                        push_segment(&mut segments, Change::Equal, new_word.text, None);
+                    } else {
+                        changed = true;
+                        stats.reformatted_words += usize::from(new_word.is_word());
+                        let formats = Some((old_word.props, new_word.props));
+                        push_segment(&mut segments, Change::Format, new_word.text, formats);
+                    }
+                }
+            }
+            DiffTag::Delete | DiffTag::Insert | DiffTag::Replace => {
+                changed = true;
+                for word in &old_words[old_range] {
+                    stats.deleted_words += usize::from(word.is_word());
+                    push_segment(&mut segments, Change::Delete, word.text, None);
+                }
+                for word in &new_words[new_range] {
+                    stats.inserted_words += usize::from(word.is_word());
+                    push_segment(&mut segments, Change::Insert, word.text, None);
+                }
+            }
+        }
+    }
+    changed.then_some(segments)
+}
+
+/// Compares two paragraphs paired by the paragraph level diff.
+fn compare(old: &Paragraph, new: &Paragraph, stats: &mut DiffStats, deadline: Option<Instant>) -> ParagraphDiff {
+    match diff_words(old, new, stats, deadline) {
+        Some(segments) => {
+            stats.modified_paragraphs += 1;
+            ParagraphDiff::Modify { segments }
+        }
+        None => ParagraphDiff::Equal {
+            text: paragraph_text(new),
+        },
+    }
+}
+
+/// Compares two document bodies, coarsely past `deadline`.
+pub fn diff_bodies(old: &Body, new: &Body, deadline: Option<Instant>) -> DocumentDiff {
+    let old_texts: Vec<String> = old.paragraphs.iter().map(paragraph_text).collect();
+    let new_texts: Vec<String> = new.paragraphs.iter().map(paragraph_text).collect();
+
+    let mut diff = DocumentDiff::default();
+    let stats = &mut diff.stats;
+    for op in diff_slices(&old_texts, &new_texts, deadline) {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        let old_paragraphs = &old.paragraphs[old_range.clone()];
+        let new_paragraphs = &new.paragraphs[new_range.clone()];
+        if tag == DiffTag::Equal {
+            for (old_paragraph, new_paragraph) in old_paragraphs.iter().zip(new_paragraphs) {
+                diff.paragraphs.push(compare(old_paragraph, new_paragraph, stats, deadline));
+            }
+            continue;
+        }
+        // Replaced paragraphs are paired in order; the rest were deleted or inserted.
+        let paired = old_paragraphs.len().min(new_paragraphs.len());
+        for (old_paragraph, new_paragraph) in old_paragraphs.iter().zip(new_paragraphs) {
+            diff.paragraphs.push(compare(old_paragraph, new_paragraph, stats, deadline));
+        }
+        for text in &old_texts[old_range][paired..] {
+            stats.deleted_paragraphs += 1;
+            diff.paragraphs.push(ParagraphDiff::Delete { text: text.clone() });
+        }
+        for text in &new_texts[new_range][paired..] {
+            stats.inserted_paragraphs += 1;
+            diff.paragraphs.push(ParagraphDiff::Insert { text: text.clone() });
+        }
+    }
+    diff
+}
+
+/// Describes the formatting differences of a segment, e.g. `bold: false → true`.
+fn format_change(old: &RunProps, new: &RunProps) -> String {
+    let mut changes = Vec::new();
+    if old.bold != new.bold {
+        changes.push(format!("bold: {} → {}", old.bold, new.bold));
+    }
+    if old.italic != new.italic {
+        changes.push(format!("italic: {} → {}", old.italic, new.italic));
+    }
+    if old.underline != new.underline {
+        changes.push(format!("underline: {} → {}", old.underline, new.underline));
+    }
+    if old.color != new.color {
+        changes.push(format!("color: {:?} → {:?}", old.color, new.color));
+    }
+    if old.font != new.font {
+        changes.push(format!("font: {:?} → {:?}", old.font, new.font));
+    }
+    if old.font_size != new.font_size {
+        changes.push(format!("size: {:?} → {:?}", old.font_size, new.font_size));
+    }
+    if old.direction != new.direction {
+        changes.push(format!(
+            "direction: {} → {}",
+            old.direction.as_str(),
+            new.direction.as_str()
+        ));
+    }
+    if old.language != new.language {
+        changes.push(format!(
+            "language: {} → {}",
+            old.language.unwrap_or("none"),
+            new.language.unwrap_or("none")
+        ));
+    }
+    changes.join(", ")
+}
+
+/// Redlined HTML rendering of `diff`.
+///
+/// Insertions are wrapped in `<ins>`, deletions in `<del>` and formatting
+/// changes in a `<span class="format">` describing them in its title.
+pub fn diff_html(diff: &DocumentDiff) -> String {
+    let mut html = String::new();
+    for paragraph in &diff.paragraphs {
+        match paragraph {
+            ParagraphDiff::Equal { text } => {
+                html.push_str(&format!("<p>{}</p>\n", escape_html(text)));
+            }
+            ParagraphDiff::Insert { text } => {
+                html.push_str(&format!("<p class=\"insert\"><ins>{}</ins></p>\n", escape_html(text)));
+            }
+            ParagraphDiff::Delete { text } => {
+                html.push_str(&format!("<p class=\"delete\"><del>{}</del></p>\n", escape_html(text)));
+            }
+            ParagraphDiff::Modify { segments } => {
+                html.push_str("<p class=\"modify\">");
+                for segment in segments {
+                    let text = escape_html(&segment.text);
+                    match (segment.change, &segment.old_format, &segment.new_format) {
+                        (Change::Insert, _, _) => html.push_str(&format!("<ins>{text}</ins>")),
+                        (Change::Delete, _, _) => html.push_str(&format!("<del>{text}</del>")),
+                        (Change::Format, Some(old), Some(new)) => html.push_str(&format!(
+                            "<span class=\"format\" title=\"{}\">{text}</span>",
+                            escape_html(&format_change(old, new))
+                        )),
+                        _ => html.push_str(&text),
+                    }
+                }
+                html.push_str("</p>\n");
+            }
+        }
+    }
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::options::ConvertOptions;
+    use crate::rtf::to_text::{read_document, tokenize};
+
+    fn body(rtf: &[u8]) -> Body {
+        let options = ConvertOptions::default();
+        let tokens = tokenize(rtf, &options.limits).unwrap();
+        read_document(&tokens, &options).unwrap().body
+    }
+
+    #[test]
+    fn test_diff_bodies() {
+        let old = body(br"{\rtf1\ansi Title\par The quick brown fox\par Removed\par End\par}");
+        let new = body(br"{\rtf1\ansi Title\par The {\b quick} red fox\par End\par Added\par}");
+        let diff = diff_bodies(&old, &new, None);
+
+        assert_eq!(
+            diff.stats,
+            DiffStats {
+                inserted_paragraphs: 1,
+                deleted_paragraphs: 1,
+                modified_paragraphs: 1,
+                inserted_words: 1,
+                deleted_words: 1,
+                reformatted_words: 1,
+            }
+        );
+        assert_eq!(diff.paragraphs[0], ParagraphDiff::Equal { text: "Title".into() });
+        let ParagraphDiff::Modify { segments } = &diff.paragraphs[1] else {
+            panic!("expected a modified paragraph, got {:?}", diff.paragraphs[1]);
+        };
+        let changes: Vec<(Change, &str)> = segments
+            .iter()
+            .map(|segment| (segment.change, segment.text.as_str()))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                (Change::Equal, "The "),
+                (Change::Format, "quick"),
+                (Change::Equal, " "),
+                (Change::Delete, "brown"),
+                (Change::Insert, "red"),
+                (Change::Equal, " fox"),
+            ]
+        );
+        assert_eq!(diff.paragraphs[2], ParagraphDiff::Delete { text: "Removed".into() });
+        assert_eq!(diff.paragraphs[4], ParagraphDiff::Insert { text: "Added".into() });
+
+        assert_eq!(
+            diff_html(&diff),
+            "<p>Title</p>\n\
+             <p class=\"modify\">The <span class=\"format\" title=\"bold: false → true\">quick</span> \
+             <del>brown</del><ins>red</ins> fox</p>\n\
+             <p class=\"delete\"><del>Removed</del></p>\n\
+             <p>End</p>\n\
+             <p class=\"insert\"><ins>Added</ins></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_format_changes() {
+        let old = body(br"{\rtf1\ansi{\colortbl;\red255\green0\blue0;}One {\i two} {\ul three\ulnone  four}\par}");
+        let new = body(br"{\rtf1\ansi{\colortbl;\red255\green0\blue0;}{\cf1 One} two {\ul three four}\par}");
+        let diff = diff_bodies(&old, &new, None);
+        assert_eq!(diff.stats.reformatted_words, 3);
+        assert_eq!(
+            diff_html(&diff),
+            "<p class=\"modify\"><span class=\"format\" title=\"color: None → Some(1)\">One</span> \
+             <span class=\"format\" title=\"italic: true → false\">two</span> three\
+             <span class=\"format\" title=\"underline: false → true\"> four</span></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_language_change() {
+        let old = body(br"{\rtf1\ansi\deflang1033 Bonjour\par}");
+        let new = body(br"{\rtf1\ansi\deflang1033{\lang1036 Bonjour}\par}");
+        let diff = diff_bodies(&old, &new, None);
+        assert_eq!(
+            diff_html(&diff),
+            "<p class=\"modify\"><span class=\"format\" title=\"language: en-US → fr-FR\">Bonjour</span></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_deadline() {
+        let old = body(br"{\rtf1\ansi A\par The quick brown fox\par B\par}");
+        let new = body(br"{\rtf1\ansi A\par The quick red fox\par C\par}");
+        // Past the deadline, changed paragraphs are still paired and compared.
+        let diff = diff_bodies(&old, &new, Some(Instant::now()));
+        assert_eq!(diff.paragraphs.len(), 3);
+        assert_eq!(diff.stats.modified_paragraphs, 2);
+        assert_eq!(diff.stats.inserted_words + diff.stats.deleted_words, 4);
+    }
+}
@@ -0,0 +1,430 @@
+//! Paragraph and run layout of the document body.
+
+use serde::Serialize;
+
+use super::error::RtfError;
+
+/// Writing direction of a document, paragraph or run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// Left to right.
+    #[default]
+    Ltr,
+    /// Right to left.
+    Rtl,
+}
+
+impl Direction {
+    /// Value of the HTML `dir` attribute.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Ltr => "ltr",
+            Direction::Rtl => "rtl",
+        }
+    }
+
+    /// Unicode mark (LRM or RLM) setting the direction of adjacent neutral characters.
+    pub fn mark(&self) -> char {
+        match self {
+            Direction::Ltr => '\u{200E}',
+            Direction::Rtl => '\u{200F}',
+        }
+    }
+}
+
+/// Character formatting of a run.
+///
+/// Right-to-left runs take their font, size, weight and language from the
+/// associated character properties (`\af`, `\afs`, `\ab`, `\ai`, `\alang`),
+/// left-to-right runs from the regular ones (`\f`, `\fs`, `\b`, `\i`, `\lang`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct RunProps {
+    /// Direction of the run (`\rtlch`, `\ltrch`).
+    pub direction: Direction,
+    /// Font table index.
+    pub font: Option<i32>,
+    /// Font size, in half-points.
+    pub font_size: Option<i32>,
+    /// Bold text.
+    pub bold: bool,
+    /// Italic text.
+    pub italic: bool,
+    /// Underlined text (`\ul`, until `\ulnone`).
+    pub underline: bool,
+    /// Color table index of the text (`\cf`), 0 being the automatic color.
+    pub color: Option<i32>,
+    /// BCP-47 tag of the language of the run (`\lang`, `\langfe`, `\alang`).
+    pub language: Option<&'static str>,
+}
+
+/// Stretch of text sharing the same formatting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Run {
+    /// Text, in logical order.
+    pub text: String,
+    /// Formatting of the text.
+    pub props: RunProps,
+}
+
+/// Paragraph of body text.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Paragraph {
+    /// Base direction of the paragraph (`\rtlpar`, `\ltrpar`).
+    pub direction: Direction,
+    /// Runs of the paragraph, in logical order.
+    pub runs: Vec<Run>,
+    /// Whether the paragraph was ended by a break, rather than by the end of the document.
+    pub terminated: bool,
+    /// Index of the section of the paragraph in [Layout::sections].
+    pub section: usize,
+}
+
+impl Paragraph {
+    /// Whether any run of the paragraph is written against its base direction.
+    pub fn is_bidi(&self) -> bool {
+        self.runs
+            .iter()
+            .any(|run| run.props.direction != self.direction)
+    }
+}
+
+/// Stretch of body text in one language.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LanguageSegment {
+    /// BCP-47 tag of the language, if known.
+    pub language: Option<&'static str>,
+    /// Text, with a line feed after each paragraph.
+    pub text: String,
+}
+
+/// Body text of a document, split into paragraphs and runs.
+#[derive(Clone, Debug, Default)]
+pub struct Body {
+    /// Base direction of the document (`\rtldoc`, `\ltrdoc`).
+    pub direction: Direction,
+    /// BCP-47 tag of the default language of the document (`\deflang`).
+    pub language: Option<&'static str>,
+    /// Paragraphs of the body, in order.
+    pub paragraphs: Vec<Paragraph>,
+    /// Section new paragraphs are added to.
+    section: usize,
+}
+
+impl Body {
+    /// Appends `text` to the body, starting a new paragraph after each line feed.
+    pub fn push_text(&mut self, text: &str, props: RunProps, direction: Direction) {
+        for (i, segment) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.open_paragraph(direction).terminated = true;
+            }
+            if segment.is_empty() {
+                continue;
+            }
+            let paragraph = self.open_paragraph(direction);
+            match paragraph.runs.last_mut() {
+                Some(run) if run.props == props => run.text.push_str(segment),
+                _ => paragraph.runs.push(Run {
+                    text: segment.to_string(),
+                    props,
+                }),
+            }
+        }
+    }
+
+    /// Splits the text of the body where its language changes.
+    ///
+    /// Whitespace-only runs, such as the space between two words in different
+    /// languages, join the preceding segment rather than starting one.
+    pub fn language_segments(&self) -> Vec<LanguageSegment> {
+        let mut segments: Vec<LanguageSegment> = Vec::new();
+        for paragraph in &self.paragraphs {
+            for run in &paragraph.runs {
+                match segments.last_mut() {
+                    Some(segment)
+                        if segment.language == run.props.language
+                            || run.text.trim().is_empty() =>
+                    {
+                        segment.text.push_str(&run.text)
+                    }
+                    _ => segments.push(LanguageSegment {
+                        language: run.props.language,
+                        text: run.text.clone(),
+                    }),
+                }
+            }
+            if paragraph.terminated {
+                match segments.last_mut() {
+                    Some(segment) => segment.text.push('\n'),
+                    None => segments.push(LanguageSegment {
+                        language: self.language,
+                        text: "\n".to_string(),
+                    }),
+                }
+            }
+        }
+        segments
+    }
+
+    /// Ends the current section (`\sect`); paragraphs opened next belong to
+    /// the following one.
+    pub fn end_section(&mut self) {
+        self.section += 1;
+    }
+
+    /// Last paragraph, unless it has been terminated.
+    fn open_paragraph(&mut self, direction: Direction) -> &mut Paragraph {
+        if self.paragraphs.last().is_none_or(|p| p.terminated) {
+            self.paragraphs.push(Paragraph {
+                direction,
+                section: self.section,
+                ..Paragraph::default()
+            });
+        }
+        self.paragraphs.last_mut().expect("a paragraph was just opened")
+    }
+}
+
+/// Page size and margins, in twips (1/1440 inch).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct PageSetup {
+    /// Page width (`\paperw`, `\pgwsxn`).
+    pub width: i32,
+    /// Page height (`\paperh`, `\pghsxn`).
+    pub height: i32,
+    /// Left margin (`\margl`, `\marglsxn`).
+    pub margin_left: i32,
+    /// Right margin (`\margr`, `\margrsxn`).
+    pub margin_right: i32,
+    /// Top margin (`\margt`, `\margtsxn`).
+    pub margin_top: i32,
+    /// Bottom margin (`\margb`, `\margbsxn`).
+    pub margin_bottom: i32,
+    /// Landscape orientation (`\landscape`, `\lndscpsxn`).
+    pub landscape: bool,
+}
+
+impl Default for PageSetup {
+    /// US Letter with the margins the specification defaults to.
+    fn default() -> Self {
+        Self {
+            width: 12240,
+            height: 15840,
+            margin_left: 1800,
+            margin_right: 1800,
+            margin_top: 1440,
+            margin_bottom: 1440,
+            landscape: false,
+        }
+    }
+}
+
+impl PageSetup {
+    /// Page width and height, swapped if needed to match the orientation.
+    pub fn oriented_size(&self) -> (i32, i32) {
+        if self.landscape == (self.width < self.height) {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        }
+    }
+}
+
+/// How a section starts (`\sbknone`, `\sbkcol`, `\sbkpage`, `\sbkeven`, `\sbkodd`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SectionBreak {
+    /// Continues on the same page.
+    None,
+    /// Starts a new column.
+    Column,
+    /// Starts a new page.
+    #[default]
+    Page,
+    /// Starts on the next even-numbered page.
+    Even,
+    /// Starts on the next odd-numbered page.
+    Odd,
+}
+
+/// Header or footer texts of a section.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct HeaderFooter {
+    /// Text of every page (`\header`, `\footer`).
+    pub all: Option<String>,
+    /// Text of the first page, with a title page (`\headerf`, `\footerf`).
+    pub first: Option<String>,
+    /// Text of left (even) pages, with facing pages (`\headerl`, `\footerl`).
+    pub left: Option<String>,
+    /// Text of right (odd) pages, with facing pages (`\headerr`, `\footerr`).
+    pub right: Option<String>,
+}
+
+impl HeaderFooter {
+    /// Whether no text is defined for any page.
+    pub fn is_empty(&self) -> bool {
+        self.all.is_none() && self.first.is_none() && self.left.is_none() && self.right.is_none()
+    }
+
+    /// Text of page `number`, which is the first page of its section if
+    /// `first_page` is set.
+    ///
+    /// The first page of a section with a title page only shows the first-page
+    /// text. With facing pages, even pages show the left text and odd pages the
+    /// right one; otherwise every page shows the text of all pages.
+    pub fn for_page(
+        &self,
+        section: &Section,
+        layout: &Layout,
+        number: i32,
+        first_page: bool,
+    ) -> Option<&str> {
+        if section.title_page && first_page {
+            return self.first.as_deref();
+        }
+        let sided = if !layout.facing_pages {
+            None
+        } else if number % 2 == 0 {
+            self.left.as_deref()
+        } else {
+            self.right.as_deref()
+        };
+        sided.or(self.all.as_deref())
+    }
+}
+
+/// Layout of a section of the document.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Section {
+    /// Page size and margins.
+    pub page: PageSetup,
+    /// Number of text columns (`\cols`).
+    pub columns: i32,
+    /// Space between columns, in twips (`\colsx`).
+    pub column_spacing: i32,
+    /// How the section starts.
+    pub break_kind: SectionBreak,
+    /// Whether the first page has its own header and footer (`\titlepg`).
+    pub title_page: bool,
+    /// Number of the first page when numbering restarts (`\pgnrestart`, `\pgnstarts`).
+    pub page_number_start: Option<i32>,
+    /// Distance of the header from the top of the page, in twips (`\headery`).
+    pub header_distance: i32,
+    /// Distance of the footer from the bottom of the page, in twips (`\footery`).
+    pub footer_distance: i32,
+    /// Headers of the section.
+    pub header: HeaderFooter,
+    /// Footers of the section.
+    pub footer: HeaderFooter,
+}
+
+impl Default for Section {
+    fn default() -> Self {
+        Self {
+            page: PageSetup::default(),
+            columns: 1,
+            column_spacing: 720,
+            break_kind: SectionBreak::default(),
+            title_page: false,
+            page_number_start: None,
+            header_distance: 720,
+            footer_distance: 720,
+            header: HeaderFooter::default(),
+            footer: HeaderFooter::default(),
+        }
+    }
+}
+
+/// Page layout of a document.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Layout {
+    /// Default page size and margins of the document.
+    pub page: PageSetup,
+    /// Whether left and right pages differ (`\facingp`).
+    pub facing_pages: bool,
+    /// Number of the first page (`\pgnstart`).
+    pub page_number_start: i32,
+    /// Sections, in order; paragraphs refer to them by index.
+    pub sections: Vec<Section>,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            page: PageSetup::default(),
+            facing_pages: false,
+            page_number_start: 1,
+            sections: Vec::new(),
+        }
+    }
+}
+
+impl Layout {
+    /// Section `index`, or the default section if the document has fewer.
+    pub fn section(&self, index: usize) -> Section {
+        self.sections.get(index).cloned().unwrap_or_else(|| Section {
+            page: self.page,
+            ..Section::default()
+        })
+    }
+}
+
+/// Result of interpreting a token stream.
+#[derive(Clone, Debug, Default)]
+pub struct Document {
+    /// Body text layout.
+    pub body: Body,
+    /// Page layout and sections.
+    pub layout: Layout,
+    /// Non-fatal problems found in the document.
+    pub diagnostics: Vec<RtfError>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_text() {
+        let rtl = RunProps {
+            direction: Direction::Rtl,
+            ..RunProps::default()
+        };
+        let mut body = Body::default();
+        body.push_text("ab", RunProps::default(), Direction::Rtl);
+        body.push_text("c", RunProps::default(), Direction::Rtl);
+        body.push_text("d\n\ne", rtl, Direction::Rtl);
+
+        assert_eq!(body.paragraphs.len(), 3);
+        let first = &body.paragraphs[0];
+        assert!(first.terminated && first.is_bidi());
+        assert_eq!(first.runs.len(), 2);
+        assert_eq!(first.runs[0].text, "abc");
+        assert_eq!(first.runs[1].text, "d");
+        assert!(body.paragraphs[1].runs.is_empty());
+        assert!(!body.paragraphs[2].terminated);
+    }
+
+    #[test]
+    fn test_language_segments() {
+        let props = |language| RunProps {
+            language,
+            ..RunProps::default()
+        };
+        let mut body = Body::default();
+        body.push_text("Hello", props(Some("en-US")), Direction::Ltr);
+        body.push_text(" ", props(None), Direction::Ltr);
+        body.push_text("monde\n", props(Some("fr-FR")), Direction::Ltr);
+        body.push_text("Bye\n", props(Some("en-US")), Direction::Ltr);
+
+        let segments = body.language_segments();
+        let segments: Vec<(Option<&str>, &str)> = segments
+            .iter()
+            .map(|segment| (segment.language, segment.text.as_str()))
+            .collect();
+        assert_eq!(
+            segments,
+            [(Some("en-US"), "Hello "), (Some("fr-FR"), "monde\n"), (Some("en-US"), "Bye\n")]
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Fallback encoding for documents that do not declare a usable codepage.
+
+use chardetng::EncodingDetector;
+use encoding_rs::Encoding;
+use rtf_grimoire::tokenizer::Token;
+use tracing::debug;
+
+use super::lcid;
+
+/// Number of document bytes fed to the statistical detector.
+const DETECTION_SAMPLE_BYTES: usize = 64 * 1024;
+
+/// Encoding for text written while no codepage is in effect.
+///
+/// Tries, in order, the codepage of the `\deflang` and `\adeflang` default
+/// languages, then a statistical guess over the document text, so that text
+/// is never discarded for lack of an encoding.
+pub fn fallback_encoding(tokens: &[Token]) -> &'static Encoding {
+    language_encoding(tokens, "deflang")
+        .or_else(|| language_encoding(tokens, "adeflang"))
+        .unwrap_or_else(|| detect_encoding(tokens))
+}
+
+/// Encoding of text in a font with character set `charset` (`\fcharset`).
+///
+/// Returns `None` for the ANSI and default character sets, whose text uses
+/// the document codepage, and for symbol character sets.
+pub fn charset_encoding(charset: i32) -> Option<&'static Encoding> {
+    let codepage = match charset {
+        77 => 10000,  // Mac Roman
+        128 => 932,   // Shift JIS
+        129 => 949,   // Hangul
+        130 => 1361,  // Johab
+        134 => 936,   // GB2312
+        136 => 950,   // Big5
+        161 => 1253,  // Greek
+        162 => 1254,  // Turkish
+        163 => 1258,  // Vietnamese
+        177 => 1255,  // Hebrew
+        178 => 1256,  // Arabic
+        186 => 1257,  // Baltic
+        204 => 1251,  // Russian
+        222 => 874,   // Thai
+        238 => 1250,  // Eastern European
+        254 => 437,   // PC 437
+        255 => 850,   // OEM
+        _ => return None,
+    };
+    codepage::to_encoding(codepage)
+}
+
+/// Encoding of the first `name` language identifier in the document.
+fn language_encoding(tokens: &[Token], name: &str) -> Option<&'static Encoding> {
+    let lcid = tokens.iter().find_map(|token| match token {
+        Token::ControlWord { name: word, arg: Some(arg) } if word == name => Some(*arg),
+        _ => None,
+    })?;
+    let encoding = lcid::ansi_codepage(lcid).and_then(codepage::to_encoding);
+    debug!("\\{}{} maps to encoding {:?}", name, lcid, encoding.map(Encoding::name));
+    encoding
+}
+
+/// Guesses the encoding of the document text.
+///
+/// Documents with no non-ASCII text come out as Windows-1252.
+fn detect_encoding(tokens: &[Token]) -> &'static Encoding {
+    let mut detector = EncodingDetector::new();
+    let mut sampled = 0;
+    let mut ascii_only = true;
+    for token in tokens {
+        if sampled >= DETECTION_SAMPLE_BYTES {
+            break;
+        }
+        let byte;
+        let bytes: &[u8] = match token {
+            Token::Text(bytes) => bytes,
+            Token::ControlWord { name, arg: Some(arg) } if name == "'" => {
+                byte = [(*arg & 0xFF) as u8];
+                &byte
+            }
+            _ => continue,
+        };
+        ascii_only &= bytes.is_ascii();
+        detector.feed(bytes, false);
+        sampled += bytes.len();
+    }
+    if ascii_only {
+        return encoding_rs::WINDOWS_1252;
+    }
+    detector.feed(&[], true);
+
+    let encoding = detector.guess(None, true);
+    debug!("Detected document encoding {}", encoding.name());
+    encoding
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(name: &str, arg: i32) -> Token {
+        Token::ControlWord {
+            name: name.to_string(),
+            arg: Some(arg),
+        }
+    }
+
+    #[test]
+    fn test_deflang_takes_precedence() {
+        let tokens = vec![word("adeflang", 1025), word("deflang", 1049)];
+        assert_eq!(fallback_encoding(&tokens), encoding_rs::WINDOWS_1251);
+        let tokens = vec![word("adeflang", 1025)];
+        assert_eq!(fallback_encoding(&tokens), encoding_rs::WINDOWS_1256);
+    }
+
+    #[test]
+    fn test_charset_encoding() {
+        assert_eq!(charset_encoding(0), None);
+        assert_eq!(charset_encoding(177), Some(encoding_rs::WINDOWS_1255));
+        assert_eq!(charset_encoding(178), Some(encoding_rs::WINDOWS_1256));
+        assert_eq!(charset_encoding(128), Some(encoding_rs::SHIFT_JIS));
+    }
+
+    #[test]
+    fn test_detection() {
+        let text = encoding_rs::WINDOWS_1251
+            .encode("Привет, как дела? Это простой тест кодировки.")
+            .0
+            .into_owned();
+        assert_eq!(
+            fallback_encoding(&[Token::Text(text)]),
+            encoding_rs::WINDOWS_1251
+        );
+        assert_eq!(fallback_encoding(&[]), encoding_rs::WINDOWS_1252);
+    }
+}
@@ -0,0 +1,376 @@
+//! Templating engines, selected by the `templating_engine` and
+//! `templating_engine_version` of a stored template.
+//!
+//! Three engines are registered:
+//!
+//! - `placeholder`, the default: `{{name}}` placeholders, see
+//!   [template](super::template);
+//! - `handlebars`: placeholders, loops and conditionals, see
+//!   [handlebars](super::handlebars);
+//! - `mergefield`: Word mail merge fields (`\fldinst MERGEFIELD name`).
+
+use rtf_grimoire::tokenizer::Token;
+use serde_json::Value;
+
+use super::error::TemplateError;
+use super::fragment::value_nodes;
+use super::handlebars::Handlebars;
+use super::normalize::{destination, parse, subgroup, write, Node};
+use super::schema::{merge_field, FieldKind, TemplateField};
+use super::template::{
+    self, check_output, find_tags, is_name, is_text_group, lookup, text_nodes, value_text, OutputSize, RenderReport,
+    PARAGRAPH_BREAKS,
+};
+use crate::settings::Limits;
+
+/// Engine of templates stored without one.
+pub const DEFAULT_ENGINE: &str = "placeholder";
+
+/// Registered engines.
+static ENGINES: &[&dyn TemplateEngine] = &[&Placeholders, &Handlebars, &MergeFields];
+
+/// Renders the templates stored with its name and version.
+pub trait TemplateEngine: Sync {
+    /// Name stored in the `templating_engine` column.
+    fn name(&self) -> &'static str;
+
+    /// Version stored in the `templating_engine_version` column.
+    fn version(&self) -> i32;
+
+    /// Checks that `token_stream` is a valid template for this engine.
+    fn compile(&self, token_stream: &[Token], limits: &Limits) -> Result<(), TemplateError>;
+
+    /// Fields of the data the template is rendered with, in document order.
+    fn fields(&self, token_stream: &[Token], limits: &Limits) -> Result<Vec<TemplateField>, TemplateError>;
+
+    /// Renders the template with `data`, returning the RTF and a report of
+    /// what was replaced.
+    fn render(
+        &self,
+        token_stream: &[Token],
+        data: &Value,
+        limits: &Limits,
+    ) -> Result<(Vec<u8>, RenderReport), TemplateError>;
+}
+
+/// All registered engines.
+pub fn engines() -> &'static [&'static dyn TemplateEngine] {
+    ENGINES
+}
+
+/// Engine registered as `name` (case-insensitive, [DEFAULT_ENGINE] when
+/// empty) and `version`, the latest version of it when none is given.
+pub fn find_engine(name: Option<&str>, version: Option<i32>) -> Result<&'static dyn TemplateEngine, TemplateError> {
+    let name = name.map(str::trim).filter(|name| !name.is_empty()).unwrap_or(DEFAULT_ENGINE);
+    ENGINES
+        .iter()
+        .copied()
+        .filter(|engine| engine.name().eq_ignore_ascii_case(name))
+        .filter(|engine| version.is_none() || version == Some(engine.version()))
+        .max_by_key(|engine| engine.version())
+        .ok_or_else(|| TemplateError::UnknownEngine {
+            name: name.to_string(),
+            version,
+        })
+}
+
+/// `{{name}}` placeholders.
+pub struct Placeholders;
+
+impl TemplateEngine for Placeholders {
+    fn name(&self) -> &'static str {
+        DEFAULT_ENGINE
+    }
+
+    fn version(&self) -> i32 {
+        1
+    }
+
+    fn compile(&self, token_stream: &[Token], limits: &Limits) -> Result<(), TemplateError> {
+        parse(token_stream, limits.max_group_depth)?;
+        Ok(())
+    }
+
+    fn fields(&self, token_stream: &[Token], limits: &Limits) -> Result<Vec<TemplateField>, TemplateError> {
+        let mut fields = Vec::new();
+        for (name, paragraph) in find_tags(&parse(token_stream, limits.max_group_depth)?, is_name) {
+            merge_field(&mut fields, TemplateField::new(&name, FieldKind::Scalar, true, paragraph));
+        }
+        Ok(fields)
+    }
+
+    fn render(
+        &self,
+        token_stream: &[Token],
+        data: &Value,
+        limits: &Limits,
+    ) -> Result<(Vec<u8>, RenderReport), TemplateError> {
+        template::render(token_stream, data, limits)
+    }
+}
+
+/// Word mail merge fields, `{\field{\*\fldinst MERGEFIELD name}{\fldrslt ...}}`.
+///
+/// A field is replaced by the value at its name, in the formatting of the
+/// first character of its result; the `\b` and `\f` switches add text before
+/// and after non-empty values.
+pub struct MergeFields;
+
+/// Name and switches of a `MERGEFIELD` instruction.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct MergeField {
+    name: String,
+    before: String,
+    after: String,
+}
+
+/// Text of a field instruction, with its escaped backslashes.
+fn instruction_text(nodes: &[Node], text: &mut Vec<u8>) {
+    for node in nodes {
+        match node {
+            Node::Group(group) => instruction_text(group, text),
+            Node::Token(Token::Text(bytes)) => text.extend_from_slice(bytes),
+            Node::Token(Token::ControlSymbol(symbol @ ('\\' | '{' | '}'))) => text.push(*symbol as u8),
+            Node::Token(_) => (),
+        }
+    }
+}
+
+/// Words of a field instruction; quoted words keep their spaces.
+fn instruction_words(instruction: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut chars = instruction.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            words.push(chars.by_ref().take_while(|&c| c != '"').collect());
+        } else {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                word.push(c);
+            }
+            words.push(word);
+        }
+    }
+    words
+}
+
+/// Merge field of a `\field` group, `None` for other groups and fields.
+fn parse_merge_field(group: &[Node]) -> Option<Result<MergeField, TemplateError>> {
+    if destination(group) != Some("field") {
+        return None;
+    }
+    let mut instruction = Vec::new();
+    instruction_text(subgroup(group, "fldinst")?, &mut instruction);
+    let instruction = String::from_utf8_lossy(&instruction);
+    let mut words = instruction_words(&instruction).into_iter();
+    if !words.next()?.eq_ignore_ascii_case("MERGEFIELD") {
+        return None;
+    }
+    let mut field = match words.next() {
+        Some(name) if !name.starts_with('\\') => MergeField {
+            name: name.trim().to_string(),
+            ..MergeField::default()
+        },
+        _ => return Some(Err(TemplateError::Syntax(format!("no field name in {instruction:?}")))),
+    };
+    while let Some(switch) = words.next() {
+        match switch.as_str() {
+            "\\b" => field.before = words.next().unwrap_or_default(),
+            "\\f" => field.after = words.next().unwrap_or_default(),
+            "\\*" => {
+                words.next();
+            }
+            _ => (),
+        }
+    }
+    Some(Ok(field))
+}
+
+/// Nodes of a field result with its text replaced by `text`.
+fn replace_text(nodes: Vec<Node>, text: &mut Option<Vec<Node>>) -> Vec<Node> {
+    let mut replaced = Vec::new();
+    for node in nodes {
+        match node {
+            Node::Group(group) => replaced.push(Node::Group(replace_text(group, text))),
+            Node::Token(Token::Text(_)) => replaced.extend(text.take().unwrap_or_default()),
+            node if matches!(node.word(), Some(("'" | "u", _))) => {
+                replaced.extend(text.take().unwrap_or_default())
+            }
+            node => replaced.push(node),
+        }
+    }
+    replaced
+}
+
+/// Content of the `\fldrslt` group of a field.
+fn take_result(field: &mut Vec<Node>) -> Vec<Node> {
+    let Some(i) = field
+        .iter()
+        .position(|node| matches!(node, Node::Group(group) if destination(group) == Some("fldrslt")))
+    else {
+        return Vec::new();
+    };
+    let Node::Group(mut result) = field.remove(i) else {
+        return Vec::new();
+    };
+    let prefix = result
+        .iter()
+        .take_while(|node| {
+            matches!(node, Node::Token(Token::ControlSymbol('*'))) || matches!(node.word(), Some(("fldrslt", _)))
+        })
+        .count();
+    result.drain(..prefix);
+    result
+}
+
+/// Replaces each merge field of `nodes` with the nodes `merge` returns for it.
+fn merge_fields(
+    nodes: &mut [Node],
+    merge: &mut dyn FnMut(MergeField) -> Result<Vec<Node>, TemplateError>,
+) -> Result<(), TemplateError> {
+    for node in nodes.iter_mut() {
+        let Node::Group(group) = node else {
+            continue;
+        };
+        let Some(field) = parse_merge_field(group) else {
+            merge_fields(group, merge)?;
+            continue;
+        };
+        let mut text = Some(merge(field?)?);
+        let mut result = replace_text(take_result(group), &mut text);
+        result.extend(text.unwrap_or_default());
+        *node = Node::Group(result);
+    }
+    Ok(())
+}
+
+/// Adds the merge fields of `nodes` to `fields`, counting paragraphs in
+/// `paragraph`.
+fn collect_fields(nodes: &[Node], paragraph: &mut usize, fields: &mut Vec<TemplateField>) -> Result<(), TemplateError> {
+    for node in nodes {
+        match node {
+            Node::Group(group) => match parse_merge_field(group) {
+                Some(field) => merge_field(fields, TemplateField::new(&field?.name, FieldKind::Scalar, true, *paragraph)),
+                None if is_text_group(group) => collect_fields(group, paragraph, fields)?,
+                None => (),
+            },
+            node => {
+                if node.word().is_some_and(|(name, _)| PARAGRAPH_BREAKS.contains(&name)) {
+                    *paragraph += 1;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+impl TemplateEngine for MergeFields {
+    fn name(&self) -> &'static str {
+        "mergefield"
+    }
+
+    fn version(&self) -> i32 {
+        1
+    }
+
+    fn compile(&self, token_stream: &[Token], limits: &Limits) -> Result<(), TemplateError> {
+        merge_fields(&mut parse(token_stream, limits.max_group_depth)?, &mut |_| Ok(Vec::new()))
+    }
+
+    fn fields(&self, token_stream: &[Token], limits: &Limits) -> Result<Vec<TemplateField>, TemplateError> {
+        let mut fields = Vec::new();
+        collect_fields(&parse(token_stream, limits.max_group_depth)?, &mut 0, &mut fields)?;
+        Ok(fields)
+    }
+
+    fn render(
+        &self,
+        token_stream: &[Token],
+        data: &Value,
+        limits: &Limits,
+    ) -> Result<(Vec<u8>, RenderReport), TemplateError> {
+        let mut document = parse(token_stream, limits.max_group_depth)?;
+        let mut report = RenderReport::default();
+        let mut size = OutputSize::new(limits);
+        merge_fields(&mut document, &mut |field| {
+            report.replaced += 1;
+            match lookup(data, &field.name) {
+                Some(value) if value_text(value).is_empty() => Ok(Vec::new()),
+                Some(value) => {
+                    let mut nodes = text_nodes(&field.before);
+                    nodes.extend(value_nodes(value, limits)?);
+                    nodes.extend(text_nodes(&field.after));
+                    size.add_nodes(&nodes)?;
+                    Ok(nodes)
+                }
+                None => {
+                    if !report.missing.contains(&field.name) {
+                        report.missing.push(field.name);
+                    }
+                    Ok(Vec::new())
+                }
+            }
+        })?;
+        report.missing.sort();
+        Ok((check_output(write(&document), limits)?, report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::error::{Limit, LimitExceeded};
+    use crate::rtf::to_text::tokenize;
+    use crate::settings::Limits;
+    use serde_json::json;
+
+    #[test]
+    fn test_find_engine() {
+        assert_eq!(find_engine(None, None).unwrap().name(), "placeholder");
+        assert_eq!(find_engine(Some(" Handlebars "), Some(1)).unwrap().name(), "handlebars");
+        assert_eq!(
+            find_engine(Some("mergefield"), Some(7)).err(),
+            Some(TemplateError::UnknownEngine {
+                name: "mergefield".to_string(),
+                version: Some(7)
+            })
+        );
+    }
+
+    #[test]
+    fn test_merge_fields() {
+        let rtf = br#"{\rtf1\ansi Dear {\field{\*\fldinst { MERGEFIELD "First Name" \\b "Dr. " \\* MERGEFORMAT }}{\fldrslt {\b\'abFirst Name\'bb}}},{\field{\*\fldinst MERGEFIELD City}{\fldrslt x}}\par}"#;
+        let tokens = tokenize(&rtf[..], &Limits::default()).unwrap();
+        let (out, report) = MergeFields
+            .render(&tokens, &json!({"First Name": "Ada"}), &Limits::default())
+            .unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "{\\rtf1\\ansi Dear {{\\b Dr. Ada}},{}\\par\n}");
+        assert_eq!(report.replaced, 2);
+        assert_eq!(report.missing, ["City"]);
+        let fields = MergeFields.fields(&tokens, &Limits::default()).unwrap();
+        assert_eq!(fields.iter().map(|field| field.name.as_str()).collect::<Vec<_>>(), ["First Name", "City"]);
+
+        let tokens = tokenize(&br"{\rtf1{\field{\*\fldinst MERGEFIELD \\* MERGEFORMAT}}}"[..], &Limits::default()).unwrap();
+        assert!(matches!(MergeFields.compile(&tokens, &Limits::default()), Err(TemplateError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_merge_fields_output_limit() {
+        let field = r"{\field{\*\fldinst MERGEFIELD a}{\fldrslt x}}";
+        let rtf = format!(r"{{\rtf1 {}}}", field.repeat(3));
+        let limits = Limits {
+            max_output_bytes: 1_000,
+            ..Limits::default()
+        };
+        let tokens = tokenize(rtf.as_bytes(), &limits).unwrap();
+        assert_eq!(
+            MergeFields.render(&tokens, &json!({"a": "x".repeat(400)}), &limits).err(),
+            Some(TemplateError::Limit(LimitExceeded::new(Limit::OutputBytes, 1_000)))
+        );
+        let (_, report) = MergeFields.render(&tokens, &json!({"a": "x".repeat(300)}), &limits).unwrap();
+        assert_eq!(report.replaced, 3);
+    }
+}
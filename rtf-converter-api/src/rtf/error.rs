@@ -0,0 +1,125 @@
+//! Errors raised while interpreting an RTF token stream.
+
+use std::fmt;
+
+/// Storage mode of an RTF destination.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DestinationKind {
+    /// Decoded text, written through the group encoding.
+    Text,
+    /// Raw bytes, stored as-is.
+    Bytes,
+}
+
+impl fmt::Display for DestinationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DestinationKind::Text => write!(f, "text"),
+            DestinationKind::Bytes => write!(f, "bytes"),
+        }
+    }
+}
+
+/// Document-level problem found while processing a token stream.
+///
+/// None of these abort a conversion: they are collected as diagnostics and
+/// the offending token is skipped.
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+pub enum RtfError {
+    /// A destination was reopened in a different mode than it was created with.
+    #[error("destination \\{name} holds {existing} and cannot be reopened as {requested}")]
+    DestinationKindMismatch {
+        /// Destination control word.
+        name: String,
+        /// Mode the destination was created with.
+        existing: DestinationKind,
+        /// Mode requested by the control word.
+        requested: DestinationKind,
+    },
+    /// The current destination vanished from the destination table.
+    #[error("destination \\{0} does not exist")]
+    MissingDestination(String),
+    /// An encoding handler was bound to a control word it has no mapping for.
+    #[error("no encoding mapping for control word \\{0}")]
+    UnknownEncoding(String),
+    /// A direction handler was bound to a control word it has no mapping for.
+    #[error("no direction mapping for control word \\{0}")]
+    UnknownDirection(String),
+}
+
+/// Resource limit enforced while converting a document.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    /// Size of the input document.
+    InputBytes,
+    /// Group nesting depth.
+    GroupDepth,
+    /// Bytes written across all destinations.
+    OutputBytes,
+    /// Number of `\pict` images.
+    Images,
+    /// Number of control words and symbols.
+    ControlWords,
+    /// Wall-clock time spent converting.
+    Duration,
+    /// Number of pages laid out.
+    Pages,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Named after the matching `Limits` setting.
+        let name = match self {
+            Limit::InputBytes => "max_input_bytes",
+            Limit::GroupDepth => "max_group_depth",
+            Limit::OutputBytes => "max_output_bytes",
+            Limit::Images => "max_images",
+            Limit::ControlWords => "max_control_words",
+            Limit::Duration => "max_duration_ms",
+            Limit::Pages => "max_pages",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A conversion was aborted because the document exceeded a resource limit.
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+#[error("document exceeds the {limit} limit of {max}")]
+pub struct LimitExceeded {
+    /// The limit that was exceeded.
+    pub limit: Limit,
+    /// Configured value of the limit.
+    pub max: u64,
+}
+
+impl LimitExceeded {
+    /// New instance of [LimitExceeded].
+    pub fn new<N: TryInto<u64>>(limit: Limit, max: N) -> Self {
+        Self {
+            limit,
+            max: max.try_into().unwrap_or(u64::MAX),
+        }
+    }
+}
+
+/// A template could not be rendered by its templating engine.
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+pub enum TemplateError {
+    /// No engine is registered under the name and version.
+    #[error("unknown templating engine {name}{}", version.map(|v| format!(" version {v}")).unwrap_or_default())]
+    UnknownEngine {
+        /// Engine name.
+        name: String,
+        /// Engine version, if one was requested.
+        version: Option<i32>,
+    },
+    /// The template does not compile.
+    #[error("template does not compile: {0}")]
+    Syntax(String),
+    /// A typed value cannot be inserted.
+    #[error("invalid template value: {0}")]
+    InvalidValue(String),
+    /// The template, or a value inserted in it, exceeds a resource limit.
+    #[error(transparent)]
+    Limit(#[from] LimitExceeded),
+}
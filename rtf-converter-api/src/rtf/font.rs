@@ -0,0 +1,53 @@
+//! Font table (`\fonttbl`) entries relevant to decoding text.
+
+use std::collections::HashMap;
+
+use encoding_rs::Encoding;
+
+use super::encoding::charset_encoding;
+
+/// Font table entry.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Font {
+    /// Character set (`\fcharset`).
+    pub charset: Option<i32>,
+    /// Codepage (`\cpg`), which takes precedence over the character set.
+    pub codepage: Option<u16>,
+}
+
+impl Font {
+    /// Encoding of text written in this font, if it differs from the document codepage.
+    pub fn encoding(&self) -> Option<&'static Encoding> {
+        self.codepage
+            .and_then(codepage::to_encoding)
+            .or_else(|| self.charset.and_then(charset_encoding))
+    }
+}
+
+/// Fonts of a document, keyed by their `\f` index.
+#[derive(Clone, Debug, Default)]
+pub struct FontTable {
+    fonts: HashMap<i32, Font>,
+}
+
+impl FontTable {
+    /// Font `index`, when the table defines it.
+    pub fn get(&self, index: i32) -> Option<&Font> {
+        self.fonts.get(&index)
+    }
+
+    /// Sets the character set (`\fcharset`) of font `index`.
+    pub fn set_charset(&mut self, index: i32, charset: i32) {
+        self.fonts.entry(index).or_default().charset = Some(charset);
+    }
+
+    /// Sets the codepage (`\cpg`) of font `index`.
+    pub fn set_codepage(&mut self, index: i32, codepage: u16) {
+        self.fonts.entry(index).or_default().codepage = Some(codepage);
+    }
+
+    /// Encoding of text written in font `index`, if it differs from the document codepage.
+    pub fn encoding(&self, index: i32) -> Option<&'static Encoding> {
+        self.get(index).and_then(Font::encoding)
+    }
+}
@@ -0,0 +1,225 @@
+//! Typed template values: pictures and rich-text fragments.
+//!
+//! Values are written as text, except objects with one of these `type`s:
+//!
+//! - `{"type": "image", "data": "<base64>", "width": 120, "height": 40}`
+//!   inserts a PNG or JPEG picture, shown at `width` by `height` pixels: its
+//!   own size by default, keeping its aspect ratio when only one is given;
+//! - `{"type": "rtf" | "html" | "markdown", "content": "..."}` inserts the
+//!   content, converted to RTF.
+//!
+//! Fragments are inserted in a group of their own without their header
+//! (font, color and style tables, document defaults) and without the
+//! references to it, so their text takes the font and color around the
+//! placeholder and, where they do not set it, the rest of its formatting.
+
+use base64::Engine;
+use rtf_grimoire::tokenizer::Token;
+use serde_json::Value;
+
+use super::error::{LimitExceeded, TemplateError};
+use super::normalize::{destination, parse, Node, COLOR_REFERENCES, FONT_REFERENCES};
+use super::template::{text_nodes, value_text};
+use super::to_text::tokenize;
+use super::writer::{write_image, Image, DEFAULT_FONT_SIZE};
+use super::{from_html, from_markdown};
+use crate::settings::Limits;
+
+/// Destinations of the document header, dropped from fragments.
+const HEADER_DESTINATIONS: &[&str] = &[
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "info",
+    "generator",
+    "listtable",
+    "listoverridetable",
+    "rsidtbl",
+];
+
+/// Document defaults and formatting resets, dropped from fragments.
+const DROPPED_WORDS: &[&str] = &[
+    "rtf", "ansi", "mac", "pc", "pca", "ansicpg", "deflang", "deflangfe", "adeflang", "plain", "pard", "sectd",
+    "s", "cs",
+];
+
+/// Largest width or height pictures are shown at, in pixels.
+const MAX_DIMENSION: u32 = 16_384;
+
+/// Value types inserted as something other than text.
+const TYPES: &[&str] = &["image", "rtf", "html", "markdown"];
+
+/// Type of a typed value.
+fn value_type(value: &Value) -> Option<&str> {
+    let kind = value.get("type")?.as_str()?;
+    let payload = if kind == "image" { "data" } else { "content" };
+    (TYPES.contains(&kind) && value.get(payload).is_some()).then_some(kind)
+}
+
+fn invalid(message: impl Into<String>) -> TemplateError {
+    TemplateError::InvalidValue(message.into())
+}
+
+/// Size of a pixel dimension of a typed value.
+fn dimension(value: &Value, name: &str) -> Result<Option<u32>, TemplateError> {
+    match value.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(size) => size
+            .as_u64()
+            .filter(|&size| size > 0 && size <= u64::from(MAX_DIMENSION))
+            .map(|size| Some(size as u32))
+            .ok_or_else(|| invalid(format!("image {name} must be between 1 and {MAX_DIMENSION} pixels"))),
+    }
+}
+
+/// Tokens of the RTF `rtf`, within `limits`.
+fn tokens(rtf: &str, limits: &Limits, what: &str) -> Result<Vec<Token>, TemplateError> {
+    tokenize(rtf.as_bytes(), limits).map_err(|err| match err.downcast::<LimitExceeded>() {
+        Ok(limit) => limit.into(),
+        Err(err) => invalid(format!("{what}: {err:#}")),
+    })
+}
+
+fn image_nodes(value: &Value, limits: &Limits) -> Result<Vec<Node>, TemplateError> {
+    let data = value["data"]
+        .as_str()
+        .and_then(|data| base64::engine::general_purpose::STANDARD.decode(data.trim()).ok())
+        .ok_or_else(|| invalid("image data must be base64"))?;
+    let image = Image::from_bytes(data).ok_or_else(|| invalid("image data must be a PNG or JPEG picture"))?;
+    let (width, height) = image.size();
+    let scale = |size: u32, from: u32, to: u32| {
+        (u64::from(size) * u64::from(to) / u64::from(from.max(1))).clamp(1, u64::from(MAX_DIMENSION)) as u32
+    };
+    let display = match (dimension(value, "width")?, dimension(value, "height")?) {
+        (Some(w), Some(h)) => (w, h),
+        (Some(w), None) => (w, scale(height, width, w)),
+        (None, Some(h)) => (scale(width, height, h), h),
+        (None, None) => (width.min(MAX_DIMENSION), height.min(MAX_DIMENSION)),
+    };
+    let mut rtf = String::new();
+    write_image(&mut rtf, &image.display_size(display.0, display.1));
+    Ok(parse(&tokens(&rtf, limits, "image")?, limits.max_group_depth)?)
+}
+
+/// Nodes of a fragment, without its header and references to it.
+///
+/// `builder` drops the font size [RtfBuilder](super::writer::RtfBuilder)
+/// sets on every paragraph, so that text keeps the size around the
+/// placeholder.
+fn clean(nodes: Vec<Node>, builder: bool) -> Vec<Node> {
+    nodes
+        .into_iter()
+        .filter_map(|node| match node {
+            Node::Group(group) if destination(&group).is_some_and(|name| HEADER_DESTINATIONS.contains(&name)) => None,
+            Node::Group(group) => Some(Node::Group(clean(group, builder))),
+            node => match node.word() {
+                Some((name, _)) if DROPPED_WORDS.contains(&name) => None,
+                Some((name, _)) if FONT_REFERENCES.contains(&name) || COLOR_REFERENCES.contains(&name) => None,
+                Some(("fs", Some(size))) if builder && size == i32::from(DEFAULT_FONT_SIZE) => None,
+                _ => Some(node),
+            },
+        })
+        .collect()
+}
+
+/// Group inserting the RTF document, or bare RTF, `rtf`.
+fn fragment_nodes(rtf: &str, builder: bool, limits: &Limits) -> Result<Vec<Node>, TemplateError> {
+    let mut document = parse(&tokens(rtf, limits, "RTF fragment")?, limits.max_group_depth)?;
+    let body = match document.as_mut_slice() {
+        [Node::Group(group)] if destination(group) == Some("rtf") => std::mem::take(group),
+        _ => document,
+    };
+    let mut body = clean(body, builder);
+    // The paragraph of the placeholder goes on after the fragment.
+    while matches!(body.last().and_then(Node::word), Some(("par", _))) {
+        body.pop();
+    }
+    Ok(vec![Node::Group(body)])
+}
+
+/// Nodes inserting `value` in place of a placeholder.
+pub(crate) fn value_nodes(value: &Value, limits: &Limits) -> Result<Vec<Node>, TemplateError> {
+    let content = || value["content"].as_str().ok_or_else(|| invalid("fragment content must be a string"));
+    match value_type(value) {
+        None => Ok(text_nodes(&value_text(value))),
+        Some("image") => image_nodes(value, limits),
+        Some("rtf") => fragment_nodes(content()?, false, limits),
+        Some("html") => fragment_nodes(&from_html::to_rtf(content()?, limits)?, true, limits),
+        Some(_) => fragment_nodes(&from_markdown::to_rtf(content()?), true, limits),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::error::{Limit, LimitExceeded};
+    use crate::rtf::normalize::write;
+    use serde_json::json;
+
+    fn inserted(value: Value) -> String {
+        String::from_utf8(write(&value_nodes(&value, &Limits::default()).unwrap())).unwrap()
+    }
+
+    #[test]
+    fn test_fragments() {
+        assert_eq!(inserted(json!("a{b}")), "a\\{b\\}");
+        assert_eq!(
+            inserted(json!({"type": "rtf", "content": r"{\rtf1\ansi\deff0{\fonttbl{\f0 Arial;}}\pard\plain\f0\cf1 x {\b y}\par}"})),
+            "{x {\\b y}}"
+        );
+        assert_eq!(
+            inserted(json!({"type": "markdown", "content": "a **b**"})),
+            "{\\uc1\\ql a {\\b b}}"
+        );
+        assert!(matches!(
+            value_nodes(&json!({"type": "image", "data": "aGVsbG8="}), &Limits::default()),
+            Err(TemplateError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_fragment_depth() {
+        let limits = Limits {
+            max_group_depth: 3,
+            ..Limits::default()
+        };
+        let deep = json!({"type": "rtf", "content": r"{\rtf1 {{{x}}}}"});
+        assert_eq!(
+            value_nodes(&deep, &limits).err(),
+            Some(TemplateError::Limit(LimitExceeded::new(Limit::GroupDepth, 3)))
+        );
+        let shallow = json!({"type": "rtf", "content": r"{\rtf1 {{x}}}"});
+        assert!(value_nodes(&shallow, &limits).is_ok());
+
+        let limits = Limits {
+            max_input_bytes: 8,
+            ..Limits::default()
+        };
+        assert_eq!(
+            value_nodes(&shallow, &limits).err(),
+            Some(TemplateError::Limit(LimitExceeded::new(Limit::InputBytes, 8)))
+        );
+        let html = json!({"type": "html", "content": "<p><b><i><u>x</u></i></b></p>"});
+        assert_eq!(
+            value_nodes(&html, &Limits { max_group_depth: 3, ..Limits::default() }).err(),
+            Some(TemplateError::Limit(LimitExceeded::new(Limit::GroupDepth, 3)))
+        );
+    }
+
+    #[test]
+    fn test_image() {
+        // 2x1 PNG header; the picture data is copied as-is.
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend_from_slice(&[0, 0, 0, 2, 0, 0, 0, 1]);
+        let data = base64::engine::general_purpose::STANDARD.encode(&png);
+        let rtf = inserted(json!({"type": "image", "data": data, "width": 100}));
+        assert!(rtf.starts_with("{\\pict\\pngblip\\picw2\\pich1\\picwgoal1500\\pichgoal750 89504e47"), "{rtf}");
+
+        let rtf = inserted(json!({"type": "image", "data": data, "height": MAX_DIMENSION}));
+        assert!(rtf.starts_with("{\\pict\\pngblip\\picw2\\pich1\\picwgoal245760\\pichgoal245760 "), "{rtf}");
+        assert!(matches!(
+            value_nodes(&json!({"type": "image", "data": data, "width": MAX_DIMENSION + 1}), &Limits::default()),
+            Err(TemplateError::InvalidValue(_))
+        ));
+    }
+}
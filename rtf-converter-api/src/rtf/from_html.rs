@@ -0,0 +1,242 @@
+//! HTML to RTF conversion.
+
+use base64::Engine;
+use ego_tree::NodeRef;
+use scraper::{Html, Node};
+
+use super::error::{Limit, LimitExceeded};
+use super::markup::{BlockCollector, Inline};
+use super::writer::{Block, Image, RtfBuilder};
+use crate::settings::Limits;
+
+/// Walks an HTML tree into a [BlockCollector].
+#[derive(Debug, Default)]
+struct Converter {
+    collector: BlockCollector,
+    /// Depth of the elements being converted.
+    depth: usize,
+    /// Elements nested deeper than this abort the conversion.
+    max_depth: usize,
+    /// Depth of `<pre>` elements, whose whitespace is kept.
+    preformatted: u32,
+    /// Set until the first text of a block, to drop its leading whitespace.
+    block_start: bool,
+    /// Set within `<thead>`.
+    table_head: bool,
+}
+
+/// Decodes the picture of a `data:` URL.
+fn data_url_image(src: &str) -> Option<Image> {
+    let (header, data) = src.strip_prefix("data:")?.split_once(',')?;
+    if !header.ends_with(";base64") {
+        return None;
+    }
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(data.trim())
+        .ok()?;
+    Image::from_bytes(data)
+}
+
+impl Converter {
+    fn text(&mut self, text: &str) {
+        if self.preformatted > 0 {
+            self.collector.text(text);
+            self.block_start = false;
+            return;
+        }
+        let mut collapsed = String::with_capacity(text.len());
+        for word in text.split_ascii_whitespace() {
+            collapsed.push_str(word);
+            collapsed.push(' ');
+        }
+        if !text.ends_with(|c: char| c.is_ascii_whitespace()) {
+            collapsed.pop();
+        }
+        if text.starts_with(|c: char| c.is_ascii_whitespace()) && !self.block_start {
+            collapsed.insert(0, ' ');
+        }
+        if !collapsed.trim().is_empty() || (!self.block_start && !collapsed.is_empty()) {
+            self.collector.text(&collapsed);
+            self.block_start = false;
+        }
+    }
+
+    fn children(&mut self, node: NodeRef<'_, Node>) -> Result<(), LimitExceeded> {
+        if self.depth >= self.max_depth {
+            return Err(LimitExceeded::new(Limit::GroupDepth, self.max_depth));
+        }
+        self.depth += 1;
+        for child in node.children() {
+            self.node(child)?;
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+
+    /// Converts `node` between `start` and `end`, as a block.
+    fn block(
+        &mut self,
+        node: NodeRef<'_, Node>,
+        start: fn(&mut BlockCollector),
+        end: fn(&mut BlockCollector),
+    ) -> Result<(), LimitExceeded> {
+        start(&mut self.collector);
+        self.block_start = true;
+        self.children(node)?;
+        end(&mut self.collector);
+        self.block_start = true;
+        Ok(())
+    }
+
+    fn inline(&mut self, node: NodeRef<'_, Node>, inline: Inline) -> Result<(), LimitExceeded> {
+        self.collector.push_inline(inline);
+        self.children(node)?;
+        self.collector.pop_inline();
+        Ok(())
+    }
+
+    fn node(&mut self, node: NodeRef<'_, Node>) -> Result<(), LimitExceeded> {
+        let element = match node.value() {
+            Node::Text(text) => {
+                self.text(text);
+                return Ok(());
+            }
+            Node::Element(element) => element,
+            _ => return Ok(()),
+        };
+        match element.name() {
+            "head" | "script" | "style" | "template" | "title" => (),
+            "p" | "div" | "section" | "article" | "header" | "footer" | "main" | "aside"
+            | "nav" | "figure" | "figcaption" | "address" | "dd" | "dt" => self.block(
+                node,
+                BlockCollector::start_paragraph,
+                BlockCollector::end_paragraph,
+            )?,
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = element.name()[1..].parse().unwrap_or(1);
+                self.collector.start_heading(level);
+                self.block_start = true;
+                self.children(node)?;
+                self.collector.end_heading();
+                self.block_start = true;
+            }
+            "blockquote" => self.block(node, BlockCollector::start_quote, BlockCollector::end_quote)?,
+            "pre" => {
+                self.preformatted += 1;
+                self.collector.push_inline(Inline::Code);
+                self.block(
+                    node,
+                    BlockCollector::start_paragraph,
+                    BlockCollector::end_paragraph,
+                )?;
+                self.collector.pop_inline();
+                self.preformatted -= 1;
+            }
+            "ul" | "menu" => self.block(node, |c| c.start_list(false), BlockCollector::end_list)?,
+            "ol" => self.block(node, |c| c.start_list(true), BlockCollector::end_list)?,
+            "li" => self.block(node, BlockCollector::start_item, BlockCollector::end_item)?,
+            "table" => self.block(node, BlockCollector::start_table, BlockCollector::end_table)?,
+            "thead" => {
+                self.table_head = true;
+                self.children(node)?;
+                self.table_head = false;
+            }
+            "tr" => {
+                let header = self.table_head
+                    || node.children().filter_map(|c| c.value().as_element()).all(|c| c.name() == "th");
+                self.collector.start_row(header);
+                self.children(node)?;
+                self.collector.end_row();
+            }
+            "td" | "th" => self.block(node, BlockCollector::start_cell, BlockCollector::end_cell)?,
+            "b" | "strong" => self.inline(node, Inline::Bold)?,
+            "i" | "em" | "cite" | "var" => self.inline(node, Inline::Italic)?,
+            "u" | "ins" => self.inline(node, Inline::Underline)?,
+            "s" | "strike" | "del" => self.inline(node, Inline::Strike)?,
+            "code" | "kbd" | "samp" | "tt" => self.inline(node, Inline::Code)?,
+            "a" => match element.attr("href") {
+                Some(href) => self.inline(node, Inline::Link(href.to_string()))?,
+                None => self.children(node)?,
+            },
+            "br" => {
+                self.collector.line_break();
+                self.block_start = true;
+            }
+            "img" => {
+                let image = element.attr("src").and_then(data_url_image);
+                self.collector.image(image, element.attr("alt").unwrap_or_default());
+            }
+            _ => self.children(node)?,
+        }
+        Ok(())
+    }
+}
+
+/// Blocks of an HTML document or fragment.
+///
+/// Elements nested deeper than `limits.max_group_depth` abort with a
+/// [LimitExceeded] error.
+pub fn blocks(html: &str, limits: &Limits) -> Result<Vec<Block>, LimitExceeded> {
+    let fragment = Html::parse_fragment(html);
+    let mut converter = Converter {
+        block_start: true,
+        max_depth: limits.max_group_depth,
+        ..Converter::default()
+    };
+    converter.children(*fragment.root_element())?;
+    Ok(converter.collector.finish())
+}
+
+/// Converts an HTML document or fragment to RTF.
+pub fn to_rtf(html: &str, limits: &Limits) -> Result<String, LimitExceeded> {
+    Ok(blocks(html, limits)?
+        .into_iter()
+        .fold(RtfBuilder::new(), RtfBuilder::block)
+        .build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::writer::{List, Paragraph, Run};
+
+    #[test]
+    fn test_blocks() {
+        let html = "<h2>Title</h2>\n<p>Some <b>bold</b>,\n   <a href=\"https://example.com\">a link</a><br>next</p>\
+                    <ol><li>one</li><li>two</li></ol><script>ignored()</script>";
+        assert_eq!(
+            blocks(html, &Limits::default()).unwrap(),
+            vec![
+                Block::Paragraph(Paragraph::new().heading(2).text("Title")),
+                Block::Paragraph(
+                    Paragraph::new()
+                        .text("Some ")
+                        .run(Run::new("bold").bold())
+                        .text(", ")
+                        .run(Run::new("a link").link("https://example.com"))
+                        .text("\n")
+                        .text("next")
+                ),
+                Block::List(
+                    List::numbered()
+                        .item(Paragraph::new().text("one"))
+                        .item(Paragraph::new().text("two"))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let limits = Limits {
+            max_group_depth: 4,
+            ..Limits::default()
+        };
+        // The fragment is parsed within an <html> element.
+        assert!(blocks("<div><p><b>x</b></p></div>", &limits).is_ok());
+        assert_eq!(
+            blocks("<div><div><p><b>x</b></p></div></div>", &limits),
+            Err(LimitExceeded::new(Limit::GroupDepth, 4))
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Markdown (CommonMark with tables and strikethrough) to RTF conversion.
+
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag};
+
+use super::markup::{BlockCollector, Inline};
+use super::writer::{Block, RtfBuilder};
+
+fn heading_level(level: HeadingLevel) -> u8 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+fn start(collector: &mut BlockCollector, tag: Tag<'_>) {
+    match tag {
+        Tag::Paragraph => collector.start_paragraph(),
+        Tag::Heading(level, ..) => collector.start_heading(heading_level(level)),
+        Tag::BlockQuote => collector.start_quote(),
+        // Collected whole by `blocks`
+        Tag::CodeBlock(_) => (),
+        Tag::List(start) => collector.start_list(start.is_some()),
+        Tag::Item => collector.start_item(),
+        Tag::Table(_) => collector.start_table(),
+        Tag::TableHead => collector.start_row(true),
+        Tag::TableRow => collector.start_row(false),
+        Tag::TableCell => collector.start_cell(),
+        Tag::Emphasis => collector.push_inline(Inline::Italic),
+        Tag::Strong => collector.push_inline(Inline::Bold),
+        Tag::Strikethrough => collector.push_inline(Inline::Strike),
+        Tag::Link(_, url, _) => collector.push_inline(Inline::Link(url.to_string())),
+        // Remote images are not fetched; their description is kept as text.
+        Tag::Image(..) | Tag::FootnoteDefinition(_) => (),
+    }
+}
+
+fn end(collector: &mut BlockCollector, tag: Tag<'_>) {
+    match tag {
+        Tag::Paragraph => collector.end_paragraph(),
+        Tag::Heading(..) => collector.end_heading(),
+        Tag::BlockQuote => collector.end_quote(),
+        Tag::CodeBlock(_) => (),
+        Tag::List(_) => collector.end_list(),
+        Tag::Item => collector.end_item(),
+        Tag::Table(_) => collector.end_table(),
+        Tag::TableHead | Tag::TableRow => collector.end_row(),
+        Tag::TableCell => collector.end_cell(),
+        Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link(..) => collector.pop_inline(),
+        Tag::Image(..) | Tag::FootnoteDefinition(_) => (),
+    }
+}
+
+/// Blocks of a Markdown document.
+pub fn blocks(markdown: &str) -> Vec<Block> {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let mut collector = BlockCollector::new();
+    // Code blocks are collected whole, to drop their final line feed.
+    let mut code_block: Option<String> = None;
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => code_block = Some(String::new()),
+            Event::End(Tag::CodeBlock(_)) => {
+                collector.start_paragraph();
+                collector.push_inline(Inline::Code);
+                collector.text(code_block.take().unwrap_or_default().trim_end_matches('\n'));
+                collector.pop_inline();
+                collector.end_paragraph();
+            }
+            Event::Text(text) if code_block.is_some() => {
+                code_block.get_or_insert_with(String::new).push_str(&text)
+            }
+            Event::Start(tag) => start(&mut collector, tag),
+            Event::End(tag) => end(&mut collector, tag),
+            Event::Text(text) => collector.text(&text),
+            Event::Code(code) => {
+                collector.push_inline(Inline::Code);
+                collector.text(&code);
+                collector.pop_inline();
+            }
+            Event::SoftBreak => collector.text(" "),
+            Event::HardBreak => collector.line_break(),
+            Event::TaskListMarker(checked) => {
+                collector.text(if checked { "\u{2611} " } else { "\u{2610} " })
+            }
+            Event::Html(_) | Event::FootnoteReference(_) | Event::Rule => (),
+        }
+    }
+    collector.finish()
+}
+
+/// Converts a Markdown document to RTF.
+pub fn to_rtf(markdown: &str) -> String {
+    blocks(markdown)
+        .into_iter()
+        .fold(RtfBuilder::new(), RtfBuilder::block)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::writer::{List, Paragraph, Row, Run, Table};
+
+    #[test]
+    fn test_blocks() {
+        let markdown = "# Title\n\nSome **bold** and *italic* `code`.\n\n\
+                        - one\n  - nested\n- two\n\n\
+                        | a | b |\n|---|---|\n| 1 | 2 |\n";
+        assert_eq!(
+            blocks(markdown),
+            vec![
+                Block::Paragraph(Paragraph::new().heading(1).text("Title")),
+                Block::Paragraph(
+                    Paragraph::new()
+                        .text("Some ")
+                        .run(Run::new("bold").bold())
+                        .text(" and ")
+                        .run(Run::new("italic").italic())
+                        .text(" ")
+                        .run(Run::new("code").code())
+                        .text(".")
+                ),
+                Block::List(
+                    List::bulleted()
+                        .item(Paragraph::new().text("one"))
+                        .nested_item(Paragraph::new().text("nested"), 1)
+                        .item(Paragraph::new().text("two"))
+                ),
+                Block::Table(
+                    Table::new()
+                        .row(
+                            Row::new()
+                                .header()
+                                .cell(Paragraph::new().text("a"))
+                                .cell(Paragraph::new().text("b"))
+                        )
+                        .row(
+                            Row::new()
+                                .cell(Paragraph::new().text("1"))
+                                .cell(Paragraph::new().text("2"))
+                        )
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//! Plain text to RTF conversion.
+
+use super::writer::{Block, Paragraph, RtfBuilder};
+
+/// Blocks of `text`, one paragraph per line.
+pub fn blocks(text: &str) -> Vec<Block> {
+    text.lines()
+        .map(|line| Block::Paragraph(Paragraph::new().text(line)))
+        .collect()
+}
+
+/// Converts plain text to an RTF document.
+pub fn to_rtf(text: &str) -> String {
+    blocks(text)
+        .into_iter()
+        .fold(RtfBuilder::new(), RtfBuilder::block)
+        .build()
+}
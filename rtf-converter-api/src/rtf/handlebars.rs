@@ -0,0 +1,783 @@
+//! Handlebars-style templates, with loops and conditionals.
+//!
+//! Tags are matched on the text of the template, as `{{name}}` placeholders
+//! are (see [template](super::template)):
+//!
+//! - `{{name}}` writes the value at `name`;
+//! - `{{#each name}}...{{else}}...{{/each}}` repeats its content for each item
+//!   of an array, or value of an object, and writes the `{{else}}` part when
+//!   there are none;
+//! - `{{#if name}}...{{else}}...{{/if}}` writes its content when the value is
+//!   truthy (not missing, `null`, `false`, `0`, `""` or `[]`), and
+//!   `{{#unless name}}` when it is not.
+//!
+//! Within `#each`, `this` is the current item and `@index`, `@key`,
+//! `@first` and `@last` describe its position; other names are looked up in
+//! the item first, then in the enclosing items and the data.
+//!
+//! Blocks repeat and drop the tokens between their tags, so the tags of a
+//! block must be at the same group depth, e.g. both in the body of a
+//! paragraph or in sibling formatting groups.
+//!
+//! Directives on a structural unit instead apply to the whole row, paragraph
+//! or section they are written in, and need no closing tag:
+//! `{{#each-row items}}` anywhere in a table row (`\trowd ... \row`) repeats
+//! the row, definition included, for each item, and `{{#if-paragraph name}}`
+//! or `{{#unless-section name}}` keep their paragraph or section only when
+//! the value is, or is not, truthy. Any of `each`, `if` and `unless` combines
+//! with any of `row`, `paragraph` and `section`.
+
+use std::borrow::Cow;
+
+use rtf_grimoire::tokenizer::Token;
+use serde_json::{json, Value};
+
+use super::engine::TemplateEngine;
+use super::error::{Limit, LimitExceeded, TemplateError};
+use super::normalize::{clone_token, destination, flatten, parse, write, Node};
+use super::schema::{merge_field, FieldKind, TemplateField};
+use super::fragment::value_nodes;
+use super::template::{
+    check_output, find_tags, is_name, is_text_group, lookup, replace_tags, OutputSize, RenderReport,
+};
+use crate::settings::Limits;
+
+/// Name of the control word standing for a tag, which no RTF can contain.
+const MARKER: &str = "{{}}";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BlockKind {
+    Each,
+    If,
+    Unless,
+}
+
+impl BlockKind {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "each" => Some(BlockKind::Each),
+            "if" => Some(BlockKind::If),
+            "unless" => Some(BlockKind::Unless),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            BlockKind::Each => "each",
+            BlockKind::If => "if",
+            BlockKind::Unless => "unless",
+        }
+    }
+}
+
+/// Structural unit a directive applies to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Unit {
+    Row,
+    Paragraph,
+    Section,
+}
+
+impl Unit {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "row" => Some(Unit::Row),
+            "paragraph" => Some(Unit::Paragraph),
+            "section" => Some(Unit::Section),
+            _ => None,
+        }
+    }
+
+    /// Control words ending the unit.
+    fn ends(self) -> &'static [&'static str] {
+        match self {
+            Unit::Row => &["row"],
+            Unit::Paragraph => &["par", "cell", "row", "sect"],
+            Unit::Section => &["sect"],
+        }
+    }
+
+    /// Control word resetting the properties of the unit, where it starts.
+    fn reset(self) -> &'static str {
+        match self {
+            Unit::Row => "trowd",
+            Unit::Paragraph => "pard",
+            Unit::Section => "sectd",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Tag {
+    Value(String),
+    Open(BlockKind, String),
+    Directive(BlockKind, Unit, String),
+    Else,
+    Close(BlockKind),
+}
+
+fn is_path(path: &str) -> bool {
+    is_name(path.strip_prefix('@').unwrap_or(path))
+}
+
+/// Whether `tag` is meant as a tag; malformed block tags are, so that
+/// compiling reports them.
+fn is_tag(tag: &str) -> bool {
+    tag.starts_with(['#', '/']) || tag == "else" || is_path(tag)
+}
+
+fn parse_tag(tag: &str) -> Result<Tag, TemplateError> {
+    let invalid = || TemplateError::Syntax(format!("invalid tag {{{{{tag}}}}}"));
+    if tag == "else" {
+        Ok(Tag::Else)
+    } else if let Some(block) = tag.strip_prefix('#') {
+        let (kind, path) = block.split_once(' ').ok_or_else(invalid)?;
+        let path = path.trim();
+        if !is_path(path) {
+            return Err(invalid());
+        }
+        match kind.split_once('-') {
+            Some((kind, unit)) => {
+                let kind = BlockKind::parse(kind).ok_or_else(invalid)?;
+                let unit = Unit::parse(unit).ok_or_else(invalid)?;
+                Ok(Tag::Directive(kind, unit, path.to_string()))
+            }
+            None => Ok(Tag::Open(BlockKind::parse(kind).ok_or_else(invalid)?, path.to_string())),
+        }
+    } else if let Some(kind) = tag.strip_prefix('/') {
+        BlockKind::parse(kind.trim()).map(Tag::Close).ok_or_else(invalid)
+    } else if is_path(tag) {
+        Ok(Tag::Value(tag.to_string()))
+    } else {
+        Err(invalid())
+    }
+}
+
+fn marker(index: usize) -> Node {
+    Node::Token(Token::ControlWord {
+        name: MARKER.to_string(),
+        arg: Some(index as i32),
+    })
+}
+
+/// Index of the first directive tag marked in `node`.
+fn find_directive(node: &Node, tags: &[(String, usize)]) -> Option<usize> {
+    match node {
+        Node::Group(group) => group.iter().find_map(|node| find_directive(node, tags)),
+        Node::Token(Token::ControlWord { name, arg: Some(index) }) if name == MARKER => {
+            let index = *index as usize;
+            matches!(parse_tag(&tags[index].0), Ok(Tag::Directive(..))).then_some(index)
+        }
+        Node::Token(_) => None,
+    }
+}
+
+fn remove_marker(nodes: &mut Vec<Node>, index: usize) -> bool {
+    let position = nodes.iter().position(|node| {
+        matches!(node, Node::Token(Token::ControlWord { name, arg: Some(i) }) if name == MARKER && *i as usize == index)
+    });
+    match position {
+        Some(position) => {
+            nodes.remove(position);
+            true
+        }
+        None => nodes.iter_mut().any(|node| match node {
+            Node::Group(group) => remove_marker(group, index),
+            Node::Token(_) => false,
+        }),
+    }
+}
+
+/// Whether `node` is, or holds as text, one of the control words `names`.
+fn has_word(node: &Node, names: &[&str]) -> bool {
+    match node {
+        Node::Group(group) => is_text_group(group) && group.iter().any(|node| has_word(node, names)),
+        node => node.word().is_some_and(|(name, _)| names.contains(&name)),
+    }
+}
+
+/// Range of the nodes of `body` forming the `unit` that holds node `at`.
+fn unit_range(body: &[Node], at: usize, unit: Unit) -> Result<(usize, usize), TemplateError> {
+    // Document control words lead the body and belong to no unit.
+    let header = body.iter().take_while(|node| node.word().is_some()).count().min(at);
+    let mut start = None;
+    for (i, node) in body[header..at].iter().enumerate().rev() {
+        let i = header + i;
+        if has_word(node, unit.ends()) || matches!(node, Node::Group(group) if !is_text_group(group)) {
+            start = Some(i + 1);
+            break;
+        }
+        if node.word().map(|(name, _)| name) == Some(unit.reset()) {
+            start = Some(i);
+            break;
+        }
+    }
+    let end = body[at..]
+        .iter()
+        .position(|node| has_word(node, unit.ends()))
+        .map(|i| at + i + 1);
+    match (unit, start, end) {
+        (Unit::Row, Some(start), Some(end)) if body[start..end].iter().any(|node| has_word(node, &["trowd"])) => {
+            Ok((start, end))
+        }
+        (Unit::Row, ..) => Err(TemplateError::Syntax("row directive outside of a table row".to_string())),
+        (_, start, end) => Ok((start.unwrap_or(header), end.unwrap_or(body.len()))),
+    }
+}
+
+/// Replaces the directives of `document` with blocks around their units.
+fn expand_directives(document: &mut [Node], tags: &mut Vec<(String, usize)>) -> Result<(), TemplateError> {
+    let Some(body) = document.iter_mut().find_map(|node| match node {
+        Node::Group(group) if destination(group) == Some("rtf") => Some(group),
+        _ => None,
+    }) else {
+        return Ok(());
+    };
+    // Last directive first, so that the units of earlier ones include the
+    // blocks of later ones.
+    while let Some((at, index)) = body
+        .iter()
+        .enumerate()
+        .rev()
+        .find_map(|(i, node)| find_directive(node, tags).map(|index| (i, index)))
+    {
+        let (tag, paragraph) = &tags[index];
+        let paragraph = *paragraph;
+        let Ok(Tag::Directive(kind, unit, path)) = parse_tag(tag) else {
+            unreachable!("found a directive");
+        };
+        let (start, end) = unit_range(body, at, unit)?;
+        tags.push((format!("/{}", kind.name()), paragraph));
+        body.insert(end, marker(tags.len() - 1));
+        remove_marker(body, index);
+        tags.push((format!("#{} {path}", kind.name()), paragraph));
+        body.insert(start, marker(tags.len() - 1));
+    }
+    Ok(())
+}
+
+/// Compiled part of a template; values and blocks keep the index of the
+/// paragraph holding their tag.
+#[derive(Debug)]
+enum Part {
+    Tokens(Vec<Token>),
+    Value(String, usize),
+    Block {
+        kind: BlockKind,
+        path: String,
+        paragraph: usize,
+        body: Vec<Part>,
+        otherwise: Vec<Part>,
+    },
+}
+
+/// Block being compiled.
+struct OpenBlock {
+    kind: BlockKind,
+    path: String,
+    paragraph: usize,
+    /// Group depth of the opening tag.
+    depth: usize,
+    /// Parts before the `{{else}}` tag, once it is found.
+    body: Option<Vec<Part>>,
+    /// Parts of the enclosing block, before this one.
+    outer: Vec<Part>,
+}
+
+fn push_token(parts: &mut Vec<Part>, token: Token) {
+    match parts.last_mut() {
+        Some(Part::Tokens(tokens)) => tokens.push(token),
+        _ => parts.push(Part::Tokens(vec![token])),
+    }
+}
+
+fn compile(token_stream: &[Token], limits: &Limits) -> Result<Vec<Part>, TemplateError> {
+    let mut document = parse(token_stream, limits.max_group_depth)?;
+    let found = find_tags(&document, is_tag);
+    let mut tags = Vec::new();
+    // Tags are replaced from the last to the first.
+    replace_tags(&mut document, is_tag, |tag| {
+        tags.push((tag.to_string(), found[found.len() - 1 - tags.len()].1));
+        vec![marker(tags.len() - 1)]
+    });
+    expand_directives(&mut document, &mut tags)?;
+    let mut tokens = Vec::new();
+    flatten(document, &mut tokens);
+
+    let mut parts = Vec::new();
+    let mut open: Vec<OpenBlock> = Vec::new();
+    let mut depth = 0usize;
+    let misplaced = |tag: &str| TemplateError::Syntax(format!("{{{{{tag}}}}} is not in the group of its block"));
+    for token in tokens {
+        let index = match &token {
+            Token::StartGroup => {
+                depth += 1;
+                push_token(&mut parts, token);
+                continue;
+            }
+            Token::EndGroup => {
+                depth = depth.saturating_sub(1);
+                push_token(&mut parts, token);
+                continue;
+            }
+            Token::ControlWord { name, arg: Some(index) } if name == MARKER => *index as usize,
+            _ => {
+                push_token(&mut parts, token);
+                continue;
+            }
+        };
+        let (tag, paragraph) = &tags[index];
+        let paragraph = *paragraph;
+        match parse_tag(tag)? {
+            Tag::Value(path) => parts.push(Part::Value(path, paragraph)),
+            // Directives left are outside of the document group.
+            Tag::Directive(..) => return Err(TemplateError::Syntax(format!("{{{{{tag}}}}} is not in the document"))),
+            // Blocks are rendered recursively, as groups are.
+            Tag::Open(..) if open.len() >= limits.max_group_depth => {
+                return Err(LimitExceeded::new(Limit::GroupDepth, limits.max_group_depth).into());
+            }
+            Tag::Open(kind, path) => open.push(OpenBlock {
+                kind,
+                path,
+                paragraph,
+                depth,
+                body: None,
+                outer: std::mem::take(&mut parts),
+            }),
+            Tag::Else => {
+                let block = open
+                    .last_mut()
+                    .ok_or_else(|| TemplateError::Syntax("{{else}} outside of a block".to_string()))?;
+                if block.depth != depth {
+                    return Err(misplaced(tag));
+                }
+                if block.body.is_some() {
+                    return Err(TemplateError::Syntax(format!("second {{{{else}}}} in {{{{#{}}}}}", block.path)));
+                }
+                block.body = Some(std::mem::take(&mut parts));
+            }
+            Tag::Close(kind) => {
+                let block = open
+                    .pop()
+                    .filter(|block| block.kind == kind)
+                    .ok_or_else(|| TemplateError::Syntax(format!("{{{{{tag}}}}} closes no block")))?;
+                if block.depth != depth {
+                    return Err(misplaced(tag));
+                }
+                let rest = std::mem::replace(&mut parts, block.outer);
+                let (body, otherwise) = match block.body {
+                    Some(body) => (body, rest),
+                    None => (rest, Vec::new()),
+                };
+                parts.push(Part::Block {
+                    kind,
+                    path: block.path,
+                    paragraph: block.paragraph,
+                    body,
+                    otherwise,
+                });
+            }
+        }
+    }
+    match open.last() {
+        Some(block) => Err(TemplateError::Syntax(format!("block on {:?} is not closed", block.path))),
+        None => Ok(parts),
+    }
+}
+
+/// Item of the data a template is rendered in.
+struct Scope<'a> {
+    value: &'a Value,
+    key: Option<&'a str>,
+    index: usize,
+    count: usize,
+}
+
+/// Value at `path` in the innermost scope holding it.
+fn resolve<'a>(scopes: &[Scope<'a>], path: &str) -> Option<Cow<'a, Value>> {
+    let scope = scopes.last()?;
+    match path {
+        "@index" => return Some(Cow::Owned(json!(scope.index))),
+        "@key" => return scope.key.map(|key| Cow::Owned(json!(key))),
+        "@first" => return Some(Cow::Owned(json!(scope.index == 0))),
+        "@last" => return Some(Cow::Owned(json!(scope.index + 1 == scope.count))),
+        "this" => return Some(Cow::Borrowed(scope.value)),
+        _ => (),
+    }
+    if let Some(path) = path.strip_prefix("this.") {
+        return lookup(scope.value, path).map(Cow::Borrowed);
+    }
+    scopes
+        .iter()
+        .rev()
+        .find_map(|scope| lookup(scope.value, path))
+        .map(Cow::Borrowed)
+}
+
+fn truthy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) | Some(Value::Bool(false)) => false,
+        Some(Value::Number(number)) => number.as_f64() != Some(0.0),
+        Some(Value::String(text)) => !text.is_empty(),
+        Some(Value::Array(items)) => !items.is_empty(),
+        Some(Value::Bool(true)) | Some(Value::Object(_)) => true,
+    }
+}
+
+/// Items an `#each` block iterates over, with their keys.
+fn items(value: Option<Cow<'_, Value>>) -> Vec<(Option<&str>, &Value)> {
+    match value {
+        Some(Cow::Borrowed(Value::Array(items))) => items.iter().map(|item| (None, item)).collect(),
+        Some(Cow::Borrowed(Value::Object(fields))) => {
+            fields.iter().map(|(key, value)| (Some(key.as_str()), value)).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Rendered tokens, within the output size limit.
+///
+/// Nested `#each` blocks multiply their content, so the size is checked as
+/// tokens are added rather than once the document is written.
+struct Output {
+    tokens: Vec<Token>,
+    size: OutputSize,
+}
+
+impl Output {
+    fn extend(&mut self, tokens: impl IntoIterator<Item = Token>) -> Result<(), LimitExceeded> {
+        for token in tokens {
+            self.size.add(&token)?;
+            self.tokens.push(token);
+        }
+        Ok(())
+    }
+}
+
+fn render_parts<'a>(
+    parts: &[Part],
+    scopes: &mut Vec<Scope<'a>>,
+    out: &mut Output,
+    report: &mut RenderReport,
+    limits: &Limits,
+) -> Result<(), TemplateError> {
+    for part in parts {
+        match part {
+            Part::Tokens(tokens) => out.extend(tokens.iter().map(clone_token))?,
+            Part::Value(path, _) => {
+                report.replaced += 1;
+                match resolve(scopes, path) {
+                    Some(value) => {
+                        let mut tokens = Vec::new();
+                        flatten(value_nodes(&value, limits)?, &mut tokens);
+                        out.extend(tokens)?;
+                    }
+                    None if !report.missing.contains(path) => report.missing.push(path.clone()),
+                    None => (),
+                }
+            }
+            Part::Block {
+                kind: BlockKind::Each,
+                path,
+                body,
+                otherwise,
+                ..
+            } => {
+                let items = items(resolve(scopes, path));
+                if items.is_empty() {
+                    render_parts(otherwise, scopes, out, report, limits)?;
+                }
+                let count = items.len();
+                for (index, (key, value)) in items.into_iter().enumerate() {
+                    scopes.push(Scope {
+                        value,
+                        key,
+                        index,
+                        count,
+                    });
+                    render_parts(body, scopes, out, report, limits)?;
+                    scopes.pop();
+                }
+            }
+            Part::Block {
+                kind,
+                path,
+                body,
+                otherwise,
+                ..
+            } => {
+                let value = resolve(scopes, path);
+                if truthy(value.as_deref()) == (*kind == BlockKind::If) {
+                    render_parts(body, scopes, out, report, limits)?;
+                } else {
+                    render_parts(otherwise, scopes, out, report, limits)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Name of the field at `path`, relative to the current item.
+fn field_name(path: &str) -> &str {
+    path.strip_prefix("this.").unwrap_or(path)
+}
+
+/// Names `parts` use in their own scope, outside of `#each` blocks.
+fn scope_names<'a>(parts: &'a [Part], names: &mut Vec<&'a str>) {
+    for part in parts {
+        match part {
+            Part::Tokens(_) => (),
+            Part::Value(path, _) => names.push(path),
+            Part::Block {
+                kind,
+                path,
+                body,
+                otherwise,
+                ..
+            } => {
+                names.push(path);
+                if *kind != BlockKind::Each {
+                    scope_names(body, names);
+                }
+                scope_names(otherwise, names);
+            }
+        }
+    }
+}
+
+/// Adds the fields used by `parts` to `fields`; names within `#each` blocks
+/// are taken as fields of the items, optional when one of the enclosing
+/// scopes, whose names are `outer`, uses them too.
+fn collect_fields(parts: &[Part], required: bool, outer: &[&str], fields: &mut Vec<TemplateField>) {
+    for part in parts {
+        match part {
+            Part::Tokens(_) => (),
+            Part::Value(path, _) if path.starts_with('@') => (),
+            Part::Value(path, paragraph) => {
+                let required = required && (path.starts_with("this.") || !outer.contains(&path.as_str()));
+                merge_field(fields, TemplateField::new(field_name(path), FieldKind::Scalar, required, *paragraph))
+            }
+            Part::Block {
+                kind,
+                path,
+                paragraph,
+                body,
+                otherwise,
+            } => {
+                if *kind == BlockKind::Each && !path.starts_with('@') {
+                    let required = required && !outer.contains(&path.as_str());
+                    let mut list = TemplateField::new(field_name(path), FieldKind::List, required, *paragraph);
+                    let mut names = outer.to_vec();
+                    scope_names(parts, &mut names);
+                    collect_fields(body, true, &names, &mut list.fields);
+                    merge_field(fields, list);
+                } else {
+                    if !path.starts_with('@') {
+                        merge_field(fields, TemplateField::new(field_name(path), FieldKind::Conditional, false, *paragraph));
+                    }
+                    collect_fields(body, false, outer, fields);
+                }
+                collect_fields(otherwise, false, outer, fields);
+            }
+        }
+    }
+}
+
+/// Handlebars-style templates.
+pub struct Handlebars;
+
+impl TemplateEngine for Handlebars {
+    fn name(&self) -> &'static str {
+        "handlebars"
+    }
+
+    fn version(&self) -> i32 {
+        1
+    }
+
+    fn compile(&self, token_stream: &[Token], limits: &Limits) -> Result<(), TemplateError> {
+        compile(token_stream, limits).map(|_| ())
+    }
+
+    fn fields(&self, token_stream: &[Token], limits: &Limits) -> Result<Vec<TemplateField>, TemplateError> {
+        let mut fields = Vec::new();
+        collect_fields(&compile(token_stream, limits)?, true, &[], &mut fields);
+        Ok(fields)
+    }
+
+    fn render(
+        &self,
+        token_stream: &[Token],
+        data: &Value,
+        limits: &Limits,
+    ) -> Result<(Vec<u8>, RenderReport), TemplateError> {
+        let parts = compile(token_stream, limits)?;
+        let mut scopes = vec![Scope {
+            value: data,
+            key: None,
+            index: 0,
+            count: 1,
+        }];
+        let mut out = Output {
+            tokens: Vec::new(),
+            size: OutputSize::new(limits),
+        };
+        let mut report = RenderReport::default();
+        render_parts(&parts, &mut scopes, &mut out, &mut report, limits)?;
+        report.missing.sort();
+        let rendered = write(&parse(&out.tokens, limits.max_group_depth)?);
+        Ok((check_output(rendered, limits)?, report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::to_text::tokenize;
+    use crate::settings::Limits;
+
+    fn rendered(rtf: &[u8], data: Value) -> Result<(String, RenderReport), TemplateError> {
+        let tokens = tokenize(rtf, &Limits::default()).unwrap();
+        let (out, report) = Handlebars.render(&tokens, &data, &Limits::default())?;
+        Ok((String::from_utf8(out).unwrap(), report))
+    }
+
+    #[test]
+    fn test_render_blocks() {
+        let rtf = br"{\rtf1\ansi {\b \{\{title\}\}}\par
+\{\{#each items\}\}{\i \{\{@index\}\}}. \{\{name\}\} \{\{#if @last\}\}end\{\{else\}\}{\rsid1 -}\{\{/if\}\}\par
+\{\{else\}\}none\par
+\{\{/each\}\}\{\{#unless items\}\}\{\{missing\}\}\{\{/unless\}\}}";
+        let data = json!({"title": "List", "items": [{"name": "a"}, {"name": "b"}]});
+        let (out, report) = rendered(rtf, data).unwrap();
+        assert_eq!(
+            out,
+            "{\\rtf1\\ansi{\\b List}\\par\n{\\i 0}. a {\\rsid1 -}\\par\n{\\i 1}. b end\\par\n}"
+        );
+        assert_eq!(report.replaced, 5);
+        assert!(report.missing.is_empty());
+
+        let (out, report) = rendered(rtf, json!({"items": []})).unwrap();
+        assert_eq!(out, "{\\rtf1\\ansi{\\b}\\par\nnone\\par\n}");
+        assert_eq!(report.missing, ["missing", "title"]);
+    }
+
+    #[test]
+    fn test_render_directives() {
+        let rtf = br"{\rtf1\ansi{\fonttbl{\f0 Arial;}}\pard Items:\par
+\trowd\cellx1000\cellx2000\pard\intbl{\b \{\{#each-row items\}\}\{\{name\}\}}\cell \{\{price\}\}\cell\row
+\pard A\par\pard {\i Note: \{\{#if-paragraph note\}\}\{\{note\}\}}\par\pard B\par}";
+        let data = json!({"items": [{"name": "a", "price": 1}, {"name": "b", "price": 2}], "note": ""});
+        let (out, report) = rendered(rtf, data).unwrap();
+        assert_eq!(
+            out,
+            "{\\rtf1\\ansi{\\fonttbl{\\f0 Arial;}}\\pard Items:\\par\n\
+             \\trowd\\cellx1000\\cellx2000\\pard\\intbl{\\b a}\\cell 1\\cell\\row\
+             \\trowd\\cellx1000\\cellx2000\\pard\\intbl{\\b b}\\cell 2\\cell\\row\
+             \\pard A\\par\n\\pard B\\par\n}"
+        );
+        assert_eq!(report.replaced, 4);
+
+        let rtf = br"{\rtf1 \pard A\par\{\{#each-row items\}\}\par}";
+        assert!(matches!(rendered(rtf, json!({})), Err(TemplateError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_render_section_directives() {
+        let rtf = br"{\rtf1\ansi\sectd A\par\sect\sectd\{\{#if-section show\}\}B\par\sect
+\sectd\{\{#each-section items\}\}\{\{this\}\}\par\sect\sectd\{\{#unless-section show\}\}C\par}";
+        let (out, _) = rendered(rtf, json!({"show": true, "items": [1, 2]})).unwrap();
+        assert_eq!(
+            out,
+            "{\\rtf1\\ansi\\sectd A\\par\n\\sect\\sectd B\\par\n\\sect\\sectd 1\\par\n\\sect\\sectd 2\\par\n\\sect}"
+        );
+        let (out, _) = rendered(rtf, json!({"show": false})).unwrap();
+        assert_eq!(out, "{\\rtf1\\ansi\\sectd A\\par\n\\sect\\sectd C\\par\n}");
+
+        for rtf in [
+            &br"{\rtf1 \{\{#if-page a\}\}x}"[..],
+            br"{\rtf1 \{\{#if-section\}\}x}",
+            br"{\rtf1 x}\{\{#if-section a\}\}",
+            br"{\rtf1 \{\{#if-section a\}\}x\{\{/if\}\}}",
+        ] {
+            assert!(matches!(rendered(rtf, json!({})), Err(TemplateError::Syntax(_))), "{rtf:?}");
+        }
+    }
+
+    #[test]
+    fn test_fields() {
+        let rtf = br"{\rtf1\ansi \{\{title\}\}\par
+\{\{#each items\}\}\{\{this.name\}\} \{\{@index\}\}\{\{#if note\}\}\{\{note\}\}\{\{/if\}\}\par\{\{/each\}\}\{\{title\}\}}";
+        let tokens = tokenize(&rtf[..], &Limits::default()).unwrap();
+        let fields = Handlebars.fields(&tokens, &Limits::default()).unwrap();
+        let names: Vec<(&str, FieldKind, bool)> = fields.iter().map(|f| (f.name.as_str(), f.kind, f.required)).collect();
+        assert_eq!(names, [("title", FieldKind::Scalar, true), ("items", FieldKind::List, true)]);
+        assert_eq!(fields[0].locations.len(), 2);
+        assert_eq!(fields[1].locations[0].paragraph, 1);
+        let items: Vec<(&str, FieldKind, bool)> =
+            fields[1].fields.iter().map(|f| (f.name.as_str(), f.kind, f.required)).collect();
+        assert_eq!(
+            items,
+            [("name", FieldKind::Scalar, true), ("note", FieldKind::Scalar, false)]
+        );
+    }
+
+    #[test]
+    fn test_outer_fields() {
+        let rtf = br"{\rtf1 \{\{currency\}\}\par\{\{#each items\}\}\{\{price\}\} \{\{currency\}\}\{\{this.code\}\}\par\{\{/each\}\}}";
+        let tokens = tokenize(&rtf[..], &Limits::default()).unwrap();
+        let fields = Handlebars.fields(&tokens, &Limits::default()).unwrap();
+        assert_eq!(fields[0].name, "currency");
+        assert!(fields[0].required);
+        let items: Vec<(&str, bool)> = fields[1].fields.iter().map(|f| (f.name.as_str(), f.required)).collect();
+        assert_eq!(items, [("price", true), ("currency", false), ("code", true)]);
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        for rtf in [
+            &br"{\rtf1 \{\{#each items\}\}x}"[..],
+            br"{\rtf1 \{\{#if a\}\}x\{\{/each\}\}}",
+            br"{\rtf1 \{\{#with a\}\}x}",
+            br"{\rtf1 {\b \{\{#if a\}\}}x\{\{/if\}\}}",
+        ] {
+            assert!(matches!(rendered(rtf, json!({})), Err(TemplateError::Syntax(_))));
+        }
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let limits = Limits {
+            max_group_depth: 2,
+            ..Limits::default()
+        };
+        let exceeded = Err(TemplateError::Limit(LimitExceeded::new(Limit::GroupDepth, 2)));
+        for rtf in [
+            &br"{\rtf1 {{x}}}"[..],
+            br"{\rtf1 \{\{#if a\}\}\{\{#if b\}\}\{\{#if c\}\}x\{\{/if\}\}\{\{/if\}\}\{\{/if\}\}}",
+        ] {
+            let tokens = tokenize(rtf, &limits).unwrap();
+            assert_eq!(Handlebars.compile(&tokens, &limits), exceeded);
+        }
+    }
+
+    #[test]
+    fn test_output_limit() {
+        let rtf = br"{\rtf1 \{\{#each a\}\}\{\{#each a\}\}\{\{#each a\}\}\{\{text\}\}\{\{/each\}\}\{\{/each\}\}\{\{/each\}\}}";
+        let data = json!({"a": vec![0; 10], "text": "x".repeat(100)});
+        let limits = Limits {
+            max_output_bytes: 10_000,
+            ..Limits::default()
+        };
+        let tokens = tokenize(&rtf[..], &limits).unwrap();
+        assert_eq!(
+            Handlebars.render(&tokens, &data, &limits).err(),
+            Some(TemplateError::Limit(LimitExceeded::new(Limit::OutputBytes, 10_000)))
+        );
+        let data = json!({"a": [0, 1], "text": "x"});
+        let (out, _) = Handlebars.render(&tokens, &data, &limits).unwrap();
+        assert_eq!(out, b"{\\rtf1 xxxxxxxx}");
+    }
+}
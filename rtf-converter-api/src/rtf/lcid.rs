@@ -0,0 +1,239 @@
+//! Windows language identifiers (LCIDs), as used by `\lang`, `\deflang` and
+//! their associated-character variants.
+
+/// Primary language part of an LCID.
+fn primary_language(lcid: i32) -> i32 {
+    lcid & 0x3FF
+}
+
+/// ANSI codepage Windows uses for the language `lcid`.
+///
+/// Returns `None` for unknown languages and for languages which only exist
+/// in Unicode (e.g. Hindi).
+pub fn ansi_codepage(lcid: i32) -> Option<u16> {
+    // Sublanguages which differ from their primary language.
+    match lcid {
+        // Chinese: Taiwan, Hong Kong SAR and Macao SAR use traditional characters
+        0x0404 | 0x0C04 | 0x1404 => return Some(950),
+        // Serbian (Cyrillic), Bosnian (Cyrillic)
+        0x0C1A | 0x201A => return Some(1251),
+        // Azeri (Cyrillic), Uzbek (Cyrillic)
+        0x082C | 0x0843 => return Some(1251),
+        _ => (),
+    }
+
+    let codepage = match primary_language(lcid) {
+        // Thai
+        0x1E => 874,
+        // Japanese
+        0x11 => 932,
+        // Chinese (simplified)
+        0x04 => 936,
+        // Korean
+        0x12 => 949,
+        // Central European: Czech, Hungarian, Polish, Romanian, Croatian/Serbian (Latin),
+        // Slovak, Albanian, Slovenian
+        0x05 | 0x0E | 0x15 | 0x18 | 0x1A | 0x1B | 0x1C | 0x24 => 1250,
+        // Cyrillic: Bulgarian, Russian, Ukrainian, Belarusian, Macedonian, Kazakh,
+        // Kyrgyz, Tatar, Mongolian
+        0x02 | 0x19 | 0x22 | 0x23 | 0x2F | 0x3F | 0x40 | 0x44 | 0x50 => 1251,
+        // Western European: Catalan, Danish, German, English, Spanish, Finnish, French,
+        // Icelandic, Italian, Dutch, Norwegian, Portuguese, Swedish, Indonesian, Basque,
+        // Afrikaans, Faroese, Malay, Swahili, Galician
+        0x03 | 0x06 | 0x07 | 0x09 | 0x0A | 0x0B | 0x0C | 0x0F | 0x10 | 0x13 | 0x14 | 0x16
+        | 0x1D | 0x21 | 0x2D | 0x36 | 0x38 | 0x3E | 0x41 | 0x56 => 1252,
+        // Greek
+        0x08 => 1253,
+        // Turkish, Azeri (Latin), Uzbek (Latin)
+        0x1F | 0x2C | 0x43 => 1254,
+        // Hebrew, Yiddish
+        0x0D | 0x3D => 1255,
+        // Arabic, Urdu, Farsi
+        0x01 | 0x20 | 0x29 => 1256,
+        // Baltic: Estonian, Latvian, Lithuanian
+        0x25..=0x27 => 1257,
+        // Vietnamese
+        0x2A => 1258,
+        _ => return None,
+    };
+    Some(codepage)
+}
+
+/// BCP-47 language tag of the language `lcid`.
+///
+/// Locales Windows defines are mapped to a language and region (e.g.
+/// `fr-CA`); other sublanguages fall back to their primary language (e.g.
+/// `fr`). Returns `None` for unknown languages and for `\lang1024`, which
+/// marks text excluded from proofing.
+pub fn bcp47(lcid: i32) -> Option<&'static str> {
+    let tag = match lcid {
+        0x0401 => "ar-SA",
+        0x0402 => "bg-BG",
+        0x0403 => "ca-ES",
+        0x0404 => "zh-TW",
+        0x0405 => "cs-CZ",
+        0x0406 => "da-DK",
+        0x0407 => "de-DE",
+        0x0408 => "el-GR",
+        0x0409 => "en-US",
+        0x040A | 0x0C0A => "es-ES",
+        0x040B => "fi-FI",
+        0x040C => "fr-FR",
+        0x040D => "he-IL",
+        0x040E => "hu-HU",
+        0x040F => "is-IS",
+        0x0410 => "it-IT",
+        0x0411 => "ja-JP",
+        0x0412 => "ko-KR",
+        0x0413 => "nl-NL",
+        0x0414 => "nb-NO",
+        0x0415 => "pl-PL",
+        0x0416 => "pt-BR",
+        0x0418 => "ro-RO",
+        0x0419 => "ru-RU",
+        0x041A => "hr-HR",
+        0x041B => "sk-SK",
+        0x041C => "sq-AL",
+        0x041D => "sv-SE",
+        0x041E => "th-TH",
+        0x041F => "tr-TR",
+        0x0420 => "ur-PK",
+        0x0421 => "id-ID",
+        0x0422 => "uk-UA",
+        0x0423 => "be-BY",
+        0x0424 => "sl-SI",
+        0x0425 => "et-EE",
+        0x0426 => "lv-LV",
+        0x0427 => "lt-LT",
+        0x0429 => "fa-IR",
+        0x042A => "vi-VN",
+        0x042D => "eu-ES",
+        0x042F => "mk-MK",
+        0x0436 => "af-ZA",
+        0x0437 => "ka-GE",
+        0x0439 => "hi-IN",
+        0x043E => "ms-MY",
+        0x043F => "kk-KZ",
+        0x0441 => "sw-KE",
+        0x0456 => "gl-ES",
+        0x0801 => "ar-IQ",
+        0x0804 => "zh-CN",
+        0x0807 => "de-CH",
+        0x0809 => "en-GB",
+        0x080A => "es-MX",
+        0x080C => "fr-BE",
+        0x0810 => "it-CH",
+        0x0813 => "nl-BE",
+        0x0814 => "nn-NO",
+        0x0816 => "pt-PT",
+        0x081A => "sr-Latn-CS",
+        0x0C01 => "ar-EG",
+        0x0C04 => "zh-HK",
+        0x0C07 => "de-AT",
+        0x0C09 => "en-AU",
+        0x0C0C => "fr-CA",
+        0x0C1A => "sr-Cyrl-CS",
+        0x1004 => "zh-SG",
+        0x1009 => "en-CA",
+        0x100C => "fr-CH",
+        0x1404 => "zh-MO",
+        0x1409 => "en-NZ",
+        0x1809 => "en-IE",
+        0x1C09 => "en-ZA",
+        0x241A => "sr-Latn-RS",
+        0x281A => "sr-Cyrl-RS",
+        0x4009 => "en-IN",
+        _ => return primary_language_tag(primary_language(lcid)),
+    };
+    Some(tag)
+}
+
+/// BCP-47 tag of a primary language.
+fn primary_language_tag(language: i32) -> Option<&'static str> {
+    let tag = match language {
+        0x01 => "ar",
+        0x02 => "bg",
+        0x03 => "ca",
+        0x04 => "zh",
+        0x05 => "cs",
+        0x06 => "da",
+        0x07 => "de",
+        0x08 => "el",
+        0x09 => "en",
+        0x0A => "es",
+        0x0B => "fi",
+        0x0C => "fr",
+        0x0D => "he",
+        0x0E => "hu",
+        0x0F => "is",
+        0x10 => "it",
+        0x11 => "ja",
+        0x12 => "ko",
+        0x13 => "nl",
+        0x14 => "no",
+        0x15 => "pl",
+        0x16 => "pt",
+        0x18 => "ro",
+        0x19 => "ru",
+        0x1A => "hr",
+        0x1B => "sk",
+        0x1C => "sq",
+        0x1D => "sv",
+        0x1E => "th",
+        0x1F => "tr",
+        0x20 => "ur",
+        0x21 => "id",
+        0x22 => "uk",
+        0x23 => "be",
+        0x24 => "sl",
+        0x25 => "et",
+        0x26 => "lv",
+        0x27 => "lt",
+        0x29 => "fa",
+        0x2A => "vi",
+        0x2B => "hy",
+        0x2D => "eu",
+        0x2F => "mk",
+        0x36 => "af",
+        0x37 => "ka",
+        0x38 => "fo",
+        0x39 => "hi",
+        0x3D => "yi",
+        0x3E => "ms",
+        0x3F => "kk",
+        0x41 => "sw",
+        0x45 => "bn",
+        0x49 => "ta",
+        0x56 => "gl",
+        _ => return None,
+    };
+    Some(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ansi_codepage() {
+        assert_eq!(ansi_codepage(1033), Some(1252)); // en-US
+        assert_eq!(ansi_codepage(1049), Some(1251)); // ru-RU
+        assert_eq!(ansi_codepage(1037), Some(1255)); // he-IL
+        assert_eq!(ansi_codepage(1025), Some(1256)); // ar-SA
+        assert_eq!(ansi_codepage(2052), Some(936)); // zh-CN
+        assert_eq!(ansi_codepage(1028), Some(950)); // zh-TW
+        assert_eq!(ansi_codepage(0x041A), Some(1250)); // hr-HR
+        assert_eq!(ansi_codepage(0x0C1A), Some(1251)); // sr-Cyrl
+        assert_eq!(ansi_codepage(1081), None); // hi-IN
+    }
+
+    #[test]
+    fn test_bcp47() {
+        assert_eq!(bcp47(1033), Some("en-US"));
+        assert_eq!(bcp47(3084), Some("fr-CA"));
+        assert_eq!(bcp47(2052), Some("zh-CN"));
+        assert_eq!(bcp47(0x2C0C), Some("fr")); // fr-CM, by its primary language
+        assert_eq!(bcp47(1024), None); // no proofing
+        assert_eq!(bcp47(0), None);
+    }
+}
@@ -0,0 +1,279 @@
+//! Assembles [writer](super::writer) blocks from a stream of markup events.
+//!
+//! Shared by the Markdown and HTML converters, which translate their own
+//! syntax into calls on a [BlockCollector].
+
+use super::writer::{Block, Image, List, Paragraph, Row, Run, Table};
+
+/// Inline formatting that can be nested.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Inline {
+    /// Bold text.
+    Bold,
+    /// Italic text.
+    Italic,
+    /// Underlined text.
+    Underline,
+    /// Struck-through text.
+    Strike,
+    /// Code.
+    Code,
+    /// Hyperlink to a URL.
+    Link(String),
+}
+
+/// Open list, with the item paragraph being written.
+#[derive(Debug)]
+struct ListFrame {
+    item: Option<Paragraph>,
+}
+
+/// Builds blocks from nested markup events.
+///
+/// Text goes to the innermost open container: a table cell, then a list
+/// item, then a free-standing paragraph.
+#[derive(Debug, Default)]
+pub struct BlockCollector {
+    blocks: Vec<Block>,
+    paragraph: Option<Paragraph>,
+    inline: Vec<Inline>,
+    heading: Option<u8>,
+    quote: u32,
+    list: Option<List>,
+    lists: Vec<ListFrame>,
+    table: Option<Table>,
+    row: Option<Row>,
+    header_row: bool,
+    cell: Option<Paragraph>,
+}
+
+impl BlockCollector {
+    /// Empty collector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Blocks collected so far, closing anything left open.
+    pub fn finish(mut self) -> Vec<Block> {
+        self.end_paragraph();
+        while !self.lists.is_empty() {
+            self.end_list();
+        }
+        if self.table.is_some() {
+            self.end_table();
+        }
+        self.blocks
+    }
+
+    /// Run with the formatting currently in effect.
+    fn run(&self, text: &str) -> Run {
+        let mut run = Run::new(text);
+        for inline in &self.inline {
+            run = match inline {
+                Inline::Bold => run.bold(),
+                Inline::Italic => run.italic(),
+                Inline::Underline => run.underline(),
+                Inline::Strike => run.strike(),
+                Inline::Code => run.code(),
+                Inline::Link(url) => run.link(url.clone()),
+            };
+        }
+        run
+    }
+
+    /// Paragraph text is currently written to.
+    fn target(&mut self) -> &mut Paragraph {
+        if let Some(cell) = &mut self.cell {
+            return cell;
+        }
+        if let Some(frame) = self.lists.last_mut() {
+            return frame.item.get_or_insert_with(Paragraph::new);
+        }
+        let heading = self.heading;
+        let quote = self.quote;
+        self.paragraph.get_or_insert_with(|| {
+            let paragraph = Paragraph::new().indent(quote);
+            match heading {
+                Some(level) => paragraph.heading(level),
+                None => paragraph,
+            }
+        })
+    }
+
+    /// Appends text with the formatting currently in effect.
+    pub fn text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let run = self.run(text);
+        let target = self.target();
+        *target = std::mem::take(target).run(run);
+    }
+
+    /// Starts a new line within the current paragraph.
+    pub fn line_break(&mut self) {
+        self.text("\n");
+    }
+
+    /// Opens inline formatting, until the matching [BlockCollector::pop_inline].
+    pub fn push_inline(&mut self, inline: Inline) {
+        self.inline.push(inline);
+    }
+
+    /// Closes the innermost inline formatting.
+    pub fn pop_inline(&mut self) {
+        self.inline.pop();
+    }
+
+    /// Starts a paragraph, ending the current one outside of cells and list items.
+    pub fn start_paragraph(&mut self) {
+        if self.cell.is_some() || !self.lists.is_empty() {
+            // Paragraphs within a cell or an item become lines.
+            let target = self.target();
+            if !target.is_empty() {
+                *target = std::mem::take(target).text("\n");
+            }
+        } else {
+            self.end_paragraph();
+        }
+    }
+
+    /// Ends the current free-standing paragraph.
+    pub fn end_paragraph(&mut self) {
+        if let Some(paragraph) = self.paragraph.take() {
+            if !paragraph.is_empty() {
+                self.blocks.push(Block::Paragraph(paragraph));
+            }
+        }
+    }
+
+    /// Starts a heading of `level`.
+    pub fn start_heading(&mut self, level: u8) {
+        self.end_paragraph();
+        self.heading = Some(level);
+    }
+
+    /// Ends the current heading.
+    pub fn end_heading(&mut self) {
+        self.end_paragraph();
+        self.heading = None;
+    }
+
+    /// Starts a block quote, indenting its paragraphs.
+    pub fn start_quote(&mut self) {
+        self.end_paragraph();
+        self.quote += 1;
+    }
+
+    /// Ends the current block quote.
+    pub fn end_quote(&mut self) {
+        self.end_paragraph();
+        self.quote = self.quote.saturating_sub(1);
+    }
+
+    /// Starts a list; lists opened within an item are nested in it.
+    pub fn start_list(&mut self, ordered: bool) {
+        if self.lists.is_empty() {
+            self.end_paragraph();
+            self.list = Some(if ordered {
+                List::numbered()
+            } else {
+                List::bulleted()
+            });
+        } else {
+            self.flush_item();
+        }
+        self.lists.push(ListFrame { item: None });
+    }
+
+    /// Ends the current list.
+    pub fn end_list(&mut self) {
+        self.flush_item();
+        self.lists.pop();
+        if self.lists.is_empty() {
+            if let Some(list) = self.list.take() {
+                self.blocks.push(Block::List(list));
+            }
+        }
+    }
+
+    /// Starts a list item.
+    pub fn start_item(&mut self) {
+        self.flush_item();
+    }
+
+    /// Ends the current list item.
+    pub fn end_item(&mut self) {
+        self.flush_item();
+    }
+
+    /// Adds the item being written to the list.
+    fn flush_item(&mut self) {
+        let level = self.lists.len().saturating_sub(1) as u32;
+        let item = self.lists.last_mut().and_then(|frame| frame.item.take());
+        if let Some(item) = item {
+            self.list = self.list.take().map(|list| list.nested_item(item, level));
+        }
+    }
+
+    /// Starts a table.
+    pub fn start_table(&mut self) {
+        self.end_paragraph();
+        self.table = Some(Table::new());
+    }
+
+    /// Ends the current table.
+    pub fn end_table(&mut self) {
+        self.end_row();
+        if let Some(table) = self.table.take() {
+            self.blocks.push(Block::Table(table));
+        }
+    }
+
+    /// Starts a table row; header rows are set in bold.
+    pub fn start_row(&mut self, header: bool) {
+        self.end_row();
+        self.row = Some(Row::new());
+        self.header_row = header;
+    }
+
+    /// Ends the current table row.
+    pub fn end_row(&mut self) {
+        self.end_cell();
+        if let Some(row) = self.row.take() {
+            let row = if self.header_row { row.header() } else { row };
+            self.table = self.table.take().map(|table| table.row(row));
+        }
+    }
+
+    /// Starts a table cell.
+    pub fn start_cell(&mut self) {
+        self.end_cell();
+        if self.table.is_none() {
+            return;
+        }
+        if self.row.is_none() {
+            self.row = Some(Row::new());
+        }
+        self.cell = Some(Paragraph::new());
+    }
+
+    /// Ends the current table cell.
+    pub fn end_cell(&mut self) {
+        if let Some(cell) = self.cell.take() {
+            self.row = self.row.take().map(|row| row.cell(cell));
+        }
+    }
+
+    /// Adds a picture in a paragraph of its own, or `alt` text where that is
+    /// not possible (in cells and list items).
+    pub fn image(&mut self, image: Option<Image>, alt: &str) {
+        match image {
+            Some(image) if self.cell.is_none() && self.lists.is_empty() => {
+                self.end_paragraph();
+                self.blocks.push(Block::Image(image));
+            }
+            _ => self.text(alt),
+        }
+    }
+}
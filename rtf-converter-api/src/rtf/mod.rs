@@ -1,4 +1,25 @@
+pub mod analyze;
 pub mod control;
+pub mod diff;
+pub mod document;
+pub mod encoding;
+pub mod engine;
+pub mod error;
+pub mod font;
+pub mod fragment;
+pub mod from_html;
+pub mod from_markdown;
+pub mod from_text;
+pub mod handlebars;
+pub mod lcid;
+pub mod markup;
+pub mod normalize;
+pub mod options;
+pub mod sanitize;
+pub mod schema;
+pub mod template;
 pub mod to_eml;
+pub mod to_html;
 pub mod to_pdf;
-pub mod to_text;
\ No newline at end of file
+pub mod to_text;
+pub mod writer;
@@ -0,0 +1,728 @@
+//! Canonical re-emission of RTF documents.
+//!
+//! The token stream is parsed into a tree of groups, stripped of editor
+//! bookkeeping (revision-save IDs, latent styles, theme data, ...) and of the
+//! fonts, colors and styles nothing refers to, then written back with a
+//! single, minimal spelling for every token.
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::{Cursor, Write};
+use std::time::Instant;
+
+use image::{imageops::FilterType, io::Reader as ImageReader, ImageFormat, ImageOutputFormat};
+use rtf_grimoire::tokenizer::Token;
+use serde::Serialize;
+
+use super::error::{Limit, LimitExceeded};
+use crate::settings::Limits;
+
+/// Destinations dropped as a whole: editor bookkeeping with no effect on rendering.
+const DROPPED_DESTINATIONS: &[&str] = &[
+    "rsidtbl",
+    "latentstyles",
+    "themedata",
+    "colorschememapping",
+    "datastore",
+];
+
+/// Control words referring to an entry of the font table.
+pub(crate) const FONT_REFERENCES: &[&str] = &[
+    "f", "af", "deff", "adeff", "pnf", "stshfdbch", "stshfloch", "stshfhich", "stshfbi",
+];
+
+/// Control words referring to an entry of the color table.
+pub(crate) const COLOR_REFERENCES: &[&str] = &[
+    "cf", "cb", "highlight", "ulc", "chcbpat", "chcfpat", "cbpat", "cfpat", "clcbpat",
+    "clcfpat", "clcbpatraw", "clcfpatraw", "trcbpat", "trcfpat", "tscellcbpat", "tscellcfpat", "brdrcf",
+    "pncf",
+];
+
+/// Control words numbering a style, both in the style sheet and where it is applied.
+const STYLE_NUMBERS: &[&str] = &["s", "cs", "ds", "ts"];
+
+/// Control words of a style sheet entry referring to other styles.
+const STYLE_LINKS: &[&str] = &["sbasedon", "snext", "slink"];
+
+/// Hex digits per line of picture data.
+const HEX_LINE_LEN: usize = 128;
+
+/// JPEG quality of resampled pictures.
+const JPEG_QUALITY: u8 = 85;
+
+/// Options of [normalize].
+#[derive(Clone, Debug, Default)]
+pub struct NormalizeOptions {
+    /// Pictures wider or taller than this many pixels are downsampled to fit.
+    pub max_image_dimension: Option<u32>,
+    /// Limits of the conversion.
+    pub limits: Limits,
+}
+
+/// What [normalize] removed or rewrote.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct NormalizeReport {
+    /// Bookkeeping destinations dropped (`\rsidtbl`, `\*\themedata`, ...).
+    pub dropped_destinations: usize,
+    /// Revision-save IDs (`\insrsid`, `\pararsid`, ...) stripped.
+    pub removed_rsids: usize,
+    /// Unused font table entries.
+    pub removed_fonts: usize,
+    /// Unused color table entries.
+    pub removed_colors: usize,
+    /// Unused style sheet entries.
+    pub removed_styles: usize,
+    /// Pictures downsampled to the maximum dimension.
+    pub resampled_images: usize,
+}
+
+/// Token or group of the document tree.
+#[derive(Debug)]
+pub(crate) enum Node {
+    Token(Token),
+    Group(Vec<Node>),
+}
+
+impl Node {
+    pub(crate) fn word(&self) -> Option<(&str, Option<i32>)> {
+        match self {
+            Node::Token(Token::ControlWord { name, arg }) => Some((name, *arg)),
+            _ => None,
+        }
+    }
+
+    fn group_mut(&mut self) -> Option<&mut Vec<Node>> {
+        match self {
+            Node::Group(group) => Some(group),
+            Node::Token(_) => None,
+        }
+    }
+}
+
+/// Copy of `token`, which does not implement [Clone].
+pub(crate) fn clone_token(token: &Token) -> Token {
+    match token {
+        Token::ControlSymbol(symbol) => Token::ControlSymbol(*symbol),
+        Token::ControlWord { name, arg } => Token::ControlWord { name: name.clone(), arg: *arg },
+        Token::ControlBin(bytes) => Token::ControlBin(bytes.clone()),
+        Token::Text(text) => Token::Text(text.clone()),
+        Token::StartGroup => Token::StartGroup,
+        Token::EndGroup => Token::EndGroup,
+        Token::Newline(bytes) => Token::Newline(bytes.clone()),
+    }
+}
+
+/// Builds the group tree, dropping line breaks and closing unbalanced groups.
+///
+/// Groups nested deeper than `max_depth` abort with a [LimitExceeded] error,
+/// which bounds the recursion of the functions walking the tree.
+pub(crate) fn parse(tokens: &[Token], max_depth: usize) -> Result<Vec<Node>, LimitExceeded> {
+    let mut stack: Vec<Vec<Node>> = vec![Vec::new()];
+    for token in tokens {
+        match token {
+            Token::StartGroup if stack.len() > max_depth => {
+                return Err(LimitExceeded::new(Limit::GroupDepth, max_depth));
+            }
+            Token::StartGroup => stack.push(Vec::new()),
+            Token::EndGroup if stack.len() > 1 => {
+                let group = stack.pop().expect("a group is open");
+                stack.last_mut().expect("root is open").push(Node::Group(group));
+            }
+            Token::EndGroup | Token::Newline(_) => (),
+            token => stack.last_mut().expect("root is open").push(Node::Token(clone_token(token))),
+        }
+    }
+    while stack.len() > 1 {
+        let group = stack.pop().expect("a group is open");
+        stack.last_mut().expect("root is open").push(Node::Group(group));
+    }
+    Ok(stack.pop().unwrap_or_default())
+}
+
+/// Turns the group tree back into a token stream.
+pub(crate) fn flatten(nodes: Vec<Node>, out: &mut Vec<Token>) {
+    for node in nodes {
+        match node {
+            Node::Token(token) => out.push(token),
+            Node::Group(group) => {
+                out.push(Token::StartGroup);
+                flatten(group, out);
+                out.push(Token::EndGroup);
+            }
+        }
+    }
+}
+
+/// Destination (or first control word) of a group, ignoring a leading `\*`.
+pub(crate) fn destination(group: &[Node]) -> Option<&str> {
+    let mut nodes = group.iter();
+    let mut first = nodes.next()?;
+    if matches!(first, Node::Token(Token::ControlSymbol('*'))) {
+        first = nodes.next()?;
+    }
+    first.word().map(|(name, _)| name)
+}
+
+/// Subgroup of `group` with the destination `name`.
+pub(crate) fn subgroup<'a>(group: &'a [Node], name: &str) -> Option<&'a Vec<Node>> {
+    group.iter().find_map(|node| match node {
+        Node::Group(sub) if destination(sub) == Some(name) => Some(sub),
+        _ => None,
+    })
+}
+
+/// Bytes of the text of a group and its subgroups, `\'hh` included.
+pub(crate) fn group_bytes(nodes: &[Node]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for node in nodes {
+        match node {
+            Node::Group(group) => bytes.extend(group_bytes(group)),
+            Node::Token(Token::Text(text)) => bytes.extend_from_slice(text),
+            Node::Token(Token::ControlWord { name, arg: Some(value) }) if name == "'" => {
+                bytes.push(*value as u8)
+            }
+            Node::Token(_) => (),
+        }
+    }
+    bytes
+}
+
+/// Trimmed text of a group and its subgroups.
+pub(crate) fn group_text(nodes: &[Node]) -> String {
+    String::from_utf8_lossy(&group_bytes(nodes)).trim().to_string()
+}
+
+/// Calls `f` on every control word, not descending into groups for which `skip` holds.
+pub(crate) fn visit_words<'a>(
+    nodes: &'a [Node],
+    skip: &dyn Fn(&[Node]) -> bool,
+    f: &mut dyn FnMut(&'a str, Option<i32>),
+) {
+    for node in nodes {
+        match node {
+            Node::Group(group) if !skip(group) => visit_words(group, skip, f),
+            Node::Group(_) => (),
+            node => {
+                if let Some((name, arg)) = node.word() {
+                    f(name, arg);
+                }
+            }
+        }
+    }
+}
+
+/// Calls `f` on the argument of every control word.
+fn rewrite_words(nodes: &mut [Node], f: &mut dyn FnMut(&str, &mut Option<i32>)) {
+    for node in nodes {
+        match node {
+            Node::Group(group) => rewrite_words(group, f),
+            Node::Token(Token::ControlWord { name, arg }) => f(name, arg),
+            Node::Token(_) => (),
+        }
+    }
+}
+
+/// Header table `name` (e.g. `fonttbl`), a direct child of the document group.
+fn table_mut<'a>(document: &'a mut [Node], name: &str) -> Option<&'a mut Vec<Node>> {
+    document
+        .iter_mut()
+        .filter_map(Node::group_mut)
+        .flat_map(|rtf| rtf.iter_mut())
+        .filter_map(Node::group_mut)
+        .find(|group| destination(group) == Some(name))
+}
+
+fn is_rsid(name: &str) -> bool {
+    name.ends_with("rsid") || name == "rsidroot"
+}
+
+/// Drops bookkeeping destinations, revision-save IDs and empty groups.
+fn strip(nodes: &mut Vec<Node>, report: &mut NormalizeReport) {
+    nodes.retain_mut(|node| match node {
+        Node::Group(group) => {
+            if destination(group).is_some_and(|name| DROPPED_DESTINATIONS.contains(&name)) {
+                report.dropped_destinations += 1;
+                return false;
+            }
+            strip(group, report);
+            !group.is_empty()
+        }
+        node => match node.word() {
+            Some((name, _)) if is_rsid(name) => {
+                report.removed_rsids += 1;
+                false
+            }
+            _ => true,
+        },
+    });
+}
+
+/// Number of a style sheet entry; entries without one define style 0.
+fn style_number(entry: &[Node]) -> i32 {
+    entry
+        .iter()
+        .filter_map(Node::word)
+        .find(|(name, _)| STYLE_NUMBERS.contains(name))
+        .map_or(0, |(_, arg)| arg.unwrap_or(0))
+}
+
+/// Removes styles neither applied in the document nor linked from an applied style.
+fn prune_styles(document: &mut [Node], report: &mut NormalizeReport) {
+    let mut used = BTreeSet::from([0]);
+    visit_words(
+        document,
+        &|group| destination(group) == Some("stylesheet"),
+        &mut |name, arg| {
+            if STYLE_NUMBERS.contains(&name) {
+                used.insert(arg.unwrap_or(0));
+            }
+        },
+    );
+    let Some(stylesheet) = table_mut(document, "stylesheet") else {
+        return;
+    };
+
+    let mut links: HashMap<i32, Vec<i32>> = HashMap::new();
+    for entry in stylesheet.iter().filter_map(|node| match node {
+        Node::Group(entry) => Some(entry),
+        Node::Token(_) => None,
+    }) {
+        let targets = links.entry(style_number(entry)).or_default();
+        visit_words(entry, &|_| false, &mut |name, arg| {
+            if STYLE_LINKS.contains(&name) {
+                targets.push(arg.unwrap_or(0));
+            }
+        });
+    }
+    let mut queue: Vec<i32> = used.iter().copied().collect();
+    while let Some(number) = queue.pop() {
+        for &target in links.get(&number).into_iter().flatten() {
+            if used.insert(target) {
+                queue.push(target);
+            }
+        }
+    }
+
+    stylesheet.retain(|node| match node {
+        Node::Group(entry) if !used.contains(&style_number(entry)) => {
+            report.removed_styles += 1;
+            false
+        }
+        _ => true,
+    });
+}
+
+/// Removes fonts nothing refers to.
+fn prune_fonts(document: &mut [Node], report: &mut NormalizeReport) {
+    let mut used = BTreeSet::new();
+    let mut default_font = false;
+    visit_words(
+        document,
+        &|group| destination(group) == Some("fonttbl"),
+        &mut |name, arg| {
+            if FONT_REFERENCES.contains(&name) {
+                used.insert(arg.unwrap_or(0));
+                default_font |= name == "deff";
+            }
+        },
+    );
+    if !default_font {
+        used.insert(0);
+    }
+    let Some(fonttbl) = table_mut(document, "fonttbl") else {
+        return;
+    };
+    fonttbl.retain(|node| match node {
+        Node::Group(entry) => {
+            let number = entry
+                .iter()
+                .filter_map(Node::word)
+                .find(|(name, _)| *name == "f")
+                .map(|(_, arg)| arg.unwrap_or(0));
+            if number.is_none_or(|number| used.contains(&number)) {
+                true
+            } else {
+                report.removed_fonts += 1;
+                false
+            }
+        }
+        Node::Token(_) => true,
+    });
+}
+
+/// Removes colors nothing refers to, renumbering the remaining references.
+///
+/// Color 0 (usually "auto") is always kept, and references past the end of
+/// the table are mapped to it.
+fn prune_colors(document: &mut [Node], report: &mut NormalizeReport) {
+    let mut used = BTreeSet::from([0]);
+    visit_words(document, &|_| false, &mut |name, arg| {
+        if COLOR_REFERENCES.contains(&name) {
+            used.insert(arg.unwrap_or(0));
+        }
+    });
+    let Some(colortbl) = table_mut(document, "colortbl") else {
+        return;
+    };
+
+    // Entries are positional, each ended by a `;`.
+    let mut head = Vec::new();
+    let mut entries: Vec<Vec<Node>> = Vec::new();
+    let mut entry = Vec::new();
+    for node in colortbl.drain(..) {
+        match node {
+            Node::Token(Token::Text(text)) => {
+                for _ in text.iter().filter(|&&byte| byte == b';') {
+                    entries.push(std::mem::take(&mut entry));
+                }
+            }
+            node if node.word().map(|(name, _)| name) == Some("colortbl") => head.push(node),
+            node => entry.push(node),
+        }
+    }
+
+    let mut mapping = HashMap::new();
+    *colortbl = head;
+    for (index, nodes) in entries.into_iter().enumerate() {
+        let index = index as i32;
+        if used.contains(&index) {
+            mapping.insert(index, mapping.len() as i32);
+            colortbl.extend(nodes);
+            colortbl.push(Node::Token(Token::Text(b";".to_vec())));
+        } else {
+            report.removed_colors += 1;
+        }
+    }
+    colortbl.extend(entry);
+
+    if report.removed_colors > 0 {
+        rewrite_words(document, &mut |name, arg| {
+            if COLOR_REFERENCES.contains(&name) {
+                *arg = Some(mapping.get(&arg.unwrap_or(0)).copied().unwrap_or(0));
+            }
+        });
+    }
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|digit| digit as u8)
+}
+
+/// Decoder limits of pictures: a decoded picture takes up to 4 bytes a pixel,
+/// within the output size.
+fn decode_limits(limits: &Limits) -> image::io::Limits {
+    let max_alloc = limits.max_output_bytes as u64;
+    let max_side = u32::try_from(max_alloc / 4).unwrap_or(u32::MAX);
+    let mut decode = image::io::Limits::default();
+    decode.max_image_width = Some(max_side);
+    decode.max_image_height = Some(max_side);
+    decode.max_alloc = Some(max_alloc);
+    decode
+}
+
+/// Downsamples `data` to fit within `max` pixels, keeping the aspect ratio.
+///
+/// Returns the re-encoded picture and its size, or `None` when the picture
+/// already fits or cannot be decoded within `limits`.
+fn resample(data: &[u8], format: ImageFormat, max: u32, limits: &Limits) -> Option<(Vec<u8>, u32, u32)> {
+    let mut reader = ImageReader::with_format(Cursor::new(data), format);
+    reader.limits(decode_limits(limits));
+    let image = reader.decode().ok()?;
+    if image.width() <= max && image.height() <= max {
+        return None;
+    }
+    let image = image.resize(max, max, FilterType::Triangle);
+    let output = match format {
+        ImageFormat::Jpeg => ImageOutputFormat::Jpeg(JPEG_QUALITY),
+        _ => ImageOutputFormat::Png,
+    };
+    let mut resampled = Cursor::new(Vec::new());
+    image.write_to(&mut resampled, output).ok()?;
+    Some((resampled.into_inner(), image.width(), image.height()))
+}
+
+/// Rewrites the data of a `\pict` group as wrapped hex, downsampling it if needed.
+///
+/// Returns whether the picture was downsampled.
+fn normalize_picture(pict: &mut Vec<Node>, options: &NormalizeOptions) -> bool {
+    let mut data = Vec::new();
+    let mut nibble = None;
+    pict.retain(|node| match node {
+        Node::Token(Token::Text(text)) => {
+            for value in text.iter().copied().filter_map(hex_value) {
+                match nibble.take() {
+                    Some(high) => data.push(high << 4 | value),
+                    None => nibble = Some(value),
+                }
+            }
+            false
+        }
+        Node::Token(Token::ControlBin(bytes)) => {
+            data.extend_from_slice(bytes);
+            false
+        }
+        _ => true,
+    });
+    if data.is_empty() {
+        return false;
+    }
+
+    let format = pict.iter().filter_map(Node::word).find_map(|(name, _)| match name {
+        "pngblip" => Some(ImageFormat::Png),
+        "jpegblip" => Some(ImageFormat::Jpeg),
+        _ => None,
+    });
+    let resampled = format
+        .zip(options.max_image_dimension)
+        .and_then(|(format, max)| resample(&data, format, max, &options.limits));
+    if let Some((resampled, width, height)) = &resampled {
+        data = resampled.clone();
+        rewrite_words(pict, &mut |name, arg| match name {
+            "picw" => *arg = Some(*width as i32),
+            "pich" => *arg = Some(*height as i32),
+            _ => (),
+        });
+    }
+
+    let mut hex = Vec::with_capacity(data.len() * 2 + data.len() / HEX_LINE_LEN + 1);
+    for line in data.chunks(HEX_LINE_LEN / 2) {
+        hex.push(b'\n');
+        for byte in line {
+            let _ = write!(hex, "{byte:02x}");
+        }
+    }
+    pict.push(Node::Token(Token::Text(hex)));
+    resampled.is_some()
+}
+
+/// Normalizes the data of every picture of the document, within the
+/// duration limit counted from `started`.
+fn normalize_pictures(
+    nodes: &mut [Node],
+    options: &NormalizeOptions,
+    started: Instant,
+    report: &mut NormalizeReport,
+) -> Result<(), LimitExceeded> {
+    for group in nodes.iter_mut().filter_map(Node::group_mut) {
+        if destination(group) == Some("pict") {
+            if started.elapsed() > options.limits.max_duration() {
+                return Err(LimitExceeded::new(Limit::Duration, options.limits.max_duration_ms));
+            }
+            if normalize_picture(group, options) {
+                report.resampled_images += 1;
+            }
+        } else {
+            normalize_pictures(group, options, started, report)?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether text starting with `byte` would run into a preceding control word.
+fn needs_delimiter(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b' ' || byte == b'-'
+}
+
+fn write_text(out: &mut Vec<u8>, text: &[u8]) {
+    for &byte in text {
+        match byte {
+            b'\\' | b'{' | b'}' => out.extend_from_slice(&[b'\\', byte]),
+            0x80.. => {
+                let _ = write!(out, "\\'{byte:02x}");
+            }
+            _ => out.push(byte),
+        }
+    }
+}
+
+/// Writes the document tree back as RTF.
+pub(crate) fn write(document: &[Node]) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_nodes(&mut out, document);
+    out
+}
+
+fn write_nodes(out: &mut Vec<u8>, nodes: &[Node]) {
+    for (i, node) in nodes.iter().enumerate() {
+        let token = match node {
+            Node::Group(group) => {
+                out.push(b'{');
+                write_nodes(out, group);
+                out.push(b'}');
+                continue;
+            }
+            Node::Token(token) => token,
+        };
+        match token {
+            Token::ControlWord { name, arg } if name == "'" => {
+                let _ = write!(out, "\\'{:02x}", arg.unwrap_or(0) & 0xFF);
+            }
+            Token::ControlWord { name, arg } => {
+                out.push(b'\\');
+                out.extend_from_slice(name.as_bytes());
+                if let Some(arg) = arg {
+                    let _ = write!(out, "{arg}");
+                }
+                match nodes.get(i + 1) {
+                    // Keeps paragraphs on lines of their own; line breaks are ignored.
+                    _ if name == "par" => out.push(b'\n'),
+                    Some(Node::Token(Token::Text(text)))
+                        if text.first().copied().is_some_and(needs_delimiter) =>
+                    {
+                        out.push(b' ')
+                    }
+                    _ => (),
+                }
+            }
+            Token::ControlSymbol(symbol) => {
+                out.push(b'\\');
+                let mut buf = [0; 4];
+                out.extend_from_slice(symbol.encode_utf8(&mut buf).as_bytes());
+            }
+            Token::ControlBin(bytes) => {
+                let _ = write!(out, "\\bin{} ", bytes.len());
+                out.extend_from_slice(bytes);
+            }
+            Token::Text(text) => write_text(out, text),
+            Token::StartGroup | Token::EndGroup | Token::Newline(_) => (),
+        }
+    }
+}
+
+/// Re-emits `token_stream` as canonical RTF.
+///
+/// Bookkeeping destinations (see [DROPPED_DESTINATIONS]), revision-save IDs
+/// and unused fonts, colors and styles are removed; picture data is written
+/// as hex, downsampled to [NormalizeOptions::max_image_dimension]; text bytes
+/// outside ASCII are written as `\'hh` and line breaks only follow `\par`.
+pub fn normalize(
+    token_stream: &[Token],
+    options: &NormalizeOptions,
+) -> Result<(Vec<u8>, NormalizeReport), LimitExceeded> {
+    let started = Instant::now();
+    let mut report = NormalizeReport::default();
+    let mut document = parse(token_stream, options.limits.max_group_depth)?;
+    strip(&mut document, &mut report);
+    prune_styles(&mut document, &mut report);
+    prune_fonts(&mut document, &mut report);
+    prune_colors(&mut document, &mut report);
+    normalize_pictures(&mut document, options, started, &mut report)?;
+
+    Ok((write(&document), report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::options::ConvertOptions;
+    use crate::rtf::to_text::{tokenize, write_plaintext};
+    use crate::settings::Limits;
+
+    fn normalized(rtf: &[u8], options: &NormalizeOptions) -> (String, NormalizeReport) {
+        let tokens = tokenize(rtf, &Limits::default()).unwrap();
+        let (out, report) = normalize(&tokens, options).unwrap();
+        (String::from_utf8(out).unwrap(), report)
+    }
+
+    fn plaintext(rtf: &[u8]) -> String {
+        let options = ConvertOptions::default();
+        let tokens = tokenize(rtf, &options.limits).unwrap();
+        let mut out = Vec::new();
+        write_plaintext(&tokens, &mut out, &options).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_strip_bookkeeping() {
+        let rtf = br"{\rtf1\ansi{\*\rsidtbl \rsid123\rsid456}{\*\themedata 0102}
+{\*\latentstyles\lsdstimax376{\lsdlockedexcept Normal;}}
+\pard\pararsid123 {\insrsid456 Caf\'e9}\par}";
+        let (out, report) = normalized(rtf, &NormalizeOptions::default());
+        assert_eq!(out, "{\\rtf1\\ansi\\pard{Caf\\'e9}\\par\n}");
+        assert_eq!(report.dropped_destinations, 3);
+        assert_eq!(report.removed_rsids, 2);
+        assert_eq!(plaintext(out.as_bytes()), plaintext(rtf));
+    }
+
+    #[test]
+    fn test_prune_tables() {
+        let rtf = br"{\rtf1\ansi\deff0{\fonttbl{\f0 Arial;}{\f1 Courier;}{\f2 Symbol;}}
+{\colortbl;\red255\green0\blue0;\red0\green0\blue255;}
+{\stylesheet{\s0 Normal;}{\s1\sbasedon0\snext2 heading 1;}{\s2 body;}{\s3 unused;}}
+\pard\s1 {\f2\cf2 Blue}\par}";
+        let (out, report) = normalized(rtf, &NormalizeOptions::default());
+        assert_eq!(report.removed_fonts, 1);
+        assert_eq!(report.removed_colors, 1);
+        assert_eq!(report.removed_styles, 1);
+        assert!(out.contains("{\\fonttbl{\\f0 Arial;}{\\f2 Symbol;}}"));
+        assert!(out.contains("{\\colortbl;\\red0\\green0\\blue255;}"));
+        assert!(out.contains("{\\f2\\cf1 Blue}"));
+        assert!(!out.contains("unused"));
+        assert_eq!(plaintext(out.as_bytes()), "Blue\n");
+    }
+
+    #[test]
+    fn test_prune_colors_of_table_styles() {
+        let rtf = br"{\rtf1\ansi{\colortbl;\red255\green0\blue0;\red0\green255\blue0;\red0\green0\blue255;}
+\trowd\tscellcbpat3\tscellcfpat1\cellx1000\pard\intbl x\cell\row}";
+        let (out, report) = normalized(rtf, &NormalizeOptions::default());
+        assert_eq!(report.removed_colors, 1);
+        assert!(out.contains("{\\colortbl;\\red255\\green0\\blue0;\\red0\\green0\\blue255;}"));
+        assert!(out.contains("\\tscellcbpat2\\tscellcfpat1"));
+    }
+
+    #[test]
+    fn test_prune_colors_out_of_table() {
+        let rtf = br"{\rtf1\ansi{\colortbl;\red255\green0\blue0;\red0\green0\blue255;}
+\pard{\cf2 Blue}{\cf9 Unknown}\par}";
+        let (out, report) = normalized(rtf, &NormalizeOptions::default());
+        assert_eq!(report.removed_colors, 1);
+        assert!(out.contains("{\\colortbl;\\red0\\green0\\blue255;}"));
+        assert!(out.contains("{\\cf1 Blue}{\\cf0 Unknown}"));
+    }
+
+    #[test]
+    fn test_resample_picture() {
+        let mut png = Cursor::new(Vec::new());
+        image::DynamicImage::new_rgb8(64, 32)
+            .write_to(&mut png, ImageOutputFormat::Png)
+            .unwrap();
+        let hex: String = png.get_ref().iter().map(|byte| format!("{byte:02X}")).collect();
+        let rtf = format!(
+            "{{\\rtf1{{\\pict\\pngblip\\picw64\\pich32\\picwgoal960\\pichgoal480\n{hex}}}}}"
+        );
+
+        let (out, report) = normalized(rtf.as_bytes(), &NormalizeOptions::default());
+        assert_eq!(report.resampled_images, 0);
+        assert!(out.contains(&hex.to_lowercase()[..HEX_LINE_LEN]));
+
+        let options = NormalizeOptions {
+            max_image_dimension: Some(16),
+            ..NormalizeOptions::default()
+        };
+        let (out, report) = normalized(rtf.as_bytes(), &options);
+        assert_eq!(report.resampled_images, 1);
+        assert!(out.contains("\\picw16\\pich8\\picwgoal960\\pichgoal480\n"));
+
+        // Pictures too large to decode within the limits are kept as they are.
+        let options = NormalizeOptions {
+            max_image_dimension: Some(16),
+            limits: Limits {
+                max_output_bytes: 200,
+                ..Limits::default()
+            },
+        };
+        let (out, report) = normalized(rtf.as_bytes(), &options);
+        assert_eq!(report.resampled_images, 0);
+        assert!(out.contains("\\picw64\\pich32"));
+
+        let options = NormalizeOptions {
+            max_image_dimension: Some(16),
+            limits: Limits {
+                max_duration_ms: 0,
+                ..Limits::default()
+            },
+        };
+        let tokens = tokenize(rtf.as_bytes(), &Limits::default()).unwrap();
+        assert_eq!(normalize(&tokens, &options).err(), Some(LimitExceeded::new(Limit::Duration, 0)));
+    }
+}
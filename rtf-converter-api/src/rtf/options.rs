@@ -0,0 +1,24 @@
+//! Caller-supplied options for a conversion.
+
+use encoding_rs::Encoding;
+
+use crate::settings::Limits;
+
+/// Options controlling how a token stream is converted.
+#[derive(Clone, Debug, Default)]
+pub struct ConvertOptions {
+    /// Resource limits enforced during the conversion.
+    pub limits: Limits,
+    /// Encoding used for all text, overriding whatever the document declares.
+    pub charset: Option<&'static Encoding>,
+}
+
+impl ConvertOptions {
+    /// Options with the given limits and no charset override.
+    pub fn new(limits: Limits) -> Self {
+        Self {
+            limits,
+            charset: None,
+        }
+    }
+}
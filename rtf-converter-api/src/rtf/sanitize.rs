@@ -0,0 +1,344 @@
+//! Removal of active and risky content from RTF documents.
+//!
+//! Embedded objects, fields pulling in external content, `\objupdate` and
+//! attached template links are removed, keeping the last rendered result of
+//! objects and fields so the document still reads the same. Constructs used
+//! by known exploits are reported, whether or not they are removed.
+
+use rtf_grimoire::tokenizer::Token;
+use serde::Serialize;
+
+use super::error::LimitExceeded;
+use super::normalize::{destination, group_bytes, group_text, parse, subgroup, write, Node};
+use crate::settings::Limits;
+
+/// Field instructions referring to external content.
+const EXTERNAL_FIELDS: &[&str] = &[
+    "INCLUDEPICTURE",
+    "INCLUDETEXT",
+    "INCLUDE",
+    "IMPORT",
+    "LINK",
+    "DDE",
+    "DDEAUTO",
+    "DATABASE",
+];
+
+/// Object data above this many bytes is reported as oversized.
+const MAX_OBJECT_DATA: usize = 1024 * 1024;
+
+/// Maximum length of the details quoted in a report.
+const MAX_DETAIL_LEN: usize = 200;
+
+/// Kind of content removed by [sanitize].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemovedKind {
+    /// Embedded or linked object (`\object`), replaced by its result.
+    Object,
+    /// Field referring to external content, replaced by its result.
+    ExternalField,
+    /// Automatic object update on open (`\objupdate`).
+    ObjectUpdate,
+    /// Attached template (`\*\template`).
+    Template,
+}
+
+/// Content removed by [sanitize].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Removed {
+    /// What was removed.
+    pub kind: RemovedKind,
+    /// Object class, field instruction or template path.
+    pub detail: Option<String>,
+}
+
+/// Construct used by known exploits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThreatKind {
+    /// Object data larger than any legitimate embedding, typical of heap sprays.
+    OversizedObjectData,
+    /// Equation Editor 3.0 object, target of CVE-2017-11882 and CVE-2018-0802.
+    EquationEditorObject,
+    /// `\levelnumbers` pointing outside of `\leveltext` (CVE-2012-2539 family).
+    MalformedLevelNumbers,
+    /// `\listoverridecount` other than 0, 1 or 9 (CVE-2012-2539).
+    InvalidListOverrideCount,
+    /// `pFragments` shape property (CVE-2010-3333).
+    ShapeFragments,
+}
+
+/// Exploit pattern found by [sanitize].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Threat {
+    /// Pattern found.
+    pub kind: ThreatKind,
+    /// Where or how the pattern was found.
+    pub detail: String,
+}
+
+/// What [sanitize] removed and found.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SanitizeReport {
+    /// Content removed from the document.
+    pub removed: Vec<Removed>,
+    /// Exploit patterns found in the original document.
+    pub threats: Vec<Threat>,
+}
+
+fn truncated(mut text: String) -> String {
+    if text.len() > MAX_DETAIL_LEN {
+        let mut end = MAX_DETAIL_LEN;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push('…');
+    }
+    text
+}
+
+/// Content of the subgroup `name` (e.g. `\result`), without its destination
+/// control word, as a group of its own.
+fn take_result(group: &mut Vec<Node>, name: &str) -> Option<Vec<Node>> {
+    let index = group.iter().position(|node| match node {
+        Node::Group(sub) => destination(sub) == Some(name),
+        Node::Token(_) => false,
+    })?;
+    let Node::Group(mut result) = group.remove(index) else {
+        return None;
+    };
+    let start = result
+        .iter()
+        .position(|node| node.word().is_some())
+        .map_or(0, |position| position + 1);
+    result.drain(..start);
+    Some(result)
+}
+
+/// Positions in `\levelnumbers` must point within `\leveltext`, whose first
+/// byte is its length.
+fn check_list_level(level: &[Node], threats: &mut Vec<Threat>) {
+    let (Some(text), Some(numbers)) = (subgroup(level, "leveltext"), subgroup(level, "levelnumbers"))
+    else {
+        return;
+    };
+    let text = group_bytes(text);
+    let length = text.first().copied().unwrap_or(0);
+    let numbers = group_bytes(numbers);
+    let positions: Vec<u8> = numbers.into_iter().take_while(|&byte| byte != b';').collect();
+    if positions.len() > 9 || positions.iter().any(|&position| position == 0 || position > length) {
+        threats.push(Threat {
+            kind: ThreatKind::MalformedLevelNumbers,
+            detail: format!("positions {positions:?} in a level text of length {length}"),
+        });
+    }
+}
+
+/// Reports exploit patterns found in `nodes`.
+fn inspect(nodes: &[Node], threats: &mut Vec<Threat>) {
+    for node in nodes {
+        let group = match node {
+            Node::Group(group) => group,
+            Node::Token(Token::ControlWord { name, arg }) if name == "listoverridecount" => {
+                if !matches!(arg.unwrap_or(0), 0 | 1 | 9) {
+                    threats.push(Threat {
+                        kind: ThreatKind::InvalidListOverrideCount,
+                        detail: format!("\\listoverridecount{}", arg.unwrap_or(0)),
+                    });
+                }
+                continue;
+            }
+            Node::Token(_) => continue,
+        };
+        match destination(group) {
+            Some("objdata") => {
+                let digits = group_bytes(group)
+                    .iter()
+                    .filter(|byte| byte.is_ascii_hexdigit())
+                    .count();
+                if digits / 2 > MAX_OBJECT_DATA {
+                    threats.push(Threat {
+                        kind: ThreatKind::OversizedObjectData,
+                        detail: format!("{} bytes of object data", digits / 2),
+                    });
+                }
+            }
+            Some("objclass") => {
+                let class = group_text(group);
+                if class.to_ascii_lowercase().starts_with("equation.3") {
+                    threats.push(Threat {
+                        kind: ThreatKind::EquationEditorObject,
+                        detail: truncated(class),
+                    });
+                }
+            }
+            Some("sn") if group_text(group).eq_ignore_ascii_case("pFragments") => {
+                threats.push(Threat {
+                    kind: ThreatKind::ShapeFragments,
+                    detail: "pFragments shape property".to_string(),
+                });
+            }
+            Some("listlevel") => check_list_level(group, threats),
+            _ => (),
+        }
+        inspect(group, threats);
+    }
+}
+
+/// Instruction of a field, e.g. `INCLUDEPICTURE "http://host/image.png"`.
+pub(crate) fn field_instruction(field: &[Node]) -> Option<String> {
+    subgroup(field, "fldinst").map(|instruction| group_text(instruction))
+}
+
+/// Removes objects, external fields, `\objupdate` and templates from `nodes`.
+fn clean(nodes: &mut Vec<Node>, removed: &mut Vec<Removed>) {
+    for node in std::mem::take(nodes) {
+        let mut group = match node {
+            Node::Group(group) => group,
+            node if node.word().map(|(name, _)| name) == Some("objupdate") => {
+                removed.push(Removed {
+                    kind: RemovedKind::ObjectUpdate,
+                    detail: None,
+                });
+                continue;
+            }
+            node => {
+                nodes.push(node);
+                continue;
+            }
+        };
+        match destination(&group) {
+            Some("object") => {
+                // Reported before its result replaces it.
+                let class = subgroup(&group, "objclass").map(|class| truncated(group_text(class)));
+                removed.push(Removed {
+                    kind: RemovedKind::Object,
+                    detail: class,
+                });
+                if group.iter().any(|node| node.word().map(|(name, _)| name) == Some("objupdate")) {
+                    removed.push(Removed {
+                        kind: RemovedKind::ObjectUpdate,
+                        detail: None,
+                    });
+                }
+                if let Some(mut result) = take_result(&mut group, "result") {
+                    clean(&mut result, removed);
+                    nodes.push(Node::Group(result));
+                }
+            }
+            Some("field") => {
+                let instruction = field_instruction(&group).unwrap_or_default();
+                let keyword = instruction
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .to_ascii_uppercase();
+                if EXTERNAL_FIELDS.contains(&keyword.as_str()) {
+                    removed.push(Removed {
+                        kind: RemovedKind::ExternalField,
+                        detail: Some(truncated(instruction)),
+                    });
+                    if let Some(mut result) = take_result(&mut group, "fldrslt") {
+                        clean(&mut result, removed);
+                        nodes.push(Node::Group(result));
+                    }
+                } else {
+                    clean(&mut group, removed);
+                    nodes.push(Node::Group(group));
+                }
+            }
+            Some("template") => removed.push(Removed {
+                kind: RemovedKind::Template,
+                detail: Some(truncated(group_text(&group[1..]))),
+            }),
+            _ => {
+                clean(&mut group, removed);
+                nodes.push(Node::Group(group));
+            }
+        }
+    }
+}
+
+/// Removes active and risky content from `token_stream`, returning the
+/// cleaned document and a report of what was removed.
+///
+/// Exploit patterns are reported from the original document, so that e.g.
+/// the data of a removed object is still inspected.
+pub fn sanitize(token_stream: &[Token], limits: &Limits) -> Result<(Vec<u8>, SanitizeReport), LimitExceeded> {
+    let mut report = SanitizeReport::default();
+    let mut document = parse(token_stream, limits.max_group_depth)?;
+    inspect(&document, &mut report.threats);
+    clean(&mut document, &mut report.removed);
+    Ok((write(&document), report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::error::Limit;
+    use crate::rtf::to_text::tokenize;
+    use crate::settings::Limits;
+
+    fn sanitized(rtf: &[u8]) -> (String, SanitizeReport) {
+        let tokens = tokenize(rtf, &Limits::default()).unwrap();
+        let (out, report) = sanitize(&tokens, &Limits::default()).unwrap();
+        (String::from_utf8(out).unwrap(), report)
+    }
+
+    #[test]
+    fn test_remove_active_content() {
+        let rtf = br#"{\rtf1\ansi{\*\template \\\\evil\\share\\t.dotm}
+{\object\objemb\objupdate{\*\objclass Equation.3}{\*\objdata 0102}{\result {\pict\pngblip 89}}}
+{\field{\*\fldinst INCLUDEPICTURE "http://evil/x.png" \\d}{\fldrslt Picture}}
+{\field{\*\fldinst HYPERLINK "https://example.com"}{\fldrslt Link}}\par}"#;
+        let (out, report) = sanitized(rtf);
+        assert_eq!(
+            out,
+            "{\\rtf1\\ansi{{\\pict\\pngblip 89}}{Picture}\
+             {\\field{\\*\\fldinst HYPERLINK \"https://example.com\"}{\\fldrslt Link}}\\par\n}"
+        );
+        let kinds: Vec<RemovedKind> = report.removed.iter().map(|removed| removed.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                RemovedKind::Template,
+                RemovedKind::Object,
+                RemovedKind::ObjectUpdate,
+                RemovedKind::ExternalField
+            ]
+        );
+        assert_eq!(report.removed[1].detail.as_deref(), Some("Equation.3"));
+        assert_eq!(report.threats.len(), 1);
+        assert_eq!(report.threats[0].kind, ThreatKind::EquationEditorObject);
+    }
+
+    #[test]
+    fn test_exploit_patterns() {
+        let rtf = br"{\rtf1{\*\listtable{\list{\listlevel{\leveltext\'02\'00.;}{\levelnumbers\'05;}}}}
+{\*\listoverridetable{\listoverride\listoverridecount25\ls1}}
+{\shp{\*\shpinst{\sp{\sn pFragments}{\sv 2;5;0}}}}}";
+        let (_, report) = sanitized(rtf);
+        let kinds: Vec<ThreatKind> = report.threats.iter().map(|threat| threat.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                ThreatKind::MalformedLevelNumbers,
+                ThreatKind::InvalidListOverrideCount,
+                ThreatKind::ShapeFragments
+            ]
+        );
+        assert!(report.removed.is_empty());
+    }
+
+    #[test]
+    fn test_group_depth_limit() {
+        let limits = Limits {
+            max_group_depth: 2,
+            ..Limits::default()
+        };
+        let tokens = tokenize(&br"{\rtf1{{\object}}}"[..], &limits).unwrap();
+        assert_eq!(sanitize(&tokens, &limits).err(), Some(LimitExceeded::new(Limit::GroupDepth, 2)));
+    }
+}
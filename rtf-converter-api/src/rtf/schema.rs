@@ -0,0 +1,281 @@
+//! Fields expected by a template, and the JSON Schema of its data.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+/// How a template uses a field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    /// Tested by a conditional, never written.
+    Conditional,
+    /// Written as text.
+    Scalar,
+    /// Iterated over; [TemplateField::fields] are those of its items.
+    List,
+}
+
+/// Where a field is used in a template.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct FieldLocation {
+    /// Index of the paragraph, counting table cells and sections as
+    /// paragraphs.
+    pub paragraph: usize,
+}
+
+/// Field of the data a template is rendered with.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TemplateField {
+    /// Dotted path of the field, relative to the items of the enclosing list.
+    pub name: String,
+    /// How the template uses the field.
+    #[serde(rename = "type")]
+    pub kind: FieldKind,
+    /// Whether rendering needs a value: fields only used within conditional
+    /// content are optional.
+    pub required: bool,
+    /// Where the field is used, in document order.
+    pub locations: Vec<FieldLocation>,
+    /// Fields of the items of a list.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<TemplateField>,
+}
+
+impl TemplateField {
+    /// New field, used at `paragraph`.
+    pub fn new(name: &str, kind: FieldKind, required: bool, paragraph: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            required,
+            locations: vec![FieldLocation { paragraph }],
+            fields: Vec::new(),
+        }
+    }
+}
+
+/// Adds `field` to `fields`, merging it with a field of the same name: the
+/// strongest use wins (a list over a scalar over a conditional), and the
+/// field is required when any use requires it.
+pub fn merge_field(fields: &mut Vec<TemplateField>, field: TemplateField) {
+    let Some(existing) = fields.iter_mut().find(|existing| existing.name == field.name) else {
+        fields.push(field);
+        return;
+    };
+    existing.kind = existing.kind.max(field.kind);
+    existing.required |= field.required;
+    for location in field.locations {
+        if !existing.locations.contains(&location) {
+            existing.locations.push(location);
+        }
+    }
+    for child in field.fields {
+        merge_field(&mut existing.fields, child);
+    }
+}
+
+/// Schema of the values written in place of a placeholder: text, or a typed
+/// picture or fragment object.
+fn scalar_schema() -> Value {
+    json!({ "type": ["string", "number", "boolean", "null", "object"] })
+}
+
+/// Fields sharing a path prefix, nested by path segment.
+#[derive(Default)]
+struct SchemaNode<'a> {
+    field: Option<&'a TemplateField>,
+    required: bool,
+    children: BTreeMap<&'a str, SchemaNode<'a>>,
+}
+
+impl<'a> SchemaNode<'a> {
+    fn new(fields: &'a [TemplateField]) -> Self {
+        let mut root = SchemaNode::default();
+        for field in fields {
+            let mut node = &mut root;
+            for segment in field.name.split('.') {
+                node.required |= field.required;
+                node = node.children.entry(segment).or_default();
+            }
+            node.required |= field.required;
+            node.field = Some(field);
+        }
+        root
+    }
+
+    fn schema(&self) -> Value {
+        if !self.children.is_empty() {
+            return self.object_schema();
+        }
+        match self.field {
+            Some(field) if field.kind == FieldKind::List => {
+                let items = match field.fields.as_slice() {
+                    [] => json!({}),
+                    [item] if item.name == "this" => SchemaNode::new(&item.fields).schema_or(scalar_schema()),
+                    fields => SchemaNode::new(fields).object_schema(),
+                };
+                json!({ "type": ["array", "object"], "items": items })
+            }
+            Some(field) if field.kind == FieldKind::Scalar => scalar_schema(),
+            _ => json!({}),
+        }
+    }
+
+    fn schema_or(&self, default: Value) -> Value {
+        if self.children.is_empty() {
+            default
+        } else {
+            self.object_schema()
+        }
+    }
+
+    /// Schema of an object holding the children, or of an array when they
+    /// are all item indices.
+    fn object_schema(&self) -> Value {
+        if self.children.keys().all(|key| key.parse::<usize>().is_ok()) {
+            let items: Vec<Value> = self.children.values().map(SchemaNode::schema).collect();
+            let items = if items.windows(2).all(|pair| pair[0] == pair[1]) {
+                items.into_iter().next().unwrap_or_default()
+            } else {
+                json!({})
+            };
+            return json!({ "type": "array", "items": items });
+        }
+        let properties: Map<String, Value> = self
+            .children
+            .iter()
+            .map(|(name, child)| (name.to_string(), child.schema()))
+            .collect();
+        let required: Vec<&str> = self
+            .children
+            .iter()
+            .filter(|(_, child)| child.required)
+            .map(|(name, _)| *name)
+            .collect();
+        json!({ "type": "object", "properties": properties, "required": required })
+    }
+}
+
+/// JSON Schema (draft 2020-12) of the data rendering `fields`.
+pub fn json_schema(fields: &[TemplateField]) -> Value {
+    let mut schema = SchemaNode::new(fields).object_schema();
+    schema["$schema"] = json!("https://json-schema.org/draft/2020-12/schema");
+    schema
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "integer" => value.as_i64().is_some() || value.as_u64().is_some(),
+        name => type_name(value) == name,
+    }
+}
+
+fn validate_at(schema: &Value, value: &Value, pointer: &str, errors: &mut Vec<String>) {
+    let types: Vec<&str> = match &schema["type"] {
+        Value::String(name) => vec![name.as_str()],
+        Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|name| has_type(value, name)) {
+        errors.push(format!("{pointer}: expected {}, got {}", types.join(" or "), type_name(value)));
+        return;
+    }
+    match value {
+        Value::Object(fields) => {
+            for name in schema["required"].as_array().into_iter().flatten().filter_map(Value::as_str) {
+                if !fields.contains_key(name) {
+                    errors.push(format!("{pointer}/{name}: missing"));
+                }
+            }
+            if let Some(properties) = schema["properties"].as_object() {
+                for (name, property) in properties {
+                    if let Some(field) = fields.get(name) {
+                        validate_at(property, field, &format!("{pointer}/{name}"), errors);
+                    }
+                }
+            }
+        }
+        Value::Array(items) if schema["items"].is_object() => {
+            for (index, item) in items.iter().enumerate() {
+                validate_at(&schema["items"], item, &format!("{pointer}/{index}"), errors);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Checks `data` against a schema from [json_schema], returning the errors
+/// found, each prefixed with the JSON pointer of the offending value.
+///
+/// Only the keywords [json_schema] generates are checked: `type`,
+/// `properties`, `required` and `items`.
+pub fn validate(schema: &Value, data: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(schema, data, "", &mut errors);
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_schema() {
+        let mut items = TemplateField::new("items", FieldKind::List, true, 2);
+        items.fields.push(TemplateField::new("name", FieldKind::Scalar, true, 2));
+        let fields = vec![
+            TemplateField::new("customer.name", FieldKind::Scalar, true, 0),
+            TemplateField::new("customer.vip", FieldKind::Conditional, false, 1),
+            items,
+        ];
+        let schema = json_schema(&fields);
+        assert_eq!(
+            schema,
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "customer": {
+                        "type": "object",
+                        "properties": {
+                            "name": {"type": ["string", "number", "boolean", "null", "object"]},
+                            "vip": {}
+                        },
+                        "required": ["name"]
+                    },
+                    "items": {
+                        "type": ["array", "object"],
+                        "items": {
+                            "type": "object",
+                            "properties": {"name": {"type": ["string", "number", "boolean", "null", "object"]}},
+                            "required": ["name"]
+                        }
+                    }
+                },
+                "required": ["customer", "items"]
+            })
+        );
+
+        assert!(validate(&schema, &json!({"customer": {"name": "Ada"}, "items": []})).is_empty());
+        assert_eq!(
+            validate(&schema, &json!({"customer": {"name": ["Ada"]}, "items": [{"name": 1}, {}]})),
+            [
+                "/customer/name: expected string or number or boolean or null or object, got array",
+                "/items/1/name: missing"
+            ]
+        );
+    }
+}
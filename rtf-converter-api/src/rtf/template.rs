@@ -0,0 +1,475 @@
+//! Merging of JSON data into RTF templates.
+//!
+//! Placeholders are written `{{name}}` in the text of a template, where
+//! `name` is a dotted path into the data (`{{customer.address.city}}`,
+//! `{{items.0.label}}`). Word often splits such text across runs and groups,
+//! e.g. when revision-save IDs (`\rsid`) or proofing marks change mid-word,
+//! so placeholders are matched on the text of the document rather than on
+//! single tokens: formatting words and group boundaries are ignored, and only
+//! words producing content (`\par`, `\tab`, ...) interrupt a placeholder.
+//! Values take the place, and the formatting, of the first character of
+//! their placeholder.
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+use rtf_grimoire::tokenizer::{parse_finished as parse_tokens, Token};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::error::{Limit, LimitExceeded, TemplateError};
+use super::fragment::value_nodes;
+use super::normalize::{destination, parse, write, Node};
+use super::writer::escape_text;
+use crate::settings::Limits;
+
+/// Destinations holding no document text.
+const NON_TEXT_DESTINATIONS: &[&str] = &[
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "info",
+    "pict",
+    "listtable",
+    "listoverridetable",
+];
+
+/// Control words producing content, which end the text a placeholder may
+/// span.
+const CONTENT_WORDS: &[&str] = &[
+    "par",
+    "line",
+    "tab",
+    "cell",
+    "row",
+    "nestcell",
+    "nestrow",
+    "sect",
+    "page",
+    "column",
+    "u",
+    "bullet",
+    "emdash",
+    "endash",
+    "emspace",
+    "enspace",
+    "qmspace",
+    "lquote",
+    "rquote",
+    "ldblquote",
+    "rdblquote",
+    "chpgn",
+    "chdate",
+    "chtime",
+    "chftn",
+];
+
+/// Control words ending a paragraph, for the locations of tags.
+pub(crate) const PARAGRAPH_BREAKS: &[&str] = &["par", "sect", "cell"];
+
+/// What [render] replaced.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct RenderReport {
+    /// Placeholders replaced.
+    pub replaced: usize,
+    /// Names of the placeholders without a value, which were left empty.
+    pub missing: Vec<String>,
+}
+
+/// Text token, or single-character token, of the template.
+#[derive(Debug)]
+struct Piece {
+    /// Indices of the token in the tree, group by group.
+    path: Vec<usize>,
+    /// Offset of the token in [TextIndex::text].
+    start: usize,
+    len: usize,
+}
+
+/// Text of a template, mapped back to the tokens holding it.
+#[derive(Debug, Default)]
+struct TextIndex {
+    /// Bytes of the text; content a placeholder cannot span reads as `0`.
+    text: Vec<u8>,
+    pieces: Vec<Piece>,
+    /// Offsets in [TextIndex::text] of the paragraph breaks.
+    breaks: Vec<usize>,
+}
+
+impl TextIndex {
+    fn new(document: &[Node]) -> Self {
+        let mut index = Self::default();
+        index.add_nodes(document, &mut Vec::new());
+        index
+    }
+
+    fn push(&mut self, path: &[usize], bytes: &[u8]) {
+        self.pieces.push(Piece {
+            path: path.to_vec(),
+            start: self.text.len(),
+            len: bytes.len(),
+        });
+        self.text.extend_from_slice(bytes);
+    }
+
+    fn add_nodes(&mut self, nodes: &[Node], path: &mut Vec<usize>) {
+        for (i, node) in nodes.iter().enumerate() {
+            path.push(i);
+            match node {
+                Node::Group(group) if is_text_group(group) => self.add_nodes(group, path),
+                Node::Group(_) => self.text.push(0),
+                Node::Token(Token::Text(bytes)) => self.push(path, bytes),
+                Node::Token(Token::ControlSymbol(symbol @ ('{' | '}' | '\\'))) => {
+                    self.push(path, &[*symbol as u8])
+                }
+                Node::Token(Token::ControlWord { name, arg: Some(byte) }) if name == "'" => {
+                    self.push(path, &[*byte as u8])
+                }
+                Node::Token(Token::ControlSymbol(_)) => self.text.push(0),
+                node => {
+                    if node.word().is_some_and(|(name, _)| PARAGRAPH_BREAKS.contains(&name)) {
+                        self.breaks.push(self.text.len());
+                    }
+                    if node.word().is_some_and(|(name, _)| CONTENT_WORDS.contains(&name)) {
+                        self.text.push(0);
+                    }
+                }
+            }
+            path.pop();
+        }
+    }
+
+    /// Index of the paragraph holding the text at `offset`.
+    fn paragraph(&self, offset: usize) -> usize {
+        self.breaks.partition_point(|&end| end <= offset)
+    }
+
+    /// Pieces overlapping `range`, in document order.
+    fn pieces_in(&self, range: &Range<usize>) -> impl Iterator<Item = &Piece> + '_ {
+        let first = self
+            .pieces
+            .partition_point(|piece| piece.start + piece.len <= range.start);
+        let end = range.end;
+        self.pieces[first..]
+            .iter()
+            .take_while(move |piece| piece.start < end)
+    }
+}
+
+/// Whether a group holds document text: field instructions do, so that
+/// e.g. hyperlink targets can be filled in, other ignorable destinations do
+/// not.
+pub(crate) fn is_text_group(group: &[Node]) -> bool {
+    let ignorable = matches!(group.first(), Some(Node::Token(Token::ControlSymbol('*'))));
+    match destination(group) {
+        Some("fldinst") => true,
+        Some(name) if NON_TEXT_DESTINATIONS.contains(&name) => false,
+        _ => !ignorable,
+    }
+}
+
+fn is_name_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'.' | b'-')
+}
+
+/// Whether `name` is a valid placeholder name.
+pub(crate) fn is_name(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(is_name_byte)
+}
+
+fn find(text: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    text.get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| from + position)
+}
+
+/// Tags of `text` whose trimmed content `is_tag` accepts, as byte ranges and
+/// contents.
+fn tags(text: &[u8], is_tag: fn(&str) -> bool) -> Vec<(Range<usize>, String)> {
+    let mut found = Vec::new();
+    let mut from = 0;
+    while let Some(open) = find(text, from, b"{{") {
+        let Some(close) = find(text, open + 2, b"}}") else {
+            break;
+        };
+        let name = String::from_utf8_lossy(&text[open + 2..close]);
+        let name = name.trim_matches(' ');
+        if is_tag(name) {
+            found.push((open..close + 2, name.to_string()));
+            from = close + 2;
+        } else {
+            from = open + 1;
+        }
+    }
+    found
+}
+
+/// Value at the dotted `path` of `data`; array items are selected by index.
+pub(crate) fn lookup<'a>(data: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(data, |value, key| match value {
+        Value::Object(fields) => fields.get(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|index| items.get(index)),
+        _ => None,
+    })
+}
+
+/// Text a value is rendered as; `null` renders empty and arrays and objects
+/// as JSON.
+pub(crate) fn value_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Nodes writing `text`, escaped as RTF.
+///
+/// Text with Unicode characters is wrapped in a group setting `\uc1`, as the
+/// template may skip any number of fallback characters after `\u`.
+pub(crate) fn text_nodes(text: &str) -> Vec<Node> {
+    let escaped = escape_text(text);
+    // Escaped text opens no group.
+    let nodes = parse(&parse_tokens(escaped.as_bytes()).unwrap_or_default(), 0).unwrap_or_default();
+    if text.is_ascii() {
+        return nodes;
+    }
+    let mut group = vec![Node::Token(Token::ControlWord {
+        name: "uc".to_string(),
+        arg: Some(1),
+    })];
+    group.extend(nodes);
+    vec![Node::Group(group)]
+}
+
+/// Group holding the token at `path`, and the index of the token in it.
+fn parent_mut<'a>(document: &'a mut Vec<Node>, path: &[usize]) -> Option<(&'a mut Vec<Node>, usize)> {
+    let (&index, groups) = path.split_last()?;
+    let mut nodes = document;
+    for &i in groups {
+        nodes = match nodes.get_mut(i)? {
+            Node::Group(group) => group,
+            Node::Token(_) => return None,
+        };
+    }
+    Some((nodes, index))
+}
+
+/// Removes the bytes of `range` from the text, inserting `replacement` where
+/// the range starts.
+fn replace_range(document: &mut Vec<Node>, index: &TextIndex, range: &Range<usize>, replacement: Vec<Node>) {
+    let pieces: Vec<&Piece> = index.pieces_in(range).collect();
+    let mut replacement = Some(replacement);
+    // Last piece first, so that the paths of earlier ones stay valid.
+    for (position, piece) in pieces.iter().enumerate().rev() {
+        let Some((nodes, i)) = parent_mut(document, &piece.path) else {
+            continue;
+        };
+        let keep_before = range.start.saturating_sub(piece.start).min(piece.len);
+        let keep_from = (range.end - piece.start).min(piece.len);
+        let mut spliced = Vec::new();
+        if let Node::Token(Token::Text(bytes)) = &nodes[i] {
+            if keep_before > 0 {
+                spliced.push(Node::Token(Token::Text(bytes[..keep_before].to_vec())));
+            }
+            if position == 0 {
+                spliced.extend(replacement.take().unwrap_or_default());
+            }
+            if keep_from < bytes.len() {
+                spliced.push(Node::Token(Token::Text(bytes[keep_from..].to_vec())));
+            }
+        } else if position == 0 {
+            spliced.extend(replacement.take().unwrap_or_default());
+        }
+        nodes.splice(i..i + 1, spliced);
+    }
+}
+
+/// Tags of `document` accepted by `is_tag`, in document order, with the index
+/// of the paragraph holding them.
+pub(crate) fn find_tags(document: &[Node], is_tag: fn(&str) -> bool) -> Vec<(String, usize)> {
+    let index = TextIndex::new(document);
+    tags(&index.text, is_tag)
+        .into_iter()
+        .map(|(range, name)| (name, index.paragraph(range.start)))
+        .collect()
+}
+
+/// Calls `replace` on each tag of `document` accepted by `is_tag`, from the
+/// last to the first, replacing it with the nodes returned.
+pub(crate) fn replace_tags(
+    document: &mut Vec<Node>,
+    is_tag: fn(&str) -> bool,
+    mut replace: impl FnMut(&str) -> Vec<Node>,
+) {
+    let index = TextIndex::new(document);
+    for (range, name) in tags(&index.text, is_tag).into_iter().rev() {
+        let replacement = replace(&name);
+        replace_range(document, &index, &range, replacement);
+    }
+}
+
+/// Bytes `token` takes at least once written.
+fn written_len(token: &Token) -> usize {
+    match token {
+        Token::ControlWord { name, .. } => name.len() + 1,
+        Token::ControlSymbol(_) => 2,
+        Token::ControlBin(data) | Token::Text(data) | Token::Newline(data) => data.len(),
+        Token::StartGroup | Token::EndGroup => 1,
+    }
+}
+
+/// Size of rendered content, within the output size limit.
+///
+/// Loops and values multiply the content of a template, so engines check the
+/// size as content is added rather than once the document is written.
+pub(crate) struct OutputSize {
+    /// Bytes the content takes at least once written.
+    len: usize,
+    max_len: usize,
+}
+
+impl OutputSize {
+    pub(crate) fn new(limits: &Limits) -> Self {
+        OutputSize {
+            len: 0,
+            max_len: limits.max_output_bytes,
+        }
+    }
+
+    pub(crate) fn add(&mut self, token: &Token) -> Result<(), LimitExceeded> {
+        self.len += written_len(token);
+        if self.len > self.max_len {
+            return Err(LimitExceeded::new(Limit::OutputBytes, self.max_len));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn add_nodes(&mut self, nodes: &[Node]) -> Result<(), LimitExceeded> {
+        for node in nodes {
+            match node {
+                Node::Token(token) => self.add(token)?,
+                Node::Group(group) => {
+                    self.add(&Token::StartGroup)?;
+                    self.add_nodes(group)?;
+                    self.add(&Token::EndGroup)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks the size of the written document `rendered`.
+pub(crate) fn check_output(rendered: Vec<u8>, limits: &Limits) -> Result<Vec<u8>, LimitExceeded> {
+    if rendered.len() > limits.max_output_bytes {
+        return Err(LimitExceeded::new(Limit::OutputBytes, limits.max_output_bytes));
+    }
+    Ok(rendered)
+}
+
+/// Replaces the `{{name}}` placeholders of `token_stream` with the values of
+/// `data`, returning the rendered RTF and a report of what was replaced.
+///
+/// Values may be pictures or rich-text fragments, see
+/// [fragment](super::fragment).
+pub fn render(
+    token_stream: &[Token],
+    data: &Value,
+    limits: &Limits,
+) -> Result<(Vec<u8>, RenderReport), TemplateError> {
+    let mut document = parse(token_stream, limits.max_group_depth)?;
+    let mut replaced = 0;
+    let mut missing = BTreeSet::new();
+    let mut error = None;
+    // Values add to the template, which is in the input limit: only they are
+    // counted as they are substituted.
+    let mut size = OutputSize::new(limits);
+    replace_tags(&mut document, is_name, |name| {
+        if error.is_some() {
+            return Vec::new();
+        }
+        replaced += 1;
+        match lookup(data, name).map(|value| value_nodes(value, limits)) {
+            Some(Ok(nodes)) => {
+                if let Err(e) = size.add_nodes(&nodes) {
+                    error = Some(e.into());
+                    return Vec::new();
+                }
+                nodes
+            }
+            Some(Err(e)) => {
+                error.get_or_insert(e);
+                Vec::new()
+            }
+            None => {
+                missing.insert(name.to_string());
+                Vec::new()
+            }
+        }
+    });
+    if let Some(error) = error {
+        return Err(error);
+    }
+    let report = RenderReport {
+        replaced,
+        missing: missing.into_iter().collect(),
+    };
+    Ok((check_output(write(&document), limits)?, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::to_text::tokenize;
+    use crate::settings::Limits;
+    use serde_json::json;
+
+    fn rendered(rtf: &[u8], data: Value) -> (String, RenderReport) {
+        let tokens = tokenize(rtf, &Limits::default()).unwrap();
+        let (out, report) = render(&tokens, &data, &Limits::default()).unwrap();
+        (String::from_utf8(out).unwrap(), report)
+    }
+
+    #[test]
+    fn test_render_split_placeholders() {
+        let rtf = br"{\rtf1\ansi Dear {\rsid1 \{\{cus}{\rsid2\b tomer.name\}}\}, \{\{ total \}\}\par}";
+        let (out, report) = rendered(rtf, json!({"customer": {"name": "Zo\u{eb} {Ltd}"}, "total": 12.5}));
+        assert_eq!(
+            out,
+            "{\\rtf1\\ansi Dear {\\rsid1{\\uc1 Zo\\u235? \\{Ltd\\}}}{\\rsid2\\b}, 12.5\\par\n}"
+        );
+        assert_eq!(report.replaced, 2);
+        assert!(report.missing.is_empty());
+    }
+
+    #[test]
+    fn test_render_missing_and_interrupted() {
+        let rtf = br"{\rtf1\ansi \{\{a\}\}\{\{b\par c\}\}{\*\fldinst HYPERLINK \{\{url\}\}}\par}";
+        let (out, report) = rendered(rtf, json!({"a": null}));
+        assert_eq!(
+            out,
+            "{\\rtf1\\ansi\\{\\{b\\par\nc\\}\\}{\\*\\fldinst HYPERLINK }\\par\n}"
+        );
+        assert_eq!(report.replaced, 2);
+        assert_eq!(report.missing, ["url"]);
+    }
+
+    #[test]
+    fn test_output_limit() {
+        let rtf = br"{\rtf1 \{\{a\}\}\{\{a\}\}\{\{a\}\}}";
+        let limits = Limits {
+            max_output_bytes: 1_000,
+            ..Limits::default()
+        };
+        let tokens = tokenize(&rtf[..], &limits).unwrap();
+        assert_eq!(
+            render(&tokens, &json!({"a": "x".repeat(400)}), &limits).err(),
+            Some(TemplateError::Limit(LimitExceeded::new(Limit::OutputBytes, 1_000)))
+        );
+        let (out, report) = render(&tokens, &json!({"a": "x".repeat(300)}), &limits).unwrap();
+        assert_eq!(out.len(), 908);
+        assert_eq!(report.replaced, 3);
+    }
+}
@@ -0,0 +1,142 @@
+//! HTML rendering of the document body.
+
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use rtf_grimoire::tokenizer::Token;
+
+use super::document::{Body, Direction, Paragraph, Run};
+use super::error::RtfError;
+use super::options::ConvertOptions;
+use super::to_text::read_document;
+
+/// Escapes `text` for use in HTML content and attribute values.
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn push_run(html: &mut String, run: &Run, paragraph: &Paragraph, body: &Body) {
+    let text = escape_html(&run.text);
+    let text = if run.props.bold {
+        format!("<b>{text}</b>")
+    } else {
+        text
+    };
+    let mut attributes = String::new();
+    if run.props.direction != paragraph.direction {
+        attributes.push_str(&format!(" dir=\"{}\"", run.props.direction.as_str()));
+    }
+    if let Some(language) = run.props.language.filter(|&language| Some(language) != body.language) {
+        attributes.push_str(&format!(" lang=\"{language}\""));
+    }
+    if attributes.is_empty() {
+        html.push_str(&text);
+    } else {
+        html.push_str(&format!("<span{attributes}>{text}</span>"));
+    }
+}
+
+/// HTML fragment of `body`, one `<p>` per paragraph.
+///
+/// Right-to-left documents, and documents declaring a default language, are
+/// wrapped in a `<div>` carrying their `dir` and `lang`; paragraphs and runs
+/// whose direction differs from their container carry their own `dir`, and
+/// runs in another language their own `lang`.
+pub fn body_html(body: &Body) -> String {
+    let mut html = String::new();
+    let wrapped = body.direction == Direction::Rtl || body.language.is_some();
+    if wrapped {
+        html.push_str("<div");
+        if let Some(language) = body.language {
+            html.push_str(&format!(" lang=\"{language}\""));
+        }
+        if body.direction == Direction::Rtl {
+            html.push_str(" dir=\"rtl\"");
+        }
+        html.push('>');
+    }
+    for paragraph in &body.paragraphs {
+        if paragraph.direction == body.direction {
+            html.push_str("<p>");
+        } else {
+            html.push_str(&format!("<p dir=\"{}\">", paragraph.direction.as_str()));
+        }
+        for run in &paragraph.runs {
+            push_run(&mut html, run, paragraph, body);
+        }
+        html.push_str("</p>\n");
+    }
+    if wrapped {
+        html.push_str("</div>\n");
+    }
+    html
+}
+
+/// Writes the document body as an HTML fragment to `writer`.
+///
+/// See [read_document] for how the token stream is interpreted.
+pub fn write_html<W: Write>(
+    token_stream: &[Token],
+    mut writer: W,
+    options: &ConvertOptions,
+) -> Result<Vec<RtfError>> {
+    let document = read_document(token_stream, options)?;
+    writer
+        .write_all(body_html(&document.body).as_bytes())
+        .context("Error writing to output file")?;
+    Ok(document.diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::to_text::tokenize;
+
+    fn html(rtf: &[u8]) -> String {
+        let options = ConvertOptions::default();
+        let tokens = tokenize(rtf, &options.limits).unwrap();
+        let mut out = Vec::new();
+        write_html(&tokens, &mut out, &options).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_write_html() {
+        assert_eq!(
+            html(br"{\rtf1\ansi a <b> & {\b c}\par}"),
+            "<p>a &lt;b&gt; &amp; <b>c</b></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_rtl_html() {
+        let rtf = br"{\rtf1\ansi\ansicpg1255\rtldoc\pard\rtlpar{\rtlch\ab \'f9\'ec\'e5\'ed}{\ltrch  Word}\par
+\pard\ltrpar English\par}";
+        assert_eq!(
+            html(rtf),
+            "<div dir=\"rtl\"><p><b>\u{5e9}\u{5dc}\u{5d5}\u{5dd}</b><span dir=\"ltr\"> Word</span></p>\n\
+             <p dir=\"ltr\">English</p>\n</div>\n"
+        );
+    }
+
+    #[test]
+    fn test_language_html() {
+        let rtf = br"{\rtf1\ansi\deflang1033 Hello {\lang1036 monde}, {\rtlch\alang1037 x}\par}";
+        assert_eq!(
+            html(rtf),
+            "<div lang=\"en-US\"><p>Hello <span lang=\"fr-FR\">monde</span>, \
+             <span dir=\"rtl\" lang=\"he-IL\">x</span></p>\n</div>\n"
+        );
+    }
+}
@@ -0,0 +1,551 @@
+//! PDF rendering of the document body.
+//!
+//! Text is set in the standard Helvetica fonts, which every PDF reader
+//! provides, so no font is embedded and characters outside of Windows-1252
+//! are replaced by `?`. Pages follow the page size, margins, columns, breaks,
+//! headers and footers of each section.
+
+use std::io::Write;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use rtf_grimoire::tokenizer::Token;
+
+use super::document::{Direction, Document, HeaderFooter, Layout, Paragraph, Section, SectionBreak};
+use super::error::{Limit, LimitExceeded, RtfError};
+use super::options::ConvertOptions;
+use super::to_text::read_document;
+use crate::settings::Limits;
+
+/// Font size of runs without one, in half-points.
+const DEFAULT_FONT_SIZE: i32 = 24;
+
+/// Font size of headers and footers, in points.
+const HEADER_FONT_SIZE: f32 = 10.0;
+
+/// Line height, relative to the largest font size of the line.
+const LINE_SPACING: f32 = 1.2;
+
+/// Tabs are set as this many spaces.
+const TAB: &str = "    ";
+
+/// Advance widths of Helvetica for ASCII 32 to 126, in 1/1000 em.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Advance widths of Helvetica-Bold for ASCII 32 to 126, in 1/1000 em.
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+/// Advance width of characters outside of ASCII, in 1/1000 em.
+const DEFAULT_WIDTH: u16 = 556;
+
+/// Converts twips to points.
+fn points(twips: i32) -> f32 {
+    twips as f32 / 20.0
+}
+
+fn text_width(text: &str, bold: bool, size: f32) -> f32 {
+    let widths = if bold {
+        &HELVETICA_BOLD_WIDTHS
+    } else {
+        &HELVETICA_WIDTHS
+    };
+    let units: u32 = text
+        .chars()
+        .map(|c| match c {
+            ' '..='~' => widths[c as usize - 32],
+            _ => DEFAULT_WIDTH,
+        } as u32)
+        .sum();
+    units as f32 * size / 1000.0
+}
+
+/// Windows-1252 byte of `c`, `?` for characters it lacks.
+fn win_ansi(c: char) -> u8 {
+    if c.is_ascii() {
+        return if c.is_ascii_control() { b' ' } else { c as u8 };
+    }
+    let mut buffer = [0; 4];
+    let (bytes, _, unmappable) = encoding_rs::WINDOWS_1252.encode(c.encode_utf8(&mut buffer));
+    match *bytes {
+        [byte] if !unmappable => byte,
+        _ => b'?',
+    }
+}
+
+/// PDF literal string of `text`, in WinAnsiEncoding.
+fn pdf_string(text: &str) -> String {
+    let mut string = String::with_capacity(text.len() + 2);
+    string.push('(');
+    for byte in text.chars().map(win_ansi) {
+        match byte {
+            b'(' | b')' | b'\\' => {
+                string.push('\\');
+                string.push(byte as char);
+            }
+            0x20..=0x7E => string.push(byte as char),
+            _ => string.push_str(&format!("\\{byte:03o}")),
+        }
+    }
+    string.push(')');
+    string
+}
+
+/// Stretch of a line set in one font.
+#[derive(Debug)]
+struct Span {
+    text: String,
+    bold: bool,
+    size: f32,
+}
+
+/// Line of a paragraph, as wrapped to its column.
+#[derive(Debug, Default)]
+struct Line {
+    spans: Vec<Span>,
+    width: f32,
+    height: f32,
+}
+
+impl Line {
+    fn push(&mut self, text: &str, bold: bool, size: f32) {
+        self.width += text_width(text, bold, size);
+        self.height = self.height.max(size * LINE_SPACING);
+        match self.spans.last_mut() {
+            Some(span) if span.bold == bold && span.size == size => span.text.push_str(text),
+            _ => self.spans.push(Span {
+                text: text.to_string(),
+                bold,
+                size,
+            }),
+        }
+    }
+}
+
+/// Byte length of the longest prefix of `text` at most `width` points wide,
+/// keeping at least one character.
+fn fitting_prefix(text: &str, bold: bool, size: f32, width: f32) -> usize {
+    let mut used = 0.0;
+    for (index, c) in text.char_indices() {
+        used += text_width(&text[index..index + c.len_utf8()], bold, size);
+        if used > width {
+            return if index == 0 { c.len_utf8() } else { index };
+        }
+    }
+    text.len()
+}
+
+/// Breaks `paragraph` into lines at most `width` points wide.
+///
+/// Lines break after spaces; words wider than a line are broken anywhere.
+fn wrap(paragraph: &Paragraph, width: f32) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut line = Line::default();
+    for run in &paragraph.runs {
+        let bold = run.props.bold;
+        let size = run.props.font_size.unwrap_or(DEFAULT_FONT_SIZE) as f32 / 2.0;
+        let text = run.text.replace('\t', TAB);
+        for word in text.split_inclusive(' ') {
+            let visible = word.trim_end_matches(' ');
+            if !line.spans.is_empty() && line.width + text_width(visible, bold, size) > width {
+                lines.push(std::mem::take(&mut line));
+                if visible.is_empty() {
+                    continue;
+                }
+            }
+            let mut rest = word;
+            while line.spans.is_empty() && text_width(rest.trim_end_matches(' '), bold, size) > width {
+                let end = fitting_prefix(rest, bold, size, width);
+                line.push(&rest[..end], bold, size);
+                rest = &rest[end..];
+                if rest.is_empty() {
+                    break;
+                }
+                lines.push(std::mem::take(&mut line));
+            }
+            if !rest.is_empty() {
+                line.push(rest, bold, size);
+            }
+        }
+    }
+    if line.spans.is_empty() {
+        line.height = DEFAULT_FONT_SIZE as f32 / 2.0 * LINE_SPACING;
+    }
+    lines.push(line);
+    lines
+}
+
+/// Page being laid out, with its content stream.
+#[derive(Debug)]
+struct Page {
+    width: f32,
+    height: f32,
+    content: String,
+}
+
+impl Page {
+    fn draw(&mut self, text: &str, bold: bool, size: f32, x: f32, y: f32) {
+        let font = if bold { "F2" } else { "F1" };
+        self.content.push_str(&format!(
+            "BT /{font} {size} Tf {x:.2} {y:.2} Td {} Tj ET\n",
+            pdf_string(text)
+        ));
+    }
+}
+
+/// Flows paragraphs into the columns and pages of their sections.
+struct Paginator<'a> {
+    layout: &'a Layout,
+    limits: &'a Limits,
+    started: Instant,
+    pages: Vec<Page>,
+    /// Bytes of the content streams of `pages`.
+    written: usize,
+    section: Section,
+    /// Index of the current section in the layout.
+    section_index: usize,
+    /// Index of the first page of the current section.
+    section_start: usize,
+    /// Number of the current page.
+    page_number: i32,
+    /// Number of the next page.
+    next_number: i32,
+    column: i32,
+    /// Top of the next line, from the bottom of the page.
+    y: f32,
+}
+
+impl<'a> Paginator<'a> {
+    fn new(layout: &'a Layout, limits: &'a Limits, started: Instant) -> Result<Self, LimitExceeded> {
+        let mut paginator = Self {
+            layout,
+            limits,
+            started,
+            pages: Vec::new(),
+            written: 0,
+            section: layout.section(0),
+            section_index: 0,
+            section_start: 0,
+            page_number: layout.page_number_start,
+            next_number: layout.page_number_start,
+            column: 0,
+            y: 0.0,
+        };
+        if let Some(start) = paginator.section.page_number_start {
+            paginator.next_number = start;
+        }
+        paginator.new_page()?;
+        Ok(paginator)
+    }
+
+    /// Checks the time spent and the size of the content laid out so far.
+    fn check_limits(&self) -> Result<(), LimitExceeded> {
+        if self.started.elapsed() > self.limits.max_duration() {
+            return Err(LimitExceeded::new(Limit::Duration, self.limits.max_duration_ms));
+        }
+        if self.written > self.limits.max_output_bytes {
+            return Err(LimitExceeded::new(Limit::OutputBytes, self.limits.max_output_bytes));
+        }
+        Ok(())
+    }
+
+    /// Top of the text area, from the bottom of the page.
+    fn top(&self) -> f32 {
+        let (_, height) = self.section.page.oriented_size();
+        points(height - self.section.page.margin_top)
+    }
+
+    fn bottom(&self) -> f32 {
+        points(self.section.page.margin_bottom)
+    }
+
+    /// Left edge and width of the current column.
+    fn column_frame(&self) -> (f32, f32) {
+        let page = &self.section.page;
+        let (width, _) = page.oriented_size();
+        let text_width = points(width - page.margin_left - page.margin_right);
+        let spacing = points(self.section.column_spacing);
+        let columns = self.section.columns as f32;
+        let column_width = ((text_width - spacing * (columns - 1.0)) / columns).max(1.0);
+        let left = points(page.margin_left) + self.column as f32 * (column_width + spacing);
+        (left, column_width)
+    }
+
+    fn new_page(&mut self) -> Result<(), LimitExceeded> {
+        if self.pages.len() >= self.limits.max_pages {
+            return Err(LimitExceeded::new(Limit::Pages, self.limits.max_pages));
+        }
+        let (width, height) = self.section.page.oriented_size();
+        self.page_number = self.next_number;
+        self.next_number += 1;
+        let mut page = Page {
+            width: points(width),
+            height: points(height),
+            content: String::new(),
+        };
+        let first_page = self.pages.len() == self.section_start;
+        self.draw_header_footer(&mut page, first_page);
+        self.written += page.content.len();
+        self.pages.push(page);
+        self.column = 0;
+        self.y = self.top();
+        self.check_limits()
+    }
+
+    fn next_column(&mut self) -> Result<(), LimitExceeded> {
+        if self.column + 1 < self.section.columns {
+            self.column += 1;
+            self.y = self.top();
+            Ok(())
+        } else {
+            self.new_page()
+        }
+    }
+
+    /// Starts section `index`, breaking as the section asks.
+    fn start_section(&mut self, index: usize) -> Result<(), LimitExceeded> {
+        self.section_index = index;
+        self.section = self.layout.section(index);
+        if let Some(start) = self.section.page_number_start {
+            self.next_number = start;
+        }
+        match self.section.break_kind {
+            SectionBreak::None => return Ok(()),
+            SectionBreak::Column => return self.next_column(),
+            SectionBreak::Page => (),
+            SectionBreak::Even | SectionBreak::Odd => {
+                let even = self.section.break_kind == SectionBreak::Even;
+                if (self.next_number % 2 == 0) != even {
+                    self.section_start = self.pages.len();
+                    self.new_page()?;
+                }
+            }
+        }
+        self.section_start = self.pages.len();
+        self.new_page()
+    }
+
+    fn draw_header_footer(&self, page: &mut Page, first_page: bool) {
+        let select = |texts: &HeaderFooter| -> Vec<String> {
+            texts
+                .for_page(&self.section, self.layout, self.page_number, first_page)
+                .map(|text| text.lines().map(str::to_string).collect())
+                .unwrap_or_default()
+        };
+        let left = points(self.section.page.margin_left);
+        let line_height = HEADER_FONT_SIZE * LINE_SPACING;
+
+        let mut y = page.height - points(self.section.header_distance) - HEADER_FONT_SIZE;
+        for line in select(&self.section.header) {
+            page.draw(&line, false, HEADER_FONT_SIZE, left, y);
+            y -= line_height;
+        }
+        let footer = select(&self.section.footer);
+        let mut y = points(self.section.footer_distance) + line_height * (footer.len() as f32 - 1.0);
+        for line in footer {
+            page.draw(&line, false, HEADER_FONT_SIZE, left, y);
+            y -= line_height;
+        }
+    }
+
+    fn add_paragraph(&mut self, paragraph: &Paragraph) -> Result<(), LimitExceeded> {
+        if paragraph.section != self.section_index {
+            self.start_section(paragraph.section)?;
+        }
+        let (_, column_width) = self.column_frame();
+        for line in wrap(paragraph, column_width) {
+            if self.y - line.height < self.bottom() && self.y < self.top() {
+                self.next_column()?;
+            }
+            let (left, width) = self.column_frame();
+            let mut x = match paragraph.direction {
+                Direction::Ltr => left,
+                Direction::Rtl => left + width - line.width,
+            };
+            let baseline = self.y - line.height / LINE_SPACING;
+            let page = self.pages.last_mut().expect("a page is always open");
+            let len = page.content.len();
+            for span in &line.spans {
+                page.draw(&span.text, span.bold, span.size, x, baseline);
+                x += text_width(&span.text, span.bold, span.size);
+            }
+            self.written += page.content.len() - len;
+            self.y -= line.height;
+            self.check_limits()?;
+        }
+        Ok(())
+    }
+}
+
+/// Assembles `pages` into a PDF file.
+fn write_objects(pages: &[Page]) -> Vec<u8> {
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            (0..pages.len())
+                .map(|index| format!("{} 0 R", 5 + 2 * index))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+    for (index, page) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] \
+             /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            page.width,
+            page.height,
+            6 + 2 * index
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            page.content.len(),
+            page.content
+        ));
+    }
+
+    let mut pdf = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{object}\nendobj\n", index + 1).as_bytes());
+    }
+    let xref = pdf.len();
+    pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{offset:010} 00000 n \n").as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .as_bytes(),
+    );
+    pdf
+}
+
+/// Lays out the body of `document`, as [document_pdf] does, within `limits`
+/// counted from `started`.
+fn layout_pdf(document: &Document, limits: &Limits, started: Instant) -> Result<Vec<u8>, LimitExceeded> {
+    let mut paginator = Paginator::new(&document.layout, limits, started)?;
+    for paragraph in &document.body.paragraphs {
+        paginator.add_paragraph(paragraph)?;
+    }
+    let pdf = write_objects(&paginator.pages);
+    if pdf.len() > limits.max_output_bytes {
+        return Err(LimitExceeded::new(Limit::OutputBytes, limits.max_output_bytes));
+    }
+    Ok(pdf)
+}
+
+/// PDF of the body of `document`, laid out by its sections.
+///
+/// Layout stops with a [LimitExceeded] error past `limits.max_pages` pages,
+/// `limits.max_output_bytes` bytes or `limits.max_duration_ms`.
+pub fn document_pdf(document: &Document, limits: &Limits) -> Result<Vec<u8>, LimitExceeded> {
+    layout_pdf(document, limits, Instant::now())
+}
+
+/// Writes the document body as PDF to `writer`.
+///
+/// See [read_document] for how the token stream is interpreted.
+pub fn write_pdf<W: Write>(
+    token_stream: &[Token],
+    mut writer: W,
+    options: &ConvertOptions,
+) -> Result<Vec<RtfError>> {
+    // The time spent reading the document counts towards the duration limit.
+    let started = Instant::now();
+    let document = read_document(token_stream, options)?;
+    writer
+        .write_all(&layout_pdf(&document, &options.limits, started)?)
+        .context("Error writing to output file")?;
+    Ok(document.diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::to_text::tokenize;
+
+    fn pdf(rtf: &[u8]) -> String {
+        let options = ConvertOptions::default();
+        let tokens = tokenize(rtf, &options.limits).unwrap();
+        let mut out = Vec::new();
+        write_pdf(&tokens, &mut out, &options).unwrap();
+        String::from_utf8_lossy(&out).into_owned()
+    }
+
+    #[test]
+    fn test_write_pdf() {
+        let rtf = br"{\rtf1\ansi\paperw11906\paperh16838\margl1134\margr1134
+\sectd\titlepg{\headerf First header\par}{\header Other header\par}
+Caf\'e9 (one)\par
+\sect\sectd\lndscpsxn\sbkodd{\footer Footer\par}
+Two\par}";
+        let pdf = pdf(rtf);
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        // The odd section break adds a blank page 2.
+        assert_eq!(pdf.matches("/Type /Page ").count(), 3);
+        assert!(pdf.contains("/MediaBox [0 0 595.30 841.90]"));
+        assert!(pdf.contains("/MediaBox [0 0 841.90 595.30]"));
+        assert!(pdf.contains("(First header)"));
+        assert!(pdf.contains("(Caf\\351 \\(one\\))"));
+        assert!(pdf.contains("(Footer)"));
+        // Page 2 is not the first page of the first section.
+        assert_eq!(pdf.matches("(Other header)").count(), 2);
+    }
+
+    #[test]
+    fn test_limits() {
+        let rtf = br"{\rtf1\ansi One\par\sect Two\par\sect Three\par}";
+        let tokens = tokenize(&rtf[..], &Limits::default()).unwrap();
+        let write = |limits: Limits| {
+            let options = ConvertOptions::new(limits);
+            write_pdf(&tokens, Vec::new(), &options).map_err(|err| err.downcast::<LimitExceeded>().unwrap())
+        };
+        assert!(write(Limits { max_pages: 3, ..Limits::default() }).is_ok());
+        assert_eq!(
+            write(Limits { max_pages: 2, ..Limits::default() }).err(),
+            Some(LimitExceeded::new(Limit::Pages, 2))
+        );
+        assert_eq!(
+            write(Limits { max_output_bytes: 100, ..Limits::default() }).err(),
+            Some(LimitExceeded::new(Limit::OutputBytes, 100))
+        );
+    }
+
+    #[test]
+    fn test_wrap() {
+        let rtf = br"{\rtf1\ansi word word word word word word word word word word\par}";
+        let options = ConvertOptions::default();
+        let tokens = tokenize(&rtf[..], &options.limits).unwrap();
+        let document = read_document(&tokens, &options).unwrap();
+        let lines = wrap(&document.body.paragraphs[0], 100.0);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|line| line.width <= 100.0 + text_width(" ", false, 12.0)));
+        let text: String = lines
+            .iter()
+            .flat_map(|line| &line.spans)
+            .map(|span| span.text.as_str())
+            .collect();
+        assert_eq!(text, "word ".repeat(9) + "word");
+    }
+}
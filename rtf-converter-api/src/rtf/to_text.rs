@@ -1,14 +1,26 @@
+use std::borrow::Cow;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::io::{Read, Write};
 use std::rc::Rc;
+use std::time::Instant;
 
 use anyhow::{Context, Result};
-use tracing::{debug, info, warn};
+use encoding_rs::Encoding;
+use tracing::{debug, info, trace, warn};
 
 use rtf_grimoire::tokenizer::parse_finished as parse_tokens;
 use rtf_grimoire::tokenizer::Token;
 use super::control;
+use super::document::{
+    Body, Direction, Document, HeaderFooter, Layout, PageSetup, RunProps, Section, SectionBreak,
+};
+use super::encoding::fallback_encoding;
+use super::error::{DestinationKind, Limit, LimitExceeded, RtfError};
+use super::font::FontTable;
+use super::lcid;
+use super::options::ConvertOptions;
+use crate::settings::Limits;
 
 #[derive(Clone, Debug)]
 pub enum Destination {
@@ -24,96 +36,548 @@ impl Destination {
         }
     }
 
-    fn append_text(&mut self, new_text: &str) {
+    fn kind(&self) -> DestinationKind {
+        match self {
+            Destination::Text(_) => DestinationKind::Text,
+            Destination::Bytes(_) => DestinationKind::Bytes,
+        }
+    }
+
+    fn append_text(&mut self, name: &str, new_text: &str) -> Result<(), RtfError> {
         if let Destination::Text(string) = self {
             string.push_str(new_text);
+            Ok(())
         } else {
-            panic!("Programmer error: attempting to add text to a byte destination");
+            Err(RtfError::DestinationKindMismatch {
+                name: name.to_owned(),
+                existing: self.kind(),
+                requested: DestinationKind::Text,
+            })
         }
     }
 
-    fn append_bytes(&mut self, new_bytes: &[u8]) {
+    fn append_bytes(&mut self, name: &str, new_bytes: &[u8]) -> Result<(), RtfError> {
         if let Destination::Bytes(bytes) = self {
             bytes.extend(new_bytes);
+            Ok(())
         } else {
-            panic!("Programmer error: attempting to add bytes to a text destination");
+            Err(RtfError::DestinationKindMismatch {
+                name: name.to_owned(),
+                existing: self.kind(),
+                requested: DestinationKind::Bytes,
+            })
         }
     }
 }
 
-/* TODO: It would be better to make 'values' CoW objects to reduce the number of copies we
- * make/keep
- */
+/// All destinations of a document, keyed by their control word, along with
+/// the font table and the body layout built while writing to them.
+#[derive(Debug, Default)]
+pub struct Destinations {
+    entries: HashMap<String, Destination>,
+    total_len: usize,
+    fonts: FontTable,
+    body: Body,
+    layout: Layout,
+}
+
+impl Destinations {
+    /// Destination of the control word `name`, when the document has one.
+    pub fn get(&self, name: &str) -> Option<&Destination> {
+        self.entries.get(name)
+    }
+
+    /// Font table of the document.
+    pub fn fonts(&self) -> &FontTable {
+        &self.fonts
+    }
+
+    /// Body text of the document, split into paragraphs and runs.
+    pub fn body(&self) -> &Body {
+        &self.body
+    }
+
+    /// Number of bytes held across all destinations.
+    pub fn total_len(&self) -> usize {
+        self.total_len
+    }
+}
+
+/// Destination holding the document body.
+const BODY_DESTINATION: &str = "rtf";
+
+/// Destination holding the font table.
+const FONT_TABLE_DESTINATION: &str = "fonttbl";
+
+/// Character formatting words reset by `\plain`.
+const CHARACTER_FORMATTING: &[&str] = &[
+    "f", "fs", "b", "i", "ul", "cf", "af", "afs", "ab", "ai", "lang", "langfe", "alang",
+];
+
+/// Section formatting words reset by `\sectd`.
+const SECTION_FORMATTING: &[&str] = &[
+    "pgwsxn",
+    "pghsxn",
+    "marglsxn",
+    "margrsxn",
+    "margtsxn",
+    "margbsxn",
+    "lndscpsxn",
+    "cols",
+    "colsx",
+    "sbknone",
+    "sbkcol",
+    "sbkpage",
+    "sbkeven",
+    "sbkodd",
+    "titlepg",
+    "pgnrestart",
+    "pgncont",
+    "pgnstarts",
+    "headery",
+    "footery",
+];
+
+/// Character type selected by `\loch`, `\hich` or `\dbch`.
+///
+/// Associated character properties (`\af`) that follow apply to it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CharType {
+    /// Single-byte low-ANSI characters, and right-to-left text.
+    #[default]
+    Loch,
+    /// Single-byte high-ANSI characters.
+    Hich,
+    /// Double-byte characters.
+    Dbch,
+}
+
+/// Control word values in effect for a group.
+///
+/// A persistent map: every `{` clones the enclosing group's state, which only
+/// shares the map, and setting a value copies the touched path alone.
+pub type GroupValues = im_rc::HashMap<Rc<str>, Option<i32>>;
+
 #[derive(Clone, Debug)]
 pub struct GroupState {
-    destinations: Rc<RefCell<HashMap<String, Destination>>>,
-    cur_destination: Option<String>,
-    dest_encoding: Option<&'static encoding_rs::Encoding>,
-    values: HashMap<String, Option<i32>>,
+    destinations: Rc<RefCell<Destinations>>,
+    cur_destination: Option<Rc<str>>,
+    dest_encoding: Option<&'static Encoding>,
+    /// Encoding for text written while no codepage is in effect.
+    fallback_encoding: &'static Encoding,
+    /// Set when the caller overrides the document encoding.
+    encoding_locked: bool,
+    char_direction: Direction,
+    paragraph_direction: Direction,
+    char_type: CharType,
+    /// Font of high-ANSI characters (`\hich\af`).
+    hich_font: Option<i32>,
+    /// Font of double-byte characters (`\dbch\af`).
+    dbch_font: Option<i32>,
+    values: GroupValues,
     opt_ignore_next_control: bool,
 }
 
 impl GroupState {
-    pub fn new(destinations: Rc<RefCell<HashMap<String, Destination>>>) -> Self {
+    pub fn new(destinations: Rc<RefCell<Destinations>>) -> Self {
         Self {
             destinations,
             cur_destination: None,
             dest_encoding: None,
-            values: HashMap::new(),
+            fallback_encoding: encoding_rs::WINDOWS_1252,
+            encoding_locked: false,
+            char_direction: Direction::Ltr,
+            paragraph_direction: Direction::Ltr,
+            char_type: CharType::Loch,
+            hich_font: None,
+            dbch_font: None,
+            values: GroupValues::new(),
             opt_ignore_next_control: false,
         }
     }
 
     pub fn set_codepage(&mut self, cp: u16) {
-        self.dest_encoding = codepage::to_encoding(cp);
+        self.set_encoding(codepage::to_encoding(cp));
     }
 
-    pub fn get_encoding(&mut self) -> Option<&'static encoding_rs::Encoding> {
+    pub fn get_encoding(&mut self) -> Option<&'static Encoding> {
         self.dest_encoding
     }
 
-    pub fn set_encoding(&mut self, encoding: Option<&'static encoding_rs::Encoding>) {
+    pub fn set_encoding(&mut self, encoding: Option<&'static Encoding>) {
+        if self.encoding_locked {
+            trace!("Ignoring document encoding {:?}", encoding.map(Encoding::name));
+            return;
+        }
         self.dest_encoding = encoding;
     }
 
-    pub fn set_destination(&mut self, name: &str, uses_encoding: bool) {
-        self.cur_destination = Some(name.to_owned());
+    pub fn set_fallback_encoding(&mut self, encoding: &'static Encoding) {
+        self.fallback_encoding = encoding;
+    }
+
+    /// Decodes all text with `encoding`, ignoring codepages set by the document.
+    pub fn override_encoding(&mut self, encoding: &'static Encoding) {
+        self.dest_encoding = Some(encoding);
+        self.encoding_locked = true;
+    }
+
+    /// Encoding of text written in this group.
+    ///
+    /// The charset of the current font wins over the document codepage; for
+    /// right-to-left runs that is the associated font (`\af`).
+    fn text_encoding(&self, fonts: &FontTable) -> &'static Encoding {
+        let font_encoding = if self.encoding_locked {
+            None
+        } else {
+            self.font().and_then(|font| fonts.encoding(font))
+        };
+        font_encoding
+            .or(self.dest_encoding)
+            .unwrap_or_else(|| {
+                trace!(
+                    "Writing to a text destination with no encoding set, using {}",
+                    self.fallback_encoding.name()
+                );
+                self.fallback_encoding
+            })
+    }
+
+    /// Decodes `bytes` written in this group.
+    ///
+    /// Left-to-right text with a high-ANSI or double-byte font of its own is
+    /// decoded range by range: ASCII as is, double-byte characters with the
+    /// `\dbch` font and remaining high bytes with the `\hich` font.
+    fn decode<'a>(&self, bytes: &'a [u8], fonts: &FontTable) -> Cow<'a, str> {
+        let encoding = self.text_encoding(fonts);
+        if self.encoding_locked || self.char_direction == Direction::Rtl {
+            return encoding.decode_without_bom_handling(bytes).0;
+        }
+        let hich = self
+            .hich_font
+            .and_then(|font| fonts.encoding(font))
+            .unwrap_or(encoding);
+        let dbch = self
+            .dbch_font
+            .and_then(|font| fonts.encoding(font))
+            .filter(|dbch| is_double_byte(dbch) && *dbch != encoding);
+        if hich == encoding && dbch.is_none() {
+            return encoding.decode_without_bom_handling(bytes).0;
+        }
+
+        let mut text = String::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i].is_ascii() {
+                let end = bytes[i..]
+                    .iter()
+                    .position(|b| !b.is_ascii())
+                    .map_or(bytes.len(), |n| i + n);
+                text.push_str(&encoding.decode_without_bom_handling(&bytes[i..end]).0);
+                i = end;
+                continue;
+            }
+            if let Some(dbch) = dbch {
+                if let Some(c) = bytes
+                    .get(i..i + 2)
+                    .and_then(|pair| dbch.decode_without_bom_handling_and_without_replacement(pair))
+                {
+                    text.push_str(&c);
+                    i += 2;
+                    continue;
+                }
+            }
+            text.push_str(&hich.decode_without_bom_handling(&bytes[i..i + 1]).0);
+            i += 1;
+        }
+        Cow::Owned(text)
+    }
+
+    /// Font of the current run, falling back to the document default font.
+    fn font(&self) -> Option<i32> {
+        let (font, default) = match self.char_direction {
+            Direction::Ltr => ("f", "deff"),
+            Direction::Rtl => ("af", "adeff"),
+        };
+        self.get_value(font)
+            .or_else(|| self.get_value(default))
+            .flatten()
+    }
+
+    /// Language of the current run, falling back to the document default.
+    ///
+    /// Right-to-left runs use the associated language (`\alang`), double-byte
+    /// runs the East Asian one (`\langfe`).
+    fn language(&self) -> Option<&'static str> {
+        let (language, default) = match (self.char_direction, self.char_type) {
+            (Direction::Rtl, _) => ("alang", "adeflang"),
+            (Direction::Ltr, CharType::Dbch) => ("langfe", "deflangfe"),
+            (Direction::Ltr, _) => ("lang", "deflang"),
+        };
+        self.get_value(language)
+            .or_else(|| self.get_value(default))
+            .flatten()
+            .and_then(lcid::bcp47)
+    }
+
+    /// Formatting of the current run.
+    fn run_props(&self) -> RunProps {
+        let (size, bold, italic) = match self.char_direction {
+            Direction::Ltr => ("fs", "b", "i"),
+            Direction::Rtl => ("afs", "ab", "ai"),
+        };
+        let toggled = |name| matches!(self.get_value(name), Some(None) | Some(Some(1..)));
+        RunProps {
+            direction: self.char_direction,
+            font: self.font(),
+            font_size: self.get_value(size).flatten(),
+            bold: toggled(bold),
+            italic: toggled(italic),
+            underline: toggled("ul"),
+            color: self.get_value("cf").flatten(),
+            language: self.language(),
+        }
+    }
+
+    pub fn set_char_direction(&mut self, direction: Direction) {
+        self.char_direction = direction;
+        self.char_type = CharType::Loch;
+    }
+
+    pub fn set_char_type(&mut self, char_type: CharType) {
+        self.char_type = char_type;
+    }
+
+    /// Sets the associated font (`\af`) of the current character type.
+    pub fn set_associated_font(&mut self, font: Option<i32>) {
+        match self.char_type {
+            CharType::Loch => self.set_value("af", font),
+            CharType::Hich => self.hich_font = font,
+            CharType::Dbch => self.dbch_font = font,
+        }
+    }
+
+    /// Number of fallback bytes following a `\u` character (`\uc`).
+    pub fn unicode_skip(&self) -> usize {
+        self.get_value("uc")
+            .flatten()
+            .map_or(1, |n| n.max(0) as usize)
+    }
+
+    pub fn set_paragraph_direction(&mut self, direction: Direction) {
+        self.paragraph_direction = direction;
+    }
+
+    pub fn set_document_direction(&mut self, direction: Direction) {
+        (*self.destinations).borrow_mut().body.direction = direction;
+    }
+
+    /// Resets paragraph formatting (`\pard`).
+    pub fn reset_paragraph_formatting(&mut self) {
+        self.paragraph_direction = Direction::Ltr;
+    }
+
+    /// Resets character formatting (`\plain`).
+    pub fn reset_character_formatting(&mut self) {
+        self.char_direction = Direction::Ltr;
+        self.char_type = CharType::Loch;
+        self.hich_font = None;
+        self.dbch_font = None;
+        for name in CHARACTER_FORMATTING {
+            self.values.remove(*name);
+        }
+    }
+
+    /// Resets section formatting (`\sectd`).
+    pub fn reset_section_formatting(&mut self) {
+        for name in SECTION_FORMATTING {
+            self.values.remove(*name);
+        }
+    }
+
+    /// Whether flag `name` is set and not turned off with a `0` argument.
+    fn is_set(&self, name: &str) -> bool {
+        matches!(self.get_value(name), Some(None) | Some(Some(1..)))
+    }
+
+    fn value_or(&self, name: &str, default: i32) -> i32 {
+        self.get_value(name).flatten().unwrap_or(default)
+    }
+
+    /// Page size and margins of the document.
+    fn document_page(&self) -> PageSetup {
+        let default = PageSetup::default();
+        PageSetup {
+            width: self.value_or("paperw", default.width),
+            height: self.value_or("paperh", default.height),
+            margin_left: self.value_or("margl", default.margin_left),
+            margin_right: self.value_or("margr", default.margin_right),
+            margin_top: self.value_or("margt", default.margin_top),
+            margin_bottom: self.value_or("margb", default.margin_bottom),
+            landscape: self.is_set("landscape"),
+        }
+    }
+
+    /// Records the layout of the section ending in this group, along with the
+    /// headers and footers written since the previous section.
+    ///
+    /// Section properties fall back to those of the document; a section
+    /// without headers or footers of its own keeps those of the previous one.
+    pub fn record_section(&self) {
+        let document = self.document_page();
+        let page = PageSetup {
+            width: self.value_or("pgwsxn", document.width),
+            height: self.value_or("pghsxn", document.height),
+            margin_left: self.value_or("marglsxn", document.margin_left),
+            margin_right: self.value_or("margrsxn", document.margin_right),
+            margin_top: self.value_or("margtsxn", document.margin_top),
+            margin_bottom: self.value_or("margbsxn", document.margin_bottom),
+            landscape: document.landscape || self.is_set("lndscpsxn"),
+        };
+        let break_kind = [
+            ("sbknone", SectionBreak::None),
+            ("sbkcol", SectionBreak::Column),
+            ("sbkeven", SectionBreak::Even),
+            ("sbkodd", SectionBreak::Odd),
+        ]
+        .into_iter()
+        .find(|(name, _)| self.is_set(name))
+        .map_or(SectionBreak::Page, |(_, kind)| kind);
+        let defaults = Section::default();
+
+        let mut destinations = (*self.destinations).borrow_mut();
+        let mut take = |name: &str| match destinations.entries.remove(name) {
+            Some(Destination::Text(text)) => Some(text.trim_end_matches('\n').to_string()),
+            _ => None,
+        };
+        let mut header = HeaderFooter {
+            all: take("header"),
+            first: take("headerf"),
+            left: take("headerl"),
+            right: take("headerr"),
+        };
+        let mut footer = HeaderFooter {
+            all: take("footer"),
+            first: take("footerf"),
+            left: take("footerl"),
+            right: take("footerr"),
+        };
+        let layout = &mut destinations.layout;
+        if let Some(previous) = layout.sections.last() {
+            if header.is_empty() {
+                header = previous.header.clone();
+            }
+            if footer.is_empty() {
+                footer = previous.footer.clone();
+            }
+        }
+        layout.page = document;
+        layout.facing_pages = self.is_set("facingp");
+        layout.page_number_start = self.value_or("pgnstart", 1);
+        layout.sections.push(Section {
+            page,
+            columns: self.value_or("cols", defaults.columns).max(1),
+            column_spacing: self.value_or("colsx", defaults.column_spacing),
+            break_kind,
+            title_page: self.is_set("titlepg"),
+            page_number_start: self
+                .is_set("pgnrestart")
+                .then(|| self.value_or("pgnstarts", 1)),
+            header_distance: self.value_or("headery", defaults.header_distance),
+            footer_distance: self.value_or("footery", defaults.footer_distance),
+            header,
+            footer,
+        });
+    }
+
+    /// Ends the current section of the body (`\sect`).
+    pub fn end_section(&mut self) {
+        if self.cur_destination.as_deref() != Some(BODY_DESTINATION) {
+            return;
+        }
+        self.record_section();
+        (*self.destinations).borrow_mut().body.end_section();
+    }
+
+    /// Font table entry being defined by this group, if any.
+    fn defined_font(&self) -> Option<i32> {
+        if self.cur_destination.as_deref() == Some(FONT_TABLE_DESTINATION) {
+            self.get_value("f").flatten()
+        } else {
+            None
+        }
+    }
+
+    /// Sets the character set (`\fcharset`) of the font being defined.
+    pub fn set_font_charset(&mut self, charset: i32) {
+        if let Some(font) = self.defined_font() {
+            (*self.destinations).borrow_mut().fonts.set_charset(font, charset);
+        }
+    }
+
+    /// Sets the codepage (`\cpg`) of the font being defined.
+    pub fn set_font_codepage(&mut self, codepage: u16) {
+        if let Some(font) = self.defined_font() {
+            (*self.destinations).borrow_mut().fonts.set_codepage(font, codepage);
+        }
+    }
+
+    pub fn set_destination(&mut self, name: &str, uses_encoding: bool) -> Result<(), RtfError> {
+        let requested = if uses_encoding {
+            DestinationKind::Text
+        } else {
+            DestinationKind::Bytes
+        };
         let mut dest = (*self.destinations).borrow_mut();
         match dest.get(name) {
-            Some(Destination::Text(string)) => {
-                debug!(
-                    "Switching to destination {}, with current length {})",
-                    name,
-                    string.len()
-                );
-                assert!(uses_encoding);
+            Some(existing) if existing.kind() != requested => {
+                // Leave the group without a destination rather than writing into
+                // a destination of the wrong kind; its content is discarded.
+                self.cur_destination = None;
+                return Err(RtfError::DestinationKindMismatch {
+                    name: name.to_owned(),
+                    existing: existing.kind(),
+                    requested,
+                });
             }
-            Some(Destination::Bytes(bytes)) => {
+            Some(existing) => {
                 debug!(
                     "Switching to destination {}, with current length {})",
                     name,
-                    bytes.len()
+                    existing.as_bytes().len()
                 );
-                assert!(!uses_encoding);
             }
             None => {
                 if uses_encoding {
-                    dest.insert(
+                    dest.entries.insert(
                         name.to_string(),
                         Destination::Text(String::with_capacity(256)),
                     );
                 } else {
-                    dest.insert(name.to_string(), Destination::Bytes(Vec::new()));
+                    dest.entries
+                        .insert(name.to_string(), Destination::Bytes(Vec::new()));
                 }
             }
         }
+        self.cur_destination = Some(Rc::from(name));
+        Ok(())
     }
 
-    pub fn get_destination_name(&self) -> Option<String> {
+    pub fn get_destination_name(&self) -> Option<Rc<str>> {
         self.cur_destination.clone()
     }
 
-    pub fn write(&mut self, bytes: &[u8]) {
+    /// Writes bytes in the group encoding to the current destination.
+    pub fn write(&mut self, bytes: &[u8]) -> Result<(), RtfError> {
+        self.write_with(bytes, None)
+    }
+
+    /// Writes already decoded text to the current destination.
+    pub fn write_text(&mut self, text: &str) -> Result<(), RtfError> {
+        self.write_with(text.as_bytes(), Some(text))
+    }
+
+    fn write_with(&mut self, bytes: &[u8], decoded: Option<&str>) -> Result<(), RtfError> {
         let dest_name = match self.get_destination_name() {
             Some(name) => name,
             None => {
@@ -121,26 +585,33 @@ impl GroupState {
                     "Document format error: Document text found outside of any document group: '{:?}'",
                     bytes
                 );
-                return;
+                return Ok(());
             }
         };
-        if let Some(dest) = (*self.destinations).borrow_mut().get_mut(&dest_name) {
-            match dest {
-                Destination::Text(_) => {
-                    if let Some(ref mut decoder) = self.dest_encoding {
-                        dest.append_text(&decoder.decode(bytes).0);
-                    } else {
-                        warn!(
-                            "Writing to a text destination ({}) with no encoding set!",
-                            dest_name
-                        );
-                    }
+        let mut destinations = (*self.destinations).borrow_mut();
+        let destinations = &mut *destinations;
+        let dest = destinations
+            .entries
+            .get_mut(&*dest_name)
+            .ok_or_else(|| RtfError::MissingDestination(dest_name.to_string()))?;
+        let len_before = dest.as_bytes().len();
+        let result = match dest {
+            Destination::Text(_) => {
+                let text = match decoded {
+                    Some(text) => Cow::Borrowed(text),
+                    None => self.decode(bytes, &destinations.fonts),
+                };
+                if &*dest_name == BODY_DESTINATION {
+                    destinations
+                        .body
+                        .push_text(&text, self.run_props(), self.paragraph_direction);
                 }
-                Destination::Bytes(_) => dest.append_bytes(bytes),
+                dest.append_text(&dest_name, &text)
             }
-        } else {
-            panic!("Programming error: specified destination {} doesn't exist after verifying its existence", dest_name);
-        }
+            Destination::Bytes(_) => dest.append_bytes(&dest_name, bytes),
+        };
+        destinations.total_len += dest.as_bytes().len() - len_before;
+        result
     }
 
     pub fn set_opt_ignore_next_control(&mut self) {
@@ -153,22 +624,92 @@ impl GroupState {
         old
     }
 
+    /// Sets the value of control word `name` in this group.
     pub fn set_value(&mut self, name: &str, value: Option<i32>) {
-        self.values.insert(name.to_string(), value);
+        // Word repeats the formatting of the enclosing group in most groups:
+        // an unchanged value keeps the map shared.
+        if self.values.get(name) != Some(&value) {
+            self.values.insert(Rc::from(name), value);
+        }
+    }
+
+    /// Value of control word `name` in this group, if it has been set.
+    pub fn get_value(&self, name: &str) -> Option<Option<i32>> {
+        self.values.get(name).copied()
+    }
+}
+
+/// Whether `encoding` is one of the double-byte ANSI codepages (932, 936, 949, 950).
+fn is_double_byte(encoding: &'static Encoding) -> bool {
+    encoding == encoding_rs::SHIFT_JIS
+        || encoding == encoding_rs::GBK
+        || encoding == encoding_rs::EUC_KR
+        || encoding == encoding_rs::BIG5
+}
+
+/// Records the last section of the body when `group`, the outermost group,
+/// ends.
+fn close_body(group: &GroupState) {
+    if group.cur_destination.as_deref() == Some(BODY_DESTINATION) {
+        group.record_section();
+    }
+}
+
+/// Number of tokens processed between two checks of the wall-clock budget.
+const DURATION_CHECK_INTERVAL: usize = 256;
+
+fn ensure_within(limit: Limit, value: usize, max: usize) -> Result<(), LimitExceeded> {
+    if value > max {
+        Err(LimitExceeded::new(limit, max))
+    } else {
+        Ok(())
     }
 }
 
 #[derive(Clone, Debug)]
 struct DocumentState {
-    destinations: Rc<RefCell<HashMap<String, Destination>>>,
+    destinations: Rc<RefCell<Destinations>>,
     group_stack: Vec<GroupState>,
+    diagnostics: Vec<RtfError>,
+    limits: Limits,
+    charset: Option<&'static Encoding>,
+    fallback_encoding: &'static Encoding,
+    started: Instant,
+    tokens: usize,
+    control_words: usize,
+    images: usize,
+    /// Text bytes and `\'hh` escapes awaiting decoding, so that a double-byte
+    /// character split across tokens is decoded as a whole.
+    pending: Vec<u8>,
+    /// Fallback bytes still to skip after a `\u` character.
+    unicode_skip: usize,
+    /// High surrogate of a `\u` pair awaiting its low surrogate.
+    high_surrogate: Option<u16>,
 }
 
 impl DocumentState {
-    fn new() -> Self {
+    fn new(options: &ConvertOptions, fallback_encoding: &'static Encoding) -> Self {
         Self {
-            destinations: Rc::new(RefCell::new(HashMap::new())),
+            destinations: Rc::new(RefCell::new(Destinations::default())),
             group_stack: Vec::new(),
+            diagnostics: Vec::new(),
+            limits: options.limits.clone(),
+            charset: options.charset,
+            fallback_encoding,
+            started: Instant::now(),
+            tokens: 0,
+            control_words: 0,
+            images: 0,
+            pending: Vec::new(),
+            unicode_skip: 0,
+            high_surrogate: None,
+        }
+    }
+
+    fn report(&mut self, result: Result<(), RtfError>) {
+        if let Err(err) = result {
+            warn!("Document format error: {}", err);
+            self.diagnostics.push(err);
         }
     }
 
@@ -179,54 +720,128 @@ impl DocumentState {
     fn do_control_symbol(&mut self, symbol: char, word_is_optional: bool) {
         let mut sym_bytes = [0; 4];
         let sym_str = symbol.encode_utf8(&mut sym_bytes);
-        if let Some(group_state) = self.get_last_group_mut() {
-            if let Some(symbol_handler) = control::SYMBOLS.get(sym_str) {
-                symbol_handler(group_state, sym_str, None);
+        let result = if let Some(group_state) = self.get_last_group_mut() {
+            if let Some(control) = control::lookup(sym_str) {
+                (control.handler)(group_state, sym_str, None)
             } else if word_is_optional {
                 info!("Skipping optional unsupported control word \\{}", symbol);
+                Ok(())
             } else {
                 warn!(
                     "Unsupported/illegal control symbol \\{} (writing to document anyway)",
                     symbol
                 );
                 self.write_to_current_destination(format!("{symbol}").as_bytes());
+                Ok(())
             }
         } else {
             warn!(
                 "Document format error: Control symbol found outside of any document group: '\\{}'",
                 symbol
             );
-        }
+            Ok(())
+        };
+        self.report(result);
     }
 
     fn do_control_word(&mut self, name: &str, arg: Option<i32>, word_is_optional: bool) {
-        if let Some(group_state) = self.get_last_group_mut() {
-            if let Some(dest_handler) = control::DESTINATIONS.get(name) {
-                dest_handler(group_state, name, arg);
-            } else if let Some(symbol_handler) = control::SYMBOLS.get(name) {
-                symbol_handler(group_state, name, arg);
-            } else if let Some(value_handler) = control::VALUES.get(name) {
-                value_handler(group_state, name, arg);
-            } else if let Some(flag_handler) = control::FLAGS.get(name) {
-                flag_handler(group_state, name, arg);
-            } else if let Some(toggle_handler) = control::TOGGLES.get(name) {
-                toggle_handler(group_state, name, arg);
+        let result = if let Some(group_state) = self.get_last_group_mut() {
+            if let Some(control) = control::lookup(name) {
+                (control.handler)(group_state, name, arg)
             } else if word_is_optional {
                 warn!("Skipping optional unsupported control word \\{}", name);
+                Ok(())
             } else {
                 warn!("Unsupported/illegal control word \\{}", name);
+                Ok(())
             }
         } else {
             warn!(
                 "Document format error: Control word found outside of any document group: '\\{}'",
                 name
             );
+            Ok(())
+        };
+        self.report(result);
+    }
+
+    /// Writes the bytes buffered in [DocumentState::pending].
+    fn flush_pending(&mut self) {
+        if !self.pending.is_empty() {
+            let pending = std::mem::take(&mut self.pending);
+            self.write_to_current_destination(&pending);
+        }
+    }
+
+    /// Writes out what is left buffered at the end of the token stream.
+    fn finish(&mut self) -> Result<(), LimitExceeded> {
+        self.flush_pending();
+        // Document truncated before the end of its body group.
+        if let Some(group) = self.group_stack.first() {
+            close_body(group);
+        }
+        self.check_output_len()
+    }
+
+    fn check_output_len(&self) -> Result<(), LimitExceeded> {
+        ensure_within(
+            Limit::OutputBytes,
+            self.destinations.borrow().total_len(),
+            self.limits.max_output_bytes,
+        )
+    }
+
+    /// Writes the UTF-16 code unit of a `\u` control word.
+    fn do_unicode(&mut self, arg: Option<i32>) {
+        let unit = arg.unwrap_or(b'?' as i32) as u16;
+        let units = match self.high_surrogate.take() {
+            Some(high) => vec![high, unit],
+            None if (0xD800..0xDC00).contains(&unit) => {
+                self.high_surrogate = Some(unit);
+                return;
+            }
+            None => vec![unit],
+        };
+        let text: String = char::decode_utf16(units)
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect();
+        if let Some(group) = self.get_last_group_mut() {
+            let result = group.write_text(&text);
+            self.report(result);
+        }
+    }
+
+    /// Consumes `token` if it is part of the fallback of a `\u` character.
+    ///
+    /// Each `\'hh` escape, text byte or control word counts as one byte of
+    /// the fallback; the end of a group ends it early.
+    fn skip_unicode_fallback<'a>(&mut self, token: &'a Token) -> Option<&'a Token> {
+        if self.unicode_skip == 0 {
+            return Some(token);
+        }
+        match token {
+            Token::Text(bytes) => {
+                let skipped = self.unicode_skip.min(bytes.len());
+                self.unicode_skip -= skipped;
+                self.pending.extend_from_slice(&bytes[skipped..]);
+                None
+            }
+            Token::StartGroup | Token::EndGroup => {
+                self.unicode_skip = 0;
+                Some(token)
+            }
+            Token::Newline(_) => None,
+            _ => {
+                self.unicode_skip -= 1;
+                None
+            }
         }
     }
 
     fn write_to_current_destination(&mut self, bytes: &[u8]) {
         if let Some(group) = self.get_last_group_mut() {
-            group.write(bytes);
+            let result = group.write(bytes);
+            self.report(result);
         } else {
             // it is a fundamental document formatting error for text to appear outside of the {\rtf1 } group
             warn!(
@@ -236,19 +851,32 @@ impl DocumentState {
         }
     }
 
-    fn start_group(&mut self) {
+    fn start_group(&mut self) -> Result<(), LimitExceeded> {
+        ensure_within(
+            Limit::GroupDepth,
+            self.group_stack.len() + 1,
+            self.limits.max_group_depth,
+        )?;
         if let Some(last_group) = self.get_last_group() {
             self.group_stack.push(last_group.clone());
         } else {
             debug!("Creating initial group...");
-            self.group_stack
-                .push(GroupState::new(self.destinations.clone()));
+            let mut group = GroupState::new(self.destinations.clone());
+            group.set_fallback_encoding(self.fallback_encoding);
+            if let Some(charset) = self.charset {
+                group.override_encoding(charset);
+            }
+            self.group_stack.push(group);
         }
+        Ok(())
     }
 
     fn end_group(&mut self) {
-        if let Some(_group) = self.group_stack.pop() {
+        if let Some(group) = self.group_stack.pop() {
             // TODO: destination-folding support (tables, etc)
+            if self.group_stack.is_empty() {
+                close_body(&group);
+            }
         } else {
             warn!("Document format error: End group count exceeds number start groups");
         }
@@ -262,50 +890,432 @@ impl DocumentState {
         self.group_stack.last()
     }
 
-    fn process_token(&mut self, token: &Token) {
+    fn check_limits(&mut self, token: &Token) -> Result<(), LimitExceeded> {
+        self.tokens += 1;
+        if self.tokens.is_multiple_of(DURATION_CHECK_INTERVAL)
+            && self.started.elapsed() > self.limits.max_duration()
+        {
+            return Err(LimitExceeded::new(
+                Limit::Duration,
+                self.limits.max_duration_ms,
+            ));
+        }
+
+        match token {
+            Token::ControlSymbol(_) | Token::ControlWord { .. } => {
+                self.control_words += 1;
+                ensure_within(
+                    Limit::ControlWords,
+                    self.control_words,
+                    self.limits.max_control_words,
+                )?;
+            }
+            _ => (),
+        }
+        if let Token::ControlWord { name, .. } = token {
+            if name == "pict" {
+                self.images += 1;
+                ensure_within(Limit::Images, self.images, self.limits.max_images)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn process_token(&mut self, token: &Token) -> Result<(), LimitExceeded> {
+        self.check_limits(token)?;
+        let token = match self.skip_unicode_fallback(token) {
+            Some(token) => token,
+            None => return Ok(()),
+        };
+
         let word_is_optional = self
             .get_last_group_mut()
             .map(|group| group.get_and_clear_ignore_next_control())
             .unwrap_or(false);
 
+        // Buffer text until the next control word or group boundary
+        match token {
+            Token::Text(bytes) => {
+                self.pending.extend_from_slice(bytes);
+                return Ok(());
+            }
+            Token::ControlWord { name, arg: Some(arg) } if name == "'" => {
+                self.pending.push((*arg & 0xFF) as u8);
+                return Ok(());
+            }
+            Token::Newline(_) => return Ok(()),
+            _ => self.flush_pending(),
+        }
+        if !matches!(token, Token::ControlWord { name, .. } if name == "u") {
+            self.high_surrogate = None;
+        }
+
         // Update state for this token
         match token {
             Token::ControlSymbol(c) => self.do_control_symbol(*c, word_is_optional),
+            Token::ControlWord { name, arg } if name == "u" => {
+                self.do_unicode(*arg);
+                self.unicode_skip = self
+                    .get_last_group()
+                    .map_or(1, GroupState::unicode_skip);
+            }
             Token::ControlWord { name, arg } => self.do_control_word(name, *arg, word_is_optional),
             Token::ControlBin(data) => self.do_control_bin(data, word_is_optional),
-            Token::Text(bytes) => self.write_to_current_destination(bytes),
-            Token::StartGroup => self.start_group(),
+            Token::StartGroup => self.start_group()?,
             Token::EndGroup => self.end_group(),
             _ => (),
         }
+
+        self.check_output_len()
     }
 }
 
-pub fn tokenize<R: Read>(mut reader: R) -> Result<Vec<Token>> {
+/// Reads at most `limits.max_input_bytes` from `reader` and tokenizes them.
+pub fn tokenize<R: Read>(reader: R, limits: &Limits) -> Result<Vec<Token>> {
     let mut data: Vec<u8> = Vec::with_capacity(4096);
     debug!("Reading all data from input.");
     reader
+        .take(limits.max_input_bytes as u64 + 1)
         .read_to_end(&mut data)
         .context("Error reading from input file")?;
+    ensure_within(Limit::InputBytes, data.len(), limits.max_input_bytes)?;
 
     debug!("Parsing into token stream.");
     parse_tokens(&data).map_err(|e| anyhow::anyhow!("Error parsing RTF tokens: {}", e))
 }
 
-pub fn write_plaintext<W: Write>(token_stream: &[Token], mut writer: W) -> Result<()> {
-    let mut state = DocumentState::new();
+/// Interprets a token stream into the body layout of the document.
+///
+/// Malformed constructs never abort the conversion; they are skipped and
+/// returned as diagnostics. Exceeding one of the limits aborts it with a
+/// [LimitExceeded] error.
+///
+/// Text is decoded with `options.charset` when set, otherwise with the
+/// codepage declared by the document or its fonts, falling back to
+/// [fallback_encoding] where it declares none.
+pub fn read_document(token_stream: &[Token], options: &ConvertOptions) -> Result<Document> {
+    let fallback = match options.charset {
+        Some(charset) => charset,
+        None => fallback_encoding(token_stream),
+    };
+    let mut state = DocumentState::new(options, fallback);
 
     debug!("Iterating over token stream.");
     for token in token_stream.iter().filter(|c| c != &&Token::Newline(b"\r\n".to_vec())) {
-        state.process_token(token);
+        state.process_token(token)?;
     }
+    state.finish()?;
     debug!("Finished token stream iteration.");
 
-    if let Some(dest) = (*state.destinations).borrow().get("rtf") {
-        debug!("Writing rtf1 content...");
-        writer
-            .write(dest.as_bytes())
-            .context("Error writing to output file")?;
+    let (mut body, layout) = {
+        let mut destinations = (*state.destinations).borrow_mut();
+        (
+            std::mem::take(&mut destinations.body),
+            std::mem::take(&mut destinations.layout),
+        )
+    };
+    body.language = token_stream
+        .iter()
+        .find_map(|token| match token {
+            Token::ControlWord { name, arg } if name == "deflang" => *arg,
+            _ => None,
+        })
+        .and_then(lcid::bcp47);
+    Ok(Document {
+        body,
+        layout,
+        diagnostics: state.diagnostics,
+    })
+}
+
+/// Plain text of `body`, one line per paragraph.
+///
+/// Text stays in logical order. Right-to-left paragraphs, and paragraphs
+/// mixing directions, start with a mark of their direction, which is repeated
+/// after each run written against it so that neutral characters around the
+/// run resolve to the paragraph direction.
+pub fn body_text(body: &Body) -> String {
+    let mut text = String::new();
+    for paragraph in &body.paragraphs {
+        let mark = paragraph.direction.mark();
+        if paragraph.direction == Direction::Rtl || paragraph.is_bidi() {
+            text.push(mark);
+        }
+        for run in &paragraph.runs {
+            text.push_str(&run.text);
+            if run.props.direction != paragraph.direction {
+                text.push(mark);
+            }
+        }
+        if paragraph.terminated {
+            text.push('\n');
+        }
+    }
+    text
+}
+
+/// Writes the plain text of the document body to `writer`.
+///
+/// See [read_document] for how the token stream is interpreted.
+pub fn write_plaintext<W: Write>(
+    token_stream: &[Token],
+    mut writer: W,
+    options: &ConvertOptions,
+) -> Result<Vec<RtfError>> {
+    let document = read_document(token_stream, options)?;
+
+    debug!("Writing rtf1 content...");
+    writer
+        .write_all(body_text(&document.body).as_bytes())
+        .context("Error writing to output file")?;
+    Ok(document.diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::{collection::vec, prelude::*, sample::select};
+
+    fn control_name() -> impl Strategy<Value = String> {
+        prop_oneof![
+            select(vec![
+                "rtf", "ansi", "mac", "pc", "pca", "ansicpg", "fonttbl", "colortbl", "pict",
+                "pntext", "pnseclvl", "info", "*", "'", "par", "cell", "row", "tab", "u", "uc",
+                "bin", "f", "b", "i",
+            ])
+            .prop_map(String::from),
+            "[a-zA-Z]{1,12}",
+        ]
+    }
+
+    fn token() -> impl Strategy<Value = Token> {
+        prop_oneof![
+            // Tokens are not `Clone`, as `Just` requires.
+            Just(()).prop_map(|_| Token::StartGroup),
+            Just(()).prop_map(|_| Token::EndGroup),
+            any::<char>().prop_map(Token::ControlSymbol),
+            (control_name(), any::<Option<i32>>())
+                .prop_map(|(name, arg)| Token::ControlWord { name, arg }),
+            vec(any::<u8>(), 0..16).prop_map(Token::ControlBin),
+            vec(any::<u8>(), 0..32).prop_map(Token::Text),
+            vec(select(vec![b'\r', b'\n']), 1..3).prop_map(Token::Newline),
+        ]
+    }
+
+    fn plaintext_with(rtf: &[u8], options: &ConvertOptions) -> (String, Vec<RtfError>) {
+        let tokens = tokenize(rtf, &options.limits).unwrap();
+        let mut out = Vec::new();
+        let diagnostics = write_plaintext(&tokens, &mut out, options).unwrap();
+        (String::from_utf8(out).unwrap(), diagnostics)
+    }
+
+    fn plaintext(rtf: &[u8]) -> (String, Vec<RtfError>) {
+        plaintext_with(rtf, &ConvertOptions::default())
+    }
+
+    #[test]
+    fn test_plaintext() {
+        let (text, diagnostics) = plaintext(br"{\rtf1\ansi{\fonttbl\f0 Arial;}Caf\'e9\par done}");
+        assert_eq!(text, "Caf\u{e9}\ndone");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_destination_kind_mismatch_is_a_diagnostic() {
+        let tokens = vec![
+            Token::StartGroup,
+            Token::ControlWord { name: "rtf".to_string(), arg: Some(1) },
+            Token::ControlWord { name: "ansi".to_string(), arg: None },
+            Token::Text(b"kept".to_vec()),
+            Token::StartGroup,
+            Token::ControlWord { name: "pict".to_string(), arg: None },
+            Token::Text(b"0102".to_vec()),
+            Token::EndGroup,
+            Token::EndGroup,
+        ];
+        let mut state = DocumentState::new(&ConvertOptions::default(), encoding_rs::WINDOWS_1252);
+        for token in &tokens {
+            state.process_token(token).unwrap();
+        }
+        // Reopen the byte destination `pict` as a text destination.
+        let mut group = GroupState::new(state.destinations.clone());
+        let err = group.set_destination("pict", true).unwrap_err();
+        assert_eq!(
+            err,
+            RtfError::DestinationKindMismatch {
+                name: "pict".to_string(),
+                existing: DestinationKind::Bytes,
+                requested: DestinationKind::Text,
+            }
+        );
+        assert_eq!(group.get_destination_name(), None);
+        assert!(group.write(b"dropped").is_ok());
+    }
+
+    #[test]
+    fn test_group_values_are_scoped() {
+        let limits = Limits::default();
+        let options = ConvertOptions::new(limits.clone());
+        let mut state = DocumentState::new(&options, encoding_rs::WINDOWS_1252);
+        for token in tokenize(&br"{\rtf1\b{\i\b0 inner}"[..], &limits).unwrap() {
+            state.process_token(&token).unwrap();
+        }
+        let group = state.get_last_group().unwrap();
+        assert_eq!(group.get_value("b"), Some(None));
+        assert_eq!(group.get_value("i"), None);
+    }
+
+    #[test]
+    fn test_group_depth_limit() {
+        let limits = Limits {
+            max_group_depth: 2,
+            ..Limits::default()
+        };
+        let tokens = tokenize(&br"{\rtf1{{deep}}}"[..], &limits).unwrap();
+        let err = write_plaintext(&tokens, Vec::new(), &ConvertOptions::new(limits.clone())).unwrap_err();
+        assert_eq!(
+            err.downcast::<LimitExceeded>().unwrap(),
+            LimitExceeded::new(Limit::GroupDepth, 2)
+        );
+    }
+
+    #[test]
+    fn test_input_and_output_limits() {
+        let rtf = br"{\rtf1\ansi some text}";
+        let limits = Limits {
+            max_input_bytes: 8,
+            ..Limits::default()
+        };
+        let err = tokenize(&rtf[..], &limits).unwrap_err();
+        assert_eq!(
+            err.downcast::<LimitExceeded>().unwrap().limit,
+            Limit::InputBytes
+        );
+
+        let limits = Limits {
+            max_output_bytes: 4,
+            ..Limits::default()
+        };
+        let tokens = tokenize(&rtf[..], &limits).unwrap();
+        let err = write_plaintext(&tokens, Vec::new(), &ConvertOptions::new(limits.clone())).unwrap_err();
+        assert_eq!(
+            err.downcast::<LimitExceeded>().unwrap().limit,
+            Limit::OutputBytes
+        );
+    }
+
+    #[test]
+    fn test_text_without_codepage_is_kept() {
+        let (text, _) = plaintext(br"{\rtf1 Caf\'e9}");
+        assert_eq!(text, "Caf\u{e9}");
+
+        // Russian default language, no \ansi or \ansicpg.
+        let (text, _) = plaintext(br"{\rtf1\deflang1049 \'cf\'f0\'e8\'e2\'e5\'f2}");
+        assert_eq!(text, "\u{41f}\u{440}\u{438}\u{432}\u{435}\u{442}");
+    }
+
+    #[test]
+    fn test_charset_override() {
+        let options = ConvertOptions {
+            charset: Some(encoding_rs::WINDOWS_1251),
+            ..ConvertOptions::default()
+        };
+        // Declares Western European but actually holds Cyrillic text.
+        let (text, _) = plaintext_with(br"{\rtf1\ansi\ansicpg1252 \'e4\'e0}", &options);
+        assert_eq!(text, "\u{434}\u{430}");
+    }
+
+    #[test]
+    fn test_rtl_runs() {
+        // Hebrew paragraph quoting an English word, with a Hebrew font over a
+        // Western European document codepage.
+        let rtf = br"{\rtf1\ansi\ansicpg1252\deff0{\fonttbl{\f0\fcharset0 Times;}{\f1\fcharset177 David;}}
+\rtldoc\pard\rtlpar{\rtlch\af1 \'f9\'ec\'e5\'ed }{\ltrch\f0 Word}{\rtlch\af1 .}\par}";
+        let options = ConvertOptions::default();
+        let tokens = tokenize(&rtf[..], &options.limits).unwrap();
+        let document = read_document(&tokens, &options).unwrap();
+        assert_eq!(document.body.direction, Direction::Rtl);
+
+        let paragraph = &document.body.paragraphs[0];
+        assert_eq!(paragraph.direction, Direction::Rtl);
+        assert_eq!(paragraph.runs[0].text, "\u{5e9}\u{5dc}\u{5d5}\u{5dd} ");
+        assert_eq!(paragraph.runs[0].props.font, Some(1));
+        assert_eq!(paragraph.runs[1].props.direction, Direction::Ltr);
+
+        assert_eq!(
+            body_text(&document.body),
+            "\u{200f}\u{5e9}\u{5dc}\u{5d5}\u{5dd} Word\u{200f}.\n"
+        );
+    }
+
+    #[test]
+    fn test_plain_resets_character_formatting() {
+        let (text, _) = plaintext(br"{\rtf1\pard\rtlpar\rtlch\ab x\plain y\par\pard z}");
+        assert_eq!(text, "\u{200f}xy\u{200f}\nz");
+    }
+
+    #[test]
+    fn test_read_layout() {
+        let rtf = br"{\rtf1\paperw11906\margl1000\facingp\pgnstart5
+\sectd\cols2\titlepg{\headerf Title}{\headerl Left}{\headerr Right}One\sect
+\sectd\sbknone\pgnrestart\marglsxn500 Two\par}";
+        let options = ConvertOptions::default();
+        let tokens = tokenize(&rtf[..], &options.limits).unwrap();
+        let document = read_document(&tokens, &options).unwrap();
+        let layout = &document.layout;
+        assert!(layout.facing_pages);
+        assert_eq!(layout.page_number_start, 5);
+        assert_eq!(layout.page.width, 11906);
+        assert_eq!(layout.sections.len(), 2);
+
+        let (first, second) = (&layout.sections[0], &layout.sections[1]);
+        assert_eq!((first.columns, first.title_page), (2, true));
+        assert_eq!(first.header.first.as_deref(), Some("Title"));
+        assert_eq!(first.header.for_page(first, layout, 6, false), Some("Left"));
+        assert_eq!(first.header.for_page(first, layout, 7, false), Some("Right"));
+        assert_eq!(second.break_kind, SectionBreak::None);
+        assert_eq!((second.columns, second.title_page), (1, false));
+        assert_eq!(second.page_number_start, Some(1));
+        assert_eq!(second.page.margin_left, 500);
+        // Headers carry over to sections without their own.
+        assert_eq!(second.header, first.header);
+
+        let sections: Vec<usize> = document.body.paragraphs.iter().map(|p| p.section).collect();
+        assert_eq!(sections, [0, 0, 1]);
+    }
+
+    #[test]
+    fn test_image_limit() {
+        let limits = Limits {
+            max_images: 1,
+            ..Limits::default()
+        };
+        let tokens = tokenize(&br"{\rtf1{\pict 00}{\pict 01}}"[..], &limits).unwrap();
+        let err = write_plaintext(&tokens, Vec::new(), &ConvertOptions::new(limits.clone())).unwrap_err();
+        assert_eq!(
+            err.downcast::<LimitExceeded>().unwrap(),
+            LimitExceeded::new(Limit::Images, 1)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn write_plaintext_never_panics(tokens in vec(token(), 0..256)) {
+            prop_assert!(write_plaintext(&tokens, Vec::new(), &ConvertOptions::default()).is_ok());
+        }
+
+        #[test]
+        fn tokenize_and_write_plaintext_never_panic(
+            body in vec(prop_oneof![
+                Just(b'{'), Just(b'}'), Just(b'\\'), Just(b'\''), Just(b' '), any::<u8>()
+            ], 0..512)
+        ) {
+            let mut rtf = br"{\rtf1\ansi ".to_vec();
+            rtf.extend(body);
+            let limits = Limits::default();
+            if let Ok(tokens) = tokenize(rtf.as_slice(), &limits) {
+                prop_assert!(write_plaintext(&tokens, Vec::new(), &ConvertOptions::new(limits.clone())).is_ok());
+            }
+        }
     }
-    Ok(())
 }
@@ -0,0 +1,781 @@
+//! Builder API emitting RTF 1.9 documents.
+//!
+//! A document is assembled from [Block]s (paragraphs, tables, lists and
+//! images) made of [Run]s of formatted text, then rendered with
+//! [RtfBuilder::build]. Fonts and colors used by runs are collected into the
+//! font and color tables as they are added.
+
+use std::fmt::Write;
+
+/// Default font of generated documents.
+const DEFAULT_FONT: &str = "Calibri";
+
+/// Monospace font, used for code.
+const MONOSPACE_FONT: &str = "Courier New";
+
+/// Default font size, in half-points.
+pub(crate) const DEFAULT_FONT_SIZE: u16 = 22;
+
+/// Width of the text area tables are spread over, in twips (6.25in).
+const TEXT_WIDTH: u32 = 9000;
+
+/// Indentation of one list or quote level, in twips.
+const INDENT: u32 = 720;
+
+/// Twips per pixel, at 96 DPI.
+const TWIPS_PER_PIXEL: u32 = 15;
+
+/// Escapes `text` for an RTF text run.
+///
+/// `\`, `{` and `}` are escaped, tabs and line feeds become `\tab` and
+/// `\line`, and non-ASCII characters are written as `\uN?` (as UTF-16
+/// surrogate pairs outside the Basic Multilingual Plane). Other control
+/// characters are dropped.
+pub fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\t' => escaped.push_str("\\tab "),
+            '\n' => escaped.push_str("\\line "),
+            ' '..='~' => escaped.push(c),
+            c if c.is_control() => (),
+            c => {
+                let mut units = [0; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    // RTF arguments are signed 16-bit numbers.
+                    let _ = write!(escaped, "\\u{}?", *unit as i16);
+                }
+            }
+        }
+    }
+    escaped
+}
+
+/// RGB color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    /// Red component.
+    pub red: u8,
+    /// Green component.
+    pub green: u8,
+    /// Blue component.
+    pub blue: u8,
+}
+
+impl Color {
+    /// New instance of [Color].
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+}
+
+/// Stretch of text sharing the same formatting.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Run {
+    text: String,
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strike: bool,
+    code: bool,
+    font: Option<String>,
+    size: Option<u16>,
+    color: Option<Color>,
+    link: Option<String>,
+}
+
+impl Run {
+    /// Run of unformatted `text`.
+    pub fn new<S: Into<String>>(text: S) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Text of the run.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Bold text.
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Italic text.
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    /// Underlined text.
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    /// Struck-through text.
+    pub fn strike(mut self) -> Self {
+        self.strike = true;
+        self
+    }
+
+    /// Code, set in a monospace font.
+    pub fn code(mut self) -> Self {
+        self.code = true;
+        self
+    }
+
+    /// Text set in font `name`.
+    pub fn font<S: Into<String>>(mut self, name: S) -> Self {
+        self.font = Some(name.into());
+        self
+    }
+
+    /// Font size, in points.
+    pub fn size(mut self, points: u16) -> Self {
+        self.size = Some(points.saturating_mul(2));
+        self
+    }
+
+    /// Text color.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Hyperlink to `url`.
+    pub fn link<S: Into<String>>(mut self, url: S) -> Self {
+        self.link = Some(url.into());
+        self
+    }
+}
+
+/// Horizontal alignment of a paragraph.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Alignment {
+    /// Flush left.
+    #[default]
+    Left,
+    /// Centered.
+    Center,
+    /// Flush right.
+    Right,
+    /// Justified.
+    Justify,
+}
+
+/// Paragraph of runs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Paragraph {
+    runs: Vec<Run>,
+    heading: Option<u8>,
+    alignment: Alignment,
+    indent: u32,
+}
+
+impl Paragraph {
+    /// Empty paragraph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a run.
+    pub fn run(mut self, run: Run) -> Self {
+        self.runs.push(run);
+        self
+    }
+
+    /// Appends an unformatted run of `text`.
+    pub fn text<S: Into<String>>(self, text: S) -> Self {
+        self.run(Run::new(text))
+    }
+
+    /// Heading of `level` 1 to 6.
+    pub fn heading(mut self, level: u8) -> Self {
+        self.heading = Some(level.clamp(1, 6));
+        self
+    }
+
+    /// Horizontal alignment.
+    pub fn align(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Indents the paragraph by `levels` steps, e.g. for quotes.
+    pub fn indent(mut self, levels: u32) -> Self {
+        self.indent = levels;
+        self
+    }
+
+    /// Runs of the paragraph.
+    pub fn runs(&self) -> &[Run] {
+        &self.runs
+    }
+
+    /// Whether the paragraph has no text.
+    pub fn is_empty(&self) -> bool {
+        self.runs.iter().all(|run| run.text.is_empty())
+    }
+}
+
+/// Table row.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Row {
+    cells: Vec<Paragraph>,
+    header: bool,
+}
+
+impl Row {
+    /// Empty row.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a cell.
+    pub fn cell(mut self, cell: Paragraph) -> Self {
+        self.cells.push(cell);
+        self
+    }
+
+    /// Header row, repeated on each page and set in bold.
+    pub fn header(mut self) -> Self {
+        self.header = true;
+        self
+    }
+}
+
+/// Table of rows.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Table {
+    rows: Vec<Row>,
+}
+
+impl Table {
+    /// Empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a row.
+    pub fn row(mut self, row: Row) -> Self {
+        self.rows.push(row);
+        self
+    }
+}
+
+/// Item of a [List].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListItem {
+    paragraph: Paragraph,
+    level: u32,
+}
+
+/// Bulleted or numbered list.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct List {
+    items: Vec<ListItem>,
+    ordered: bool,
+}
+
+impl List {
+    /// Empty bulleted list.
+    pub fn bulleted() -> Self {
+        Self::default()
+    }
+
+    /// Empty numbered list.
+    pub fn numbered() -> Self {
+        Self {
+            ordered: true,
+            ..Self::default()
+        }
+    }
+
+    /// Appends a top-level item.
+    pub fn item(self, paragraph: Paragraph) -> Self {
+        self.nested_item(paragraph, 0)
+    }
+
+    /// Appends an item nested `level` levels deep.
+    pub fn nested_item(mut self, paragraph: Paragraph, level: u32) -> Self {
+        self.items.push(ListItem { paragraph, level });
+        self
+    }
+}
+
+/// Format of an embedded picture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// PNG (`\pngblip`).
+    Png,
+    /// JPEG (`\jpegblip`).
+    Jpeg,
+}
+
+/// Embedded picture.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    data: Vec<u8>,
+    format: ImageFormat,
+    width: u32,
+    height: u32,
+    /// Size the picture is shown at, in pixels.
+    display: (u32, u32),
+}
+
+impl Image {
+    /// Picture of `width` by `height` pixels.
+    pub fn new(data: Vec<u8>, format: ImageFormat, width: u32, height: u32) -> Self {
+        Self {
+            data,
+            format,
+            width,
+            height,
+            display: (width, height),
+        }
+    }
+
+    /// Size of the picture, in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Shows the picture at `width` by `height` pixels instead of its own size.
+    pub fn display_size(mut self, width: u32, height: u32) -> Self {
+        self.display = (width, height);
+        self
+    }
+
+    /// Picture from PNG or JPEG `data`, reading its size from the file header.
+    pub fn from_bytes(data: Vec<u8>) -> Option<Self> {
+        let (format, width, height) = if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            // Size is the first field of the IHDR chunk.
+            let width = u32::from_be_bytes(data.get(16..20)?.try_into().ok()?);
+            let height = u32::from_be_bytes(data.get(20..24)?.try_into().ok()?);
+            (ImageFormat::Png, width, height)
+        } else if data.starts_with(&[0xFF, 0xD8]) {
+            let (width, height) = jpeg_size(&data)?;
+            (ImageFormat::Jpeg, width, height)
+        } else {
+            return None;
+        };
+        Some(Self::new(data, format, width, height))
+    }
+}
+
+/// Size of a JPEG image, from its start-of-frame segment.
+fn jpeg_size(data: &[u8]) -> Option<(u32, u32)> {
+    let mut i = 2;
+    while i + 4 <= data.len() {
+        if data[i] != 0xFF {
+            return None;
+        }
+        let marker = data[i + 1];
+        let len = u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+        // SOF0 to SOF15, except DHT, JPG and DAC.
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            let height = u16::from_be_bytes([*data.get(i + 5)?, *data.get(i + 6)?]);
+            let width = u16::from_be_bytes([*data.get(i + 7)?, *data.get(i + 8)?]);
+            return Some((width.into(), height.into()));
+        }
+        i += 2 + len;
+    }
+    None
+}
+
+/// Top-level element of a document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Block {
+    /// Paragraph of text.
+    Paragraph(Paragraph),
+    /// Table.
+    Table(Table),
+    /// Bulleted or numbered list.
+    List(List),
+    /// Picture, in a paragraph of its own.
+    Image(Image),
+}
+
+/// Builder of an RTF document.
+#[derive(Clone, Debug)]
+pub struct RtfBuilder {
+    fonts: Vec<String>,
+    colors: Vec<Color>,
+    blocks: Vec<Block>,
+}
+
+impl Default for RtfBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RtfBuilder {
+    /// Empty document.
+    pub fn new() -> Self {
+        Self {
+            fonts: vec![DEFAULT_FONT.to_string()],
+            colors: Vec::new(),
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Appends a paragraph.
+    pub fn paragraph(self, paragraph: Paragraph) -> Self {
+        self.block(Block::Paragraph(paragraph))
+    }
+
+    /// Appends a table.
+    pub fn table(self, table: Table) -> Self {
+        self.block(Block::Table(table))
+    }
+
+    /// Appends a list.
+    pub fn list(self, list: List) -> Self {
+        self.block(Block::List(list))
+    }
+
+    /// Appends a picture.
+    pub fn image(self, image: Image) -> Self {
+        self.block(Block::Image(image))
+    }
+
+    /// Appends a block.
+    pub fn block(mut self, block: Block) -> Self {
+        self.push(block);
+        self
+    }
+
+    /// Appends a block in place.
+    pub fn push(&mut self, block: Block) {
+        self.blocks.push(block);
+    }
+
+    /// Blocks added so far.
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    fn font_index(&mut self, name: &str) -> usize {
+        match self.fonts.iter().position(|font| font == name) {
+            Some(index) => index,
+            None => {
+                self.fonts.push(name.to_string());
+                self.fonts.len() - 1
+            }
+        }
+    }
+
+    /// Index of `color` in the color table; index 0 is the automatic color.
+    fn color_index(&mut self, color: Color) -> usize {
+        match self.colors.iter().position(|c| *c == color) {
+            Some(index) => index + 1,
+            None => {
+                self.colors.push(color);
+                self.colors.len()
+            }
+        }
+    }
+
+    /// Renders the document.
+    pub fn build(mut self) -> String {
+        let blocks = std::mem::take(&mut self.blocks);
+        let mut body = String::new();
+        for block in &blocks {
+            match block {
+                Block::Paragraph(paragraph) => {
+                    self.write_paragraph(&mut body, paragraph, "");
+                    body.push_str("\\par\n");
+                }
+                Block::Table(table) => self.write_table(&mut body, table),
+                Block::List(list) => self.write_list(&mut body, list),
+                Block::Image(image) => {
+                    body.push_str("\\pard\\plain ");
+                    write_image(&mut body, image);
+                    body.push_str("\\par\n");
+                }
+            }
+        }
+
+        let mut rtf = String::with_capacity(body.len() + 256);
+        rtf.push_str("{\\rtf1\\ansi\\ansicpg1252\\deff0\\uc1\n{\\fonttbl");
+        for (index, font) in self.fonts.iter().enumerate() {
+            let family = if font == MONOSPACE_FONT { "fmodern" } else { "fswiss" };
+            let _ = write!(rtf, "{{\\f{index}\\{family}\\fcharset0 {};}}", escape_text(font));
+        }
+        rtf.push_str("}\n");
+        if !self.colors.is_empty() {
+            rtf.push_str("{\\colortbl;");
+            for color in &self.colors {
+                let _ = write!(
+                    rtf,
+                    "\\red{}\\green{}\\blue{};",
+                    color.red, color.green, color.blue
+                );
+            }
+            rtf.push_str("}\n");
+        }
+        rtf.push_str(&body);
+        rtf.push('}');
+        rtf
+    }
+
+    /// Writes the properties and runs of `paragraph`, without ending it.
+    fn write_paragraph(&mut self, out: &mut String, paragraph: &Paragraph, prefix: &str) {
+        out.push_str("\\pard\\plain ");
+        out.push_str(prefix);
+        if paragraph.indent > 0 {
+            let _ = write!(out, "\\li{}", paragraph.indent * INDENT);
+        }
+        out.push_str(match paragraph.alignment {
+            Alignment::Left => "\\ql",
+            Alignment::Center => "\\qc",
+            Alignment::Right => "\\qr",
+            Alignment::Justify => "\\qj",
+        });
+        if let Some(level) = paragraph.heading {
+            let _ = write!(out, "\\outlinelevel{}", level - 1);
+        }
+        let _ = write!(out, "\\f0\\fs{DEFAULT_FONT_SIZE} ");
+
+        // Headings are bold, shrinking from 20pt (level 1) to 11pt (level 6).
+        let heading = paragraph.heading.map(|level| match level {
+            1 => 40,
+            2 => 32,
+            3 => 28,
+            4 => 26,
+            5 => 24,
+            _ => DEFAULT_FONT_SIZE,
+        });
+        for run in &paragraph.runs {
+            self.write_run(out, run, heading);
+        }
+    }
+
+    fn write_run(&mut self, out: &mut String, run: &Run, heading: Option<u16>) {
+        if run.text.is_empty() {
+            return;
+        }
+        let mut props = String::new();
+        if run.bold || heading.is_some() {
+            props.push_str("\\b");
+        }
+        if run.italic {
+            props.push_str("\\i");
+        }
+        if run.underline || run.link.is_some() {
+            props.push_str("\\ul");
+        }
+        if run.strike {
+            props.push_str("\\strike");
+        }
+        let font = match (&run.font, run.code) {
+            (_, true) => Some(self.font_index(MONOSPACE_FONT)),
+            (Some(font), false) => Some(self.font_index(font)),
+            (None, false) => None,
+        };
+        if let Some(font) = font {
+            let _ = write!(props, "\\f{font}");
+        }
+        if let Some(size) = run.size.or(heading) {
+            let _ = write!(props, "\\fs{size}");
+        }
+        if let Some(color) = run.color {
+            let _ = write!(props, "\\cf{}", self.color_index(color));
+        }
+
+        let text = if props.is_empty() {
+            escape_text(&run.text)
+        } else {
+            format!("{{{props} {}}}", escape_text(&run.text))
+        };
+        match &run.link {
+            Some(url) => {
+                let _ = write!(
+                    out,
+                    "{{\\field{{\\*\\fldinst HYPERLINK \"{}\"}}{{\\fldrslt {text}}}}}",
+                    escape_text(&url.replace('"', "%22"))
+                );
+            }
+            None => out.push_str(&text),
+        }
+    }
+
+    fn write_table(&mut self, out: &mut String, table: &Table) {
+        let columns = table.rows.iter().map(|row| row.cells.len()).max().unwrap_or(0);
+        if columns == 0 {
+            return;
+        }
+        let width = TEXT_WIDTH / columns as u32;
+        for row in &table.rows {
+            out.push_str("\\trowd\\trgaph108\\trleft0");
+            if row.header {
+                out.push_str("\\trhdr");
+            }
+            for column in 1..=columns {
+                for side in ["t", "l", "b", "r"] {
+                    let _ = write!(out, "\\clbrdr{side}\\brdrs\\brdrw10");
+                }
+                let _ = write!(out, "\\cellx{}", width * column as u32);
+            }
+            out.push('\n');
+            for column in 0..columns {
+                let cell = row.cells.get(column).cloned().unwrap_or_default();
+                let cell = if row.header {
+                    Paragraph {
+                        runs: cell.runs.into_iter().map(Run::bold).collect(),
+                        ..cell
+                    }
+                } else {
+                    cell
+                };
+                self.write_paragraph(out, &cell, "\\intbl");
+                out.push_str("\\cell\n");
+            }
+            out.push_str("\\row\n");
+        }
+        out.push_str("\\pard\n");
+    }
+
+    fn write_list(&mut self, out: &mut String, list: &List) {
+        // Numbering restarts whenever a list returns to a shallower level.
+        let mut counters: Vec<u32> = Vec::new();
+        for item in &list.items {
+            let level = item.level as usize;
+            counters.truncate(level + 1);
+            counters.resize(level + 1, 0);
+            counters[level] += 1;
+
+            let (marker, numbering) = if list.ordered {
+                (
+                    format!("{}.", counters[level]),
+                    format!("\\pndec\\pnstart{}{{\\pntxta .}}", counters[level]),
+                )
+            } else {
+                (
+                    "\\bullet".to_string(),
+                    "\\pnlvlblt{\\pntxtb\\bullet}".to_string(),
+                )
+            };
+            let indent = INDENT * (item.level + 1);
+            let prefix = format!(
+                "{{\\pntext {marker}\\tab}}{{\\*\\pn\\pnlvlbody\\pnindent360{numbering}}}\\fi-360\\li{indent}"
+            );
+            let paragraph = Paragraph {
+                indent: 0,
+                ..item.paragraph.clone()
+            };
+            self.write_paragraph(out, &paragraph, &prefix);
+            out.push_str("\\par\n");
+        }
+    }
+}
+
+pub(crate) fn write_image(out: &mut String, image: &Image) {
+    let blip = match image.format {
+        ImageFormat::Png => "\\pngblip",
+        ImageFormat::Jpeg => "\\jpegblip",
+    };
+    let _ = write!(out, "{{\\pict{blip}\\picw{}\\pich{}", image.width, image.height);
+    // Readers show the picture at its own size without a goal.
+    let twips = |pixels: u32| pixels.checked_mul(TWIPS_PER_PIXEL).filter(|&twips| twips <= i32::MAX as u32);
+    if let (Some(width), Some(height)) = (twips(image.display.0), twips(image.display.1)) {
+        let _ = write!(out, "\\picwgoal{width}\\pichgoal{height}");
+    }
+    out.push('\n');
+    for line in image.data.chunks(64) {
+        for byte in line {
+            let _ = write!(out, "{byte:02x}");
+        }
+        out.push('\n');
+    }
+    out.push('}');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtf::{options::ConvertOptions, to_text};
+
+    fn plaintext(rtf: &str) -> String {
+        let options = ConvertOptions::default();
+        let tokens = to_text::tokenize(rtf.as_bytes(), &options.limits).unwrap();
+        let mut out = Vec::new();
+        let diagnostics = to_text::write_plaintext(&tokens, &mut out, &options).unwrap();
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(escape_text(r"a\b{c}"), r"a\\b\{c\}");
+        assert_eq!(escape_text("caf\u{e9}\ttab"), "caf\\u233?\\tab tab");
+        assert_eq!(escape_text("\u{4e2d}"), "\\u20013?");
+        assert_eq!(escape_text("\u{1F600}"), "\\u-10179?\\u-8704?");
+        assert_eq!(escape_text("\u{7}"), "");
+    }
+
+    #[test]
+    fn test_build_round_trips() {
+        let rtf = RtfBuilder::new()
+            .paragraph(Paragraph::new().heading(1).text("Title {1}"))
+            .paragraph(
+                Paragraph::new()
+                    .text("Plain, ")
+                    .run(Run::new("bold").bold())
+                    .text(" and ")
+                    .run(Run::new("red \u{5e9}\u{1F600}").color(Color::new(255, 0, 0))),
+            )
+            .list(List::numbered().item(Paragraph::new().text("one")))
+            .table(
+                Table::new()
+                    .row(Row::new().header().cell(Paragraph::new().text("a")).cell(Paragraph::new().text("b")))
+                    .row(Row::new().cell(Paragraph::new().text("c"))),
+            )
+            .build();
+
+        assert!(rtf.starts_with("{\\rtf1\\ansi"));
+        assert!(rtf.contains("{\\colortbl;\\red255\\green0\\blue0;}"));
+        assert_eq!(
+            plaintext(&rtf),
+            "Title {1}\nPlain, bold and red \u{5e9}\u{1F600}\n1.\tone\na\tb\t\n c\t\t\n "
+        );
+    }
+
+    #[test]
+    fn test_image_from_bytes() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend(2u32.to_be_bytes());
+        png.extend(3u32.to_be_bytes());
+        let image = Image::from_bytes(png).unwrap();
+        assert_eq!((image.format, image.width, image.height), (ImageFormat::Png, 2, 3));
+
+        let jpeg = vec![
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x0B, 0x08, 0x00,
+            0x05, 0x00, 0x07,
+        ];
+        let image = Image::from_bytes(jpeg).unwrap();
+        assert_eq!((image.format, image.width, image.height), (ImageFormat::Jpeg, 7, 5));
+        assert!(Image::from_bytes(b"GIF89a".to_vec()).is_none());
+    }
+
+    #[test]
+    fn test_write_image_goal() {
+        let image = Image::new(vec![0xAB], ImageFormat::Png, 2, 3);
+        let mut out = String::new();
+        write_image(&mut out, &image.clone().display_size(4, 6));
+        assert_eq!(out, "{\\pict\\pngblip\\picw2\\pich3\\picwgoal60\\pichgoal90\nab\n}");
+
+        let mut out = String::new();
+        write_image(&mut out, &image.display_size(u32::MAX, 6));
+        assert_eq!(out, "{\\pict\\pngblip\\picw2\\pich3\nab\n}");
+    }
+}
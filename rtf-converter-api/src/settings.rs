@@ -68,12 +68,176 @@ impl std::fmt::Debug for Otel {
     }
 }
 
+/// Resource limits applied to every RTF conversion.
+///
+/// These bound the work a single (possibly hostile) document can cause.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct Limits {
+    /// Maximum size of an input document in bytes.
+    pub max_input_bytes: usize,
+    /// Maximum group nesting depth.
+    pub max_group_depth: usize,
+    /// Maximum number of bytes written across all destinations.
+    pub max_output_bytes: usize,
+    /// Maximum number of `\pict` images.
+    pub max_images: usize,
+    /// Maximum number of control words and symbols.
+    pub max_control_words: usize,
+    /// Wall-clock budget of a single conversion in milliseconds.
+    pub max_duration_ms: u64,
+    /// Maximum number of pages laid out for PDF output.
+    pub max_pages: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_input_bytes: 50 * 1024 * 1024,
+            max_group_depth: 256,
+            max_output_bytes: 100 * 1024 * 1024,
+            max_images: 1_000,
+            max_control_words: 10_000_000,
+            max_duration_ms: 10_000,
+            max_pages: 10_000,
+        }
+    }
+}
+
+impl Limits {
+    /// Convert `max_duration_ms` to [Duration].
+    pub fn max_duration(&self) -> Duration {
+        Duration::from_millis(self.max_duration_ms)
+    }
+}
+
+/// Purge of the files past their `max_age`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct Retention {
+    /// Whether the server runs the purge.
+    pub enabled: bool,
+    /// Interval between purges in seconds.
+    pub interval_secs: u64,
+    /// Time in seconds expired files answer `410 Gone` before being purged.
+    pub grace_period_secs: u64,
+    /// Number of files deleted per transaction.
+    pub batch_size: i64,
+    /// Maximum number of batches per purge; the rest waits for the next one.
+    pub max_batches: u32,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 3_600,
+            grace_period_secs: 7 * 24 * 3_600,
+            batch_size: 500,
+            max_batches: 100,
+        }
+    }
+}
+
+impl Retention {
+    /// Convert `interval_secs` to [Duration].
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+
+    /// Convert `grace_period_secs` to [Duration].
+    pub fn grace_period(&self) -> Duration {
+        Duration::from_secs(self.grace_period_secs)
+    }
+}
+
+/// Asynchronous conversion jobs.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct Jobs {
+    /// Whether the server runs conversion workers, besides the
+    /// `rtf-converter-worker` binary.
+    pub run_in_server: bool,
+    /// Maximum number of conversions run at once by a process.
+    pub concurrency: usize,
+    /// Attempts of a conversion timing out or interrupted before it is
+    /// dead-lettered.
+    pub max_attempts: i32,
+    /// Delay before the first retry in milliseconds, doubled on each retry.
+    pub retry_backoff_ms: u64,
+    /// Default, and maximum, time budget of an attempt in milliseconds.
+    pub timeout_ms: u64,
+    /// Interval between the sweeps dead-lettering the jobs whose worker
+    /// stopped during their last attempt, in milliseconds; such jobs are
+    /// swept once past their time budget by this interval.
+    pub sweep_interval_ms: u64,
+}
+
+impl Default for Jobs {
+    fn default() -> Self {
+        Self {
+            run_in_server: true,
+            concurrency: 4,
+            max_attempts: 3,
+            retry_backoff_ms: 1_000,
+            timeout_ms: 120_000,
+            sweep_interval_ms: 60_000,
+        }
+    }
+}
+
+impl Jobs {
+    /// Convert `retry_backoff_ms` to [Duration].
+    pub fn retry_backoff(&self) -> Duration {
+        Duration::from_millis(self.retry_backoff_ms)
+    }
+
+    /// Convert `sweep_interval_ms` to [Duration].
+    pub fn sweep_interval(&self) -> Duration {
+        Duration::from_millis(self.sweep_interval_ms)
+    }
+}
+
+/// Webhook callbacks of conversion jobs.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct Webhooks {
+    /// Client of the callbacks; transient failures are retried.
+    pub http_client: HttpClient,
+    /// Largest result embedded in a callback, when its job asks for it.
+    pub max_output_bytes: usize,
+    /// Whether callbacks may target addresses which are not public
+    /// (loopback, private, link-local, ...), for local development.
+    pub allow_private_hosts: bool,
+}
+
+impl Default for Webhooks {
+    fn default() -> Self {
+        Self {
+            http_client: HttpClient {
+                timeout_ms: 10_000,
+                ..HttpClient::default()
+            },
+            max_output_bytes: 10 * 1024 * 1024,
+            allow_private_hosts: false,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 /// Application settings.
 pub struct Settings {
     monitoring: Monitoring,
     server: Server,
     otel: Otel,
+    #[serde(default)]
+    limits: Limits,
+    #[serde(default)]
+    jobs: Jobs,
+    #[serde(default)]
+    retention: Retention,
+    #[serde(default)]
+    webhooks: Webhooks,
 }
 
 impl Settings {
@@ -96,6 +260,26 @@ impl Settings {
     pub fn server(&self) -> &Server {
         &self.server
     }
+
+    /// Conversion limits getter.
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    /// Conversion jobs settings getter.
+    pub fn jobs(&self) -> &Jobs {
+        &self.jobs
+    }
+
+    /// Retention settings getter.
+    pub fn retention(&self) -> &Retention {
+        &self.retention
+    }
+
+    /// Webhooks settings getter.
+    pub fn webhooks(&self) -> &Webhooks {
+        &self.webhooks
+    }
 }
 
 impl Settings {
@@ -242,4 +426,13 @@ mod tests {
         assert_eq!(settings.http_client.retry_options.count, 1);
         assert_eq!(settings.http_client.timeout_ms, 10_000);
     }
+
+    #[test]
+    fn test_limits_partial_overrides() {
+        let limits: Limits = serde_json::from_str(r#"{"max_group_depth": 8}"#).unwrap();
+
+        assert_eq!(limits.max_group_depth, 8);
+        assert_eq!(limits.max_input_bytes, Limits::default().max_input_bytes);
+        assert_eq!(limits.max_duration_ms, Limits::default().max_duration_ms);
+    }
 }
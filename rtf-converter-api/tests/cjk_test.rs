@@ -0,0 +1,38 @@
+use std::{fs, path::PathBuf};
+
+use rtf_converter::rtf::{options::ConvertOptions, to_text};
+
+fn fixtures() -> Vec<PathBuf> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cjk");
+    let mut fixtures: Vec<PathBuf> = fs::read_dir(dir)
+        .expect("fixture directory")
+        .map(|entry| entry.expect("fixture").path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "rtf"))
+        .collect();
+    fixtures.sort();
+    fixtures
+}
+
+/// Each `.rtf` fixture converts to the text of the `.txt` file next to it.
+#[test]
+fn test_cjk_fixtures() {
+    let options = ConvertOptions::default();
+    let fixtures = fixtures();
+    assert!(!fixtures.is_empty());
+    for fixture in fixtures {
+        let rtf = fs::read(&fixture).unwrap();
+        let expected = fs::read_to_string(fixture.with_extension("txt")).unwrap();
+
+        let tokens = to_text::tokenize(rtf.as_slice(), &options.limits).unwrap();
+        let mut text = Vec::new();
+        let diagnostics = to_text::write_plaintext(&tokens, &mut text, &options).unwrap();
+
+        assert_eq!(
+            String::from_utf8(text).unwrap(),
+            expected,
+            "{}",
+            fixture.display()
+        );
+        assert!(diagnostics.is_empty(), "{}: {diagnostics:?}", fixture.display());
+    }
+}
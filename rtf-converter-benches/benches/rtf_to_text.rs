@@ -0,0 +1,55 @@
+use std::{fs, path::PathBuf};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rtf_converter::{rtf::{options::ConvertOptions, to_text}, settings::Limits};
+
+/// Every `.rtf` file in `corpus/`, sorted by name.
+fn corpus() -> Vec<(String, Vec<u8>)> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("corpus");
+    let mut files: Vec<(String, Vec<u8>)> = fs::read_dir(dir)
+        .expect("corpus directory")
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rtf"))
+        .map(|path| {
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            (name, fs::read(&path).expect("corpus file"))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// A document made of `count` groups nested `depth` deep, each setting a few
+/// properties, which stresses per-group state copies.
+fn nested_groups(count: usize, depth: usize) -> Vec<u8> {
+    let mut rtf = br"{\rtf1\ansi\ansicpg1252\deff0\deflang1033 ".to_vec();
+    for _ in 0..count {
+        for level in 0..depth {
+            rtf.extend(format!(r"{{\rtlch\fcs1\af0\ltrch\fcs0\f0\fs{}\insrsid{} ", 20 + level, level).as_bytes());
+        }
+        rtf.extend(b"text\\par ");
+        rtf.extend(std::iter::repeat_n(b'}', depth));
+    }
+    rtf.push(b'}');
+    rtf
+}
+
+pub fn to_text_benchmark(c: &mut Criterion) {
+    let options = ConvertOptions::new(Limits::default());
+    let mut documents = corpus();
+    documents.push(("nested_groups".to_string(), nested_groups(2_000, 16)));
+
+    let mut group = c.benchmark_group("write_plaintext");
+    for (name, data) in &documents {
+        let tokens = to_text::tokenize(data.as_slice(), &options.limits).expect("valid RTF");
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &tokens, |b, tokens| {
+            b.iter(|| to_text::write_plaintext(tokens, std::io::sink(), &options).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, to_text_benchmark);
+criterion_main!(benches);